@@ -0,0 +1,641 @@
+//! A bounding volume hierarchy over a triangle mesh for fast ray casts and overlap queries,
+//! e.g. for picking against meshes with many triangles.
+//!
+//! # Example
+//! ```
+//! use js_linalg::{Ray, Vector3};
+//! use js_linalg::bvh::Bvh;
+//! let vertices = [
+//!     Vector3::from_i32(0, 0, 0), Vector3::from_i32(1, 0, 0),
+//!     Vector3::from_i32(1, 1, 0), Vector3::from_i32(0, 1, 0),
+//! ];
+//! let indices = [[0, 1, 2], [0, 2, 3]];
+//! let bvh = Bvh::build(&vertices, &indices);
+//!
+//! let ray = Ray::new(&Vector3::from(0.25, 0.75, 1.), &Vector3::from_i32(0, 0, -1));
+//! let hit = bvh.closest_hit(&ray, false).unwrap();
+//! assert_eq!(hit.triangle, 1);
+//! assert_eq!(hit.hit.hit.distance, 1.);
+//! ```
+
+use super::{Matrix3, Matrix4, Ray, TriangleHit, Vector3};
+use super::geometry::{Aabb, Triangle};
+
+/// The number of bins candidate splits are evaluated at per axis.
+const BINS: usize = 12;
+
+/// Nodes with at most this many triangles are never split.
+const MIN_LEAF_SIZE: u32 = 2;
+
+/// Limits the depth so that traversal works with a fixed-size stack.
+const MAX_DEPTH: usize = 48;
+
+#[derive(Debug, Copy, Clone)]
+struct Node {
+    bounds: Aabb,
+
+    /// The index of the left child (the right one follows it) for inner nodes, the index of the
+    /// first triangle in `Bvh::order` for leaves.
+    first: u32,
+
+    /// The number of triangles for leaves, zero for inner nodes.
+    count: u32,
+}
+
+/// A bounding volume hierarchy over a triangle mesh given by vertex positions and index triples,
+/// built with the surface area heuristic (SAH). The hierarchy keeps a copy of the mesh.
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    vertices: Vec<Vector3>,
+    indices: Vec<[u32; 3]>,
+    nodes: Vec<Node>,
+
+    /// The triangle indices in leaf order.
+    order: Vec<u32>,
+}
+
+/// The result of a ray cast against a `Bvh`.
+#[derive(Debug, Copy, Clone)]
+pub struct BvhHit {
+    /// The index of the hit triangle, i.e. the position of its index triple.
+    pub triangle: usize,
+
+    pub hit: TriangleHit,
+}
+
+impl Bvh {
+
+    /// Builds the hierarchy over the triangles given by `indices`, each an index triple into
+    /// `vertices`. Panics if an index is out of bounds.
+    pub fn build(vertices:&[Vector3], indices:&[[u32; 3]]) -> Bvh {
+        let mut bvh = Bvh {
+            vertices: vertices.to_vec(),
+            indices: indices.to_vec(),
+            nodes: Vec::with_capacity(2 * indices.len().max(1)),
+            order: (0..indices.len() as u32).collect(),
+        };
+
+        let centroids: Vec<Vector3> = (0..indices.len()).map(|i| bvh.triangle(i).centroid()).collect();
+        bvh.nodes.push(Node {bounds: Aabb::empty(), first: 0, count: indices.len() as u32});
+        bvh.subdivide(0, &centroids, 0);
+        bvh
+    }
+
+    /// Returns the triangle with the provided index.
+    pub fn triangle(&self, index:usize) -> Triangle {
+        let [a, b, c] = self.indices[index];
+        Triangle {
+            a: self.vertices[a as usize],
+            b: self.vertices[b as usize],
+            c: self.vertices[c as usize],
+        }
+    }
+
+    /// The vertex positions of the mesh.
+    pub fn vertices(&self) -> &[Vector3] {
+        &self.vertices
+    }
+
+    /// The index triples of the mesh.
+    pub fn indices(&self) -> &[[u32; 3]] {
+        &self.indices
+    }
+
+    /// The box enclosing the whole mesh, empty for a mesh without triangles.
+    pub fn bounds(&self) -> Aabb {
+        self.nodes[0].bounds
+    }
+
+    /// The number of nodes in the hierarchy.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn leaf_bounds(&self, node:&Node) -> Aabb {
+        let mut bounds = Aabb::empty();
+        for &t in &self.order[node.first as usize..(node.first + node.count) as usize] {
+            bounds.merge_mut(&self.triangle(t as usize).to_aabb());
+        }
+        bounds
+    }
+
+    fn subdivide(&mut self, index:usize, centroids:&[Vector3], depth:usize) {
+        let node = self.nodes[index];
+        self.nodes[index].bounds = self.leaf_bounds(&node);
+
+        if node.count <= MIN_LEAF_SIZE || depth >= MAX_DEPTH {
+            return;
+        }
+
+        let range = node.first as usize..(node.first + node.count) as usize;
+        let mut centroid_bounds = Aabb::empty();
+        for &t in &self.order[range.clone()] {
+            centroid_bounds.expand_mut(&centroids[t as usize]);
+        }
+
+        // binned SAH: try the borders between bins on every axis, keep the cheapest split
+        let bin_of = |axis:usize, c:&Vector3| {
+            let min = centroid_bounds.min.as_array()[axis];
+            let extent = centroid_bounds.max.as_array()[axis] - min;
+            (((c.as_array()[axis] - min) / extent * BINS as f32) as usize).min(BINS - 1)
+        };
+
+        let mut best:Option<(f32, usize, usize)> = None;
+        for axis in 0..3 {
+            if centroid_bounds.max.as_array()[axis] <= centroid_bounds.min.as_array()[axis] {
+                continue;
+            }
+
+            let mut bins = [(Aabb::empty(), 0u32); BINS];
+            for &t in &self.order[range.clone()] {
+                let bin = &mut bins[bin_of(axis, &centroids[t as usize])];
+                bin.0.merge_mut(&self.triangle(t as usize).to_aabb());
+                bin.1 += 1;
+            }
+
+            // sweep from the right to know the cost of each right side, then from the left
+            let mut right_costs = [0.0; BINS];
+            let mut bounds = Aabb::empty();
+            let mut count = 0;
+            for i in (1..BINS).rev() {
+                bounds.merge_mut(&bins[i].0);
+                count += bins[i].1;
+                right_costs[i] = if count > 0 { count as f32 * bounds.surface_area() } else { 0.0 };
+            }
+
+            let mut bounds = Aabb::empty();
+            let mut count = 0;
+            for i in 0..BINS - 1 {
+                bounds.merge_mut(&bins[i].0);
+                count += bins[i].1;
+                let left_cost = if count > 0 { count as f32 * bounds.surface_area() } else { 0.0 };
+                let cost = left_cost + right_costs[i + 1];
+                if count > 0 && count < node.count && best.iter().all(|&(c, _, _)| cost < c) {
+                    best = Some((cost, axis, i + 1));
+                }
+            }
+        }
+
+        // all centroids in one spot, no split possible
+        let (cost, axis, split) = match best {
+            Some(b) => b,
+            None => return,
+        };
+        let leaf_cost = node.count as f32 * self.nodes[index].bounds.surface_area();
+        if cost >= leaf_cost && node.count <= 8 {
+            return;
+        }
+
+        // partition the triangles of this node by the chosen split
+        let mut i = range.start;
+        let mut j = range.end;
+        while i < j {
+            if bin_of(axis, &centroids[self.order[i] as usize]) < split {
+                i += 1;
+            } else {
+                j -= 1;
+                self.order.swap(i, j);
+            }
+        }
+        let left_count = (i - range.start) as u32;
+
+        let left = self.nodes.len();
+        self.nodes.push(Node {bounds: Aabb::empty(), first: node.first, count: left_count});
+        self.nodes.push(Node {bounds: Aabb::empty(), first: node.first + left_count, count: node.count - left_count});
+        self.nodes[index].first = left as u32;
+        self.nodes[index].count = 0;
+
+        self.subdivide(left, centroids, depth + 1);
+        self.subdivide(left + 1, centroids, depth + 1);
+    }
+
+    /// Copies the new vertex positions and updates the bounds of all nodes without changing the
+    /// structure of the hierarchy, e.g. after vertex animation. This is much faster than building
+    /// the hierarchy again, but queries get slower the more the triangles moved relative to each
+    /// other. Panics if the number of vertices differs from the one the hierarchy was built with.
+    pub fn refit(&mut self, vertices:&[Vector3]) -> &mut Bvh {
+        assert_eq!(vertices.len(), self.vertices.len());
+        self.vertices.copy_from_slice(vertices);
+
+        // children are always stored behind their parent
+        for i in (0..self.nodes.len()).rev() {
+            let node = self.nodes[i];
+            self.nodes[i].bounds = if node.count > 0 || self.indices.is_empty() {
+                self.leaf_bounds(&node)
+            } else {
+                let left = node.first as usize;
+                self.nodes[left].bounds.merge(&self.nodes[left + 1].bounds)
+            };
+        }
+
+        self
+    }
+
+    /// Returns the closest triangle hit by the provided ray, see `Ray::intersect_triangle`.
+    pub fn closest_hit(&self, ray:&Ray, cull_backfaces:bool) -> Option<BvhHit> {
+        self.closest_hit_wound(ray, cull_backfaces, false)
+    }
+
+    fn closest_hit_wound(&self, ray:&Ray, cull_backfaces:bool, mirrored:bool) -> Option<BvhHit> {
+        let mut best:Option<BvhHit> = None;
+        self.traverse(ray, |t| {
+            if let Some(hit) = self.intersect(ray, t, cull_backfaces, mirrored) {
+                if best.iter().all(|b| hit.hit.distance < b.hit.hit.distance) {
+                    best = Some(BvhHit {triangle: t, hit});
+                }
+            }
+            best.map(|b| b.hit.hit.distance)
+        });
+        best
+    }
+
+    /// Returns `true` if the provided ray hits any triangle closer than `max_distance`, e.g. for
+    /// shadow rays. This stops at the first hit found, which is cheaper than `closest_hit`.
+    pub fn any_hit(&self, ray:&Ray, max_distance:f32, cull_backfaces:bool) -> bool {
+        self.any_hit_wound(ray, max_distance, cull_backfaces, false)
+    }
+
+    fn any_hit_wound(&self, ray:&Ray, max_distance:f32, cull_backfaces:bool, mirrored:bool) -> bool {
+        let mut found = false;
+        self.traverse(ray, |t| {
+            if let Some(hit) = self.intersect(ray, t, cull_backfaces, mirrored) {
+                if hit.hit.distance <= max_distance {
+                    found = true;
+                    return Some(-1.0);
+                }
+            }
+            Some(max_distance)
+        });
+        found
+    }
+
+    /// Intersects a single triangle. `mirrored` turns its winding around, so that front faces
+    /// of a mesh seen through a mirroring model matrix are still the ones that are kept.
+    fn intersect(&self, ray:&Ray, t:usize, cull_backfaces:bool, mirrored:bool) -> Option<TriangleHit> {
+        let triangle = self.triangle(t);
+        if !mirrored {
+            return ray.intersect_triangle(&triangle, cull_backfaces);
+        }
+
+        let swapped = Triangle {a: triangle.a, b: triangle.c, c: triangle.b};
+        let mut hit = ray.intersect_triangle(&swapped, cull_backfaces)?;
+        hit.barycentric = Vector3::from(hit.barycentric.x, hit.barycentric.z, hit.barycentric.y);
+        Some(hit)
+    }
+
+    /// Visits the leaves hit by the ray front to back. `visit` is called with each triangle and
+    /// returns the distance beyond which nodes are skipped.
+    fn traverse<F:FnMut(usize) -> Option<f32>>(&self, ray:&Ray, mut visit:F) {
+        if self.indices.is_empty() {
+            return;
+        }
+
+        let inv = Vector3::from(1.0 / ray.direction.x, 1.0 / ray.direction.y, 1.0 / ray.direction.z);
+        let mut limit = f32::INFINITY;
+        let mut stack = [0u32; MAX_DEPTH + 2];
+        let mut size = 0;
+
+        if slab(ray, &inv, &self.nodes[0].bounds, limit).is_none() {
+            return;
+        }
+        stack[size] = 0;
+        size += 1;
+
+        while size > 0 {
+            size -= 1;
+            let node = &self.nodes[stack[size] as usize];
+            if slab(ray, &inv, &node.bounds, limit).is_none() {
+                continue;
+            }
+
+            if node.count > 0 {
+                for &t in &self.order[node.first as usize..(node.first + node.count) as usize] {
+                    if let Some(l) = visit(t as usize) {
+                        if l < 0.0 {
+                            return;
+                        }
+                        limit = limit.min(l);
+                    }
+                }
+                continue;
+            }
+
+            // push the far child first so the near one is visited next
+            let left = node.first;
+            let right = left + 1;
+            let dl = slab(ray, &inv, &self.nodes[left as usize].bounds, limit);
+            let dr = slab(ray, &inv, &self.nodes[right as usize].bounds, limit);
+            match (dl, dr) {
+                (Some(l), Some(r)) => {
+                    let (near, far) = if l <= r { (left, right) } else { (right, left) };
+                    stack[size] = far;
+                    stack[size + 1] = near;
+                    size += 2;
+                },
+                (Some(_), None) => { stack[size] = left; size += 1; },
+                (None, Some(_)) => { stack[size] = right; size += 1; },
+                (None, None) => {},
+            }
+        }
+    }
+
+    /// Calls `visit` with every triangle whose bounds overlap the provided box. The test is
+    /// conservative, the caller may test the triangles exactly.
+    pub fn query_aabb<F:FnMut(usize)>(&self, aabb:&Aabb, mut visit:F) {
+        if self.indices.is_empty() {
+            return;
+        }
+
+        let mut stack = [0u32; MAX_DEPTH + 2];
+        let mut size = 1;
+
+        while size > 0 {
+            size -= 1;
+            let node = &self.nodes[stack[size] as usize];
+            if !node.bounds.intersects_aabb(aabb) {
+                continue;
+            }
+
+            if node.count > 0 {
+                for &t in &self.order[node.first as usize..(node.first + node.count) as usize] {
+                    if self.triangle(t as usize).to_aabb().intersects_aabb(aabb) {
+                        visit(t as usize);
+                    }
+                }
+            } else {
+                stack[size] = node.first;
+                stack[size + 1] = node.first + 1;
+                size += 2;
+            }
+        }
+    }
+
+    /// Like `closest_hit`, but for the mesh transformed by the provided model matrix, without
+    /// rebuilding the hierarchy. The ray is brought into the space of the mesh via the inverse
+    /// model matrix, so the distance of the hit is measured in units of the world-space direction.
+    /// The point and normal of the hit are in world space. A mirroring model matrix turns the
+    /// winding around, which culling and the normal take into account. Returns `None` if the
+    /// matrix cannot be inverted.
+    pub fn closest_hit_transformed(&self, ray:&Ray, model:&Matrix4, cull_backfaces:bool) -> Option<BvhHit> {
+        let inverse = model.try_inverse().ok()?;
+        let mut local = *ray;
+        local.transform_mut(&inverse);
+
+        // mirroring models turn the winding around
+        let mirrored = model.determinant() < 0.0;
+        let mut result = self.closest_hit_wound(&local, cull_backfaces, mirrored)?;
+        let hit = &mut result.hit.hit;
+        model.mult_to_vector3(&mut hit.point, 1.0);
+        Matrix3::normal_matrix(model).mult_to_vector3(&mut hit.normal);
+        hit.normal.normalize_mut();
+        Some(result)
+    }
+
+    /// Like `any_hit`, but for the mesh transformed by the provided model matrix, taking a
+    /// mirroring matrix into account when culling. Returns `false` if the matrix cannot be inverted.
+    pub fn any_hit_transformed(&self, ray:&Ray, model:&Matrix4, max_distance:f32, cull_backfaces:bool) -> bool {
+        let inverse = match model.try_inverse() {
+            Ok(i) => i,
+            Err(_) => return false,
+        };
+        let mut local = *ray;
+        local.transform_mut(&inverse);
+        self.any_hit_wound(&local, max_distance, cull_backfaces, model.determinant() < 0.0)
+    }
+
+    /// Like `query_aabb`, but for the mesh transformed by the provided model matrix. The box is
+    /// brought into the space of the mesh, where it grows for rotations, so the test gets more
+    /// conservative. Visits nothing if the matrix cannot be inverted.
+    pub fn query_aabb_transformed<F:FnMut(usize)>(&self, aabb:&Aabb, model:&Matrix4, visit:F) {
+        if let Ok(inverse) = model.try_inverse() {
+            let mut local = *aabb;
+            local.transform_mut(&inverse);
+            self.query_aabb(&local, visit);
+        }
+    }
+}
+
+/// The distance at which the ray enters the box (zero if it starts inside), if before `limit`.
+fn slab(ray:&Ray, inv:&Vector3, aabb:&Aabb, limit:f32) -> Option<f32> {
+    let (x0, x1) = slab_axis(ray.origin.x, inv.x, aabb.min.x, aabb.max.x);
+    let (y0, y1) = slab_axis(ray.origin.y, inv.y, aabb.min.y, aabb.max.y);
+    let (z0, z1) = slab_axis(ray.origin.z, inv.z, aabb.min.z, aabb.max.z);
+
+    let t_near = x0.max(y0).max(z0).max(0.0);
+    let t_far = x1.min(y1).min(z1).min(limit);
+
+    if t_near <= t_far { Some(t_near) } else { None }
+}
+
+/// The entry and exit distance of one slab.
+fn slab_axis(origin:f32, inv:f32, min:f32, max:f32) -> (f32, f32) {
+    if inv.is_infinite() {
+        // parallel to the slab, either always or never inside
+        return if origin >= min && origin <= max {
+            (f32::NEG_INFINITY, f32::INFINITY)
+        } else {
+            (f32::INFINITY, f32::NEG_INFINITY)
+        };
+    }
+    let t0 = (min - origin) * inv;
+    let t1 = (max - origin) * inv;
+    if t0 <= t1 { (t0, t1) } else { (t1, t0) }
+}
+
+#[cfg(test)]
+fn test_mesh() -> (Vec<Vector3>, Vec<[u32; 3]>) {
+    // a wavy grid of 40 x 40 quads in the xy plane
+    let n = 41;
+    let mut vertices = Vec::new();
+    for y in 0..n {
+        for x in 0..n {
+            let z = ((x as f32) * 0.4).sin() * ((y as f32) * 0.3).cos();
+            vertices.push(Vector3::from(x as f32 * 0.25, y as f32 * 0.25, z));
+        }
+    }
+    let mut indices = Vec::new();
+    for y in 0..n - 1 {
+        for x in 0..n - 1 {
+            let i = y * n + x;
+            indices.push([i, i + 1, i + n + 1]);
+            indices.push([i, i + n + 1, i + n]);
+        }
+    }
+    (vertices, indices)
+}
+
+#[cfg(test)]
+fn brute_force(bvh:&Bvh, ray:&Ray, cull_backfaces:bool) -> Option<(usize, f32)> {
+    let mut best:Option<(usize, f32)> = None;
+    for t in 0..bvh.indices().len() {
+        if let Some(hit) = ray.intersect_triangle(&bvh.triangle(t), cull_backfaces) {
+            if best.iter().all(|&(_, d)| hit.hit.distance < d) {
+                best = Some((t, hit.hit.distance));
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+fn test_rays() -> Vec<Ray> {
+    let mut rays = Vec::new();
+    for i in 0..100 {
+        let f = i as f32;
+        let origin = Vector3::from((f * 0.37) % 10., (f * 0.73) % 10., 3.);
+        let direction = Vector3::from((f * 0.1).sin() * 0.5, (f * 0.2).cos() * 0.5, -1.).normalize();
+        rays.push(Ray::new(&origin, &direction));
+    }
+    rays
+}
+
+#[test]
+fn test_bvh_matches_brute_force() {
+    let (vertices, indices) = test_mesh();
+    let bvh = Bvh::build(&vertices, &indices);
+    assert!(bvh.node_count() > 1);
+
+    let mut hits = 0;
+    for ray in test_rays() {
+        let expected = brute_force(&bvh, &ray, false);
+        let hit = bvh.closest_hit(&ray, false);
+        assert_eq!(hit.is_some(), expected.is_some());
+        if let (Some(hit), Some((_, d))) = (hit, expected) {
+            assert!((hit.hit.hit.distance - d).abs() < 1e-5);
+            assert!(bvh.any_hit(&ray, d + 0.01, false));
+            assert!(!bvh.any_hit(&ray, d - 0.01, false));
+            hits += 1;
+        }
+    }
+    assert!(hits > 50);
+}
+
+#[test]
+fn test_bvh_query_aabb() {
+    let (vertices, indices) = test_mesh();
+    let bvh = Bvh::build(&vertices, &indices);
+    let query = Aabb::new(&Vector3::from(2., 3., -2.), &Vector3::from(3., 3.5, 2.));
+
+    let mut found = Vec::new();
+    bvh.query_aabb(&query, |t| found.push(t));
+    found.sort();
+
+    let expected: Vec<usize> = (0..indices.len()).filter(|&t| bvh.triangle(t).to_aabb().intersects_aabb(&query)).collect();
+    assert_eq!(found, expected);
+    assert!(!found.is_empty());
+}
+
+#[test]
+fn test_bvh_refit() {
+    let (mut vertices, indices) = test_mesh();
+    let mut bvh = Bvh::build(&vertices, &indices);
+
+    // lift the whole mesh and squash it
+    for v in vertices.iter_mut() {
+        v.z = v.z * 0.5 + 10.;
+    }
+    bvh.refit(&vertices);
+    assert!(bvh.bounds().min.z >= 9.);
+
+    for ray in test_rays() {
+        let mut ray = ray;
+        ray.origin.z += 10.;
+        let expected = brute_force(&bvh, &ray, false).map(|(_, d)| d);
+        let hit = bvh.closest_hit(&ray, false).map(|h| h.hit.hit.distance);
+        assert_eq!(hit.is_some(), expected.is_some());
+        if let (Some(a), Some(b)) = (hit, expected) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_bvh_transformed() {
+    let (vertices, indices) = test_mesh();
+    let bvh = Bvh::build(&vertices, &indices);
+
+    // the mesh stood upright and moved away: its z axis now points along -y
+    let mut model = Matrix4::new();
+    model.translation_mut(0., 20., 0.);
+    let mut r = Matrix4::new();
+    r.rotation_mut(std::f32::consts::FRAC_PI_2, &Vector3::from_i32(1, 0, 0));
+    model.mult_mut(&r);
+
+    // aim off the grid vertices and diagonals so that exactly one triangle is hit
+    let ray = Ray::new(&Vector3::from(5.1, 0., 4.9), &Vector3::from_i32(0, 1, 0));
+    let hit = bvh.closest_hit_transformed(&ray, &model, false).unwrap();
+
+    // the same cast against the mesh transformed into world space triangle by triangle
+    let world:Vec<Vector3> = vertices.iter().map(|v| {
+        let mut w = *v;
+        model.mult_to_vector3(&mut w, 1.0);
+        w
+    }).collect();
+    let world_bvh = Bvh::build(&world, &indices);
+    let (triangle, distance) = brute_force(&world_bvh, &ray, false).unwrap();
+    let expected = ray.intersect_triangle(&world_bvh.triangle(triangle), false).unwrap().hit;
+
+    assert_eq!(hit.triangle, triangle);
+    assert!((hit.hit.hit.distance - distance).abs() < 1e-4);
+    assert!(hit.hit.hit.point.distance(&expected.point) < 1e-4);
+    assert!(hit.hit.hit.point.distance(&ray.point_at(distance)) < 1e-4);
+    assert!(hit.hit.hit.normal.distance(&expected.normal) < 1e-4);
+    assert!((hit.hit.hit.normal.length() - 1.).abs() < 1e-5);
+    assert!(bvh.any_hit_transformed(&ray, &model, distance + 0.01, false));
+    assert!(!bvh.any_hit_transformed(&ray, &model, distance - 0.01, false));
+
+    let mut found = Vec::new();
+    bvh.query_aabb_transformed(&Aabb::new(&Vector3::from(4.9, 15., 4.9), &Vector3::from(5.1, 25., 5.1)), &model, |t| found.push(t));
+    assert!(found.contains(&triangle));
+
+    let empty = Bvh::build(&[], &[]);
+    assert!(empty.closest_hit(&ray, false).is_none());
+}
+
+
+#[test]
+fn test_bvh_transformed_mirrored() {
+    let (vertices, indices) = test_mesh();
+    let bvh = Bvh::build(&vertices, &indices);
+
+    // mirror the mesh at x = 5, which turns the winding around
+    let mut model = Matrix4::new();
+    model.translation_mut(10., 0., 0.);
+    let mut s = Matrix4::new();
+    s.scale_mut(-1., 1., 1.);
+    model.mult_mut(&s);
+    assert!(model.determinant() < 0.);
+
+    let world:Vec<Vector3> = vertices.iter().map(|v| {
+        let mut w = *v;
+        model.mult_to_vector3(&mut w, 1.0);
+        w
+    }).collect();
+    let world_bvh = Bvh::build(&world, &indices);
+
+    // the mesh faces up, its mirror image down: cast from both sides
+    let mut hits = 0;
+    for mut ray in test_rays() {
+        // stay clear of the border of the mesh, where the mirrored cast may round differently
+        ray.origin += Vector3::from(0.013, 0.017, 0.);
+        let mut below = ray;
+        below.origin.z = -3.;
+        below.direction.z *= -1.;
+        for ray in [ray, below].iter() {
+            let expected = brute_force(&world_bvh, ray, true);
+            let hit = bvh.closest_hit_transformed(ray, &model, true);
+            assert_eq!(hit.is_some(), expected.is_some());
+            if let (Some(hit), Some((triangle, d))) = (hit, expected) {
+                let normal = ray.intersect_triangle(&world_bvh.triangle(triangle), true).unwrap().hit.normal;
+                assert_eq!(hit.triangle, triangle);
+                assert!((hit.hit.hit.distance - d).abs() < 1e-4);
+                assert!(hit.hit.hit.normal.distance(&normal) < 1e-4);
+                assert!(bvh.any_hit_transformed(ray, &model, d + 0.01, true));
+                hits += 1;
+            } else {
+                assert!(!bvh.any_hit_transformed(ray, &model, 100., true));
+            }
+        }
+    }
+    assert!(hits > 50);
+}
@@ -0,0 +1,328 @@
+//! Camera controllers that hold their state between frames and produce view matrices, built
+//! on `Matrix4::look_at`.
+//!
+//! The orbit and the fly camera measure `yaw` around their `up` axis and `pitch` above the
+//! plane perpendicular to it, both in radians. For the default z-up world (see
+//! `Vector3::new_z_up`), yaw `0` points along `x` and yaw `PI/2` along `y`.
+
+use super::{Matrix4, Quaternion, Vector3};
+
+/// Pitch is kept this far away from straight up and down, where the view direction would be
+/// parallel to the up axis and `look_at` undefined.
+const PITCH_LIMIT: f32 = std::f32::consts::FRAC_PI_2 - 1e-3;
+
+/// A camera circling around a target, e.g. for model viewers and editors.
+#[derive(Debug, Copy, Clone)]
+pub struct OrbitCamera {
+    /// The point the camera looks at and circles around.
+    pub target: Vector3,
+
+    /// The angle around the up axis in radians.
+    pub yaw: f32,
+
+    /// The angle above the ground plane in radians, positive values look down onto the target.
+    pub pitch: f32,
+
+    /// The distance between the camera and the target.
+    pub distance: f32,
+
+    /// The up direction of the world, of unit length.
+    pub up: Vector3
+}
+
+/// A first-person camera flying freely through the scene.
+#[derive(Debug, Copy, Clone)]
+pub struct FlyCamera {
+    /// The position of the camera.
+    pub position: Vector3,
+
+    /// The angle of the view direction around the up axis in radians.
+    pub yaw: f32,
+
+    /// The angle of the view direction above the ground plane in radians.
+    pub pitch: f32,
+
+    /// The up direction of the world, of unit length.
+    pub up: Vector3
+}
+
+/// Shoemake's arcball, which maps mouse drags over the viewport to rotations of a virtual ball
+/// under the mouse, e.g. to rotate a model in view space with `view * arcball.matrix() * model`.
+#[derive(Debug, Copy, Clone)]
+pub struct Arcball {
+    width: f32,
+    height: f32,
+    rotation: Quaternion,
+    drag: Option<(Vector3, Quaternion)>,
+}
+
+/// Two unit vectors spanning the ground plane perpendicular to `up`, the first one is yaw `0`.
+fn ground_axes(up:&Vector3) -> (Vector3, Vector3) {
+    let reference = if up.x.abs() < 0.9 { Vector3::from(1., 0., 0.) } else { Vector3::from(0., 1., 0.) };
+    let first = (reference - up * up.dot(&reference)).normalize();
+    let second = up.cross(&first);
+    (first, second)
+}
+
+/// The unit direction of the provided angles, see the module documentation.
+fn direction(up:&Vector3, yaw:f32, pitch:f32) -> Vector3 {
+    let (first, second) = ground_axes(up);
+    let (sy, cy) = f32::sin_cos(yaw);
+    let (sp, cp) = f32::sin_cos(pitch);
+    first * (cp * cy) + second * (cp * sy) + up * sp
+}
+
+impl OrbitCamera {
+
+    /// Creates a new `OrbitCamera` looking at `target` from `distance` along the `x` axis in a
+    /// z-up world.
+    pub fn new(target:&Vector3, distance:f32) -> OrbitCamera {
+        OrbitCamera {target: *target, yaw: 0.0, pitch: 0.0, distance, up: Vector3::new_z_up()}
+    }
+
+    /// Returns the position of the camera.
+    pub fn eye(&self) -> Vector3 {
+        self.target + direction(&self.up, self.yaw, self.pitch) * self.distance
+    }
+
+    /// Circles around the target by the provided angles in radians, e.g. from mouse movement
+    /// scaled by a sensitivity. The pitch is clamped short of straight up and down.
+    pub fn rotate(&mut self, delta_yaw:f32, delta_pitch:f32) -> &mut OrbitCamera {
+        self.yaw += delta_yaw;
+        self.pitch = (self.pitch + delta_pitch).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+        self
+    }
+
+    /// Multiplies the distance by `factor`, e.g. `0.9` per mouse wheel step towards the target.
+    /// The distance never drops below `min_distance`.
+    pub fn zoom(&mut self, factor:f32, min_distance:f32) -> &mut OrbitCamera {
+        self.distance = (self.distance * factor).max(min_distance);
+        self
+    }
+
+    /// Moves the target (and with it the camera) parallel to the image plane, `right` to the
+    /// right and `up` upwards on screen, in world units.
+    pub fn pan(&mut self, right:f32, up:f32) -> &mut OrbitCamera {
+        let back = direction(&self.up, self.yaw, self.pitch);
+        let screen_right = self.up.cross(&back).normalize();
+        let screen_up = back.cross(&screen_right);
+        self.target += screen_right * right + screen_up * up;
+        self
+    }
+
+    /// Returns the view matrix, see `Matrix4::look_at`.
+    pub fn view_matrix(&self) -> Matrix4 {
+        Matrix4::look_at(&self.eye(), &self.target, &self.up)
+    }
+}
+
+impl FlyCamera {
+
+    /// Creates a new `FlyCamera` at `position` looking along the `x` axis in a z-up world.
+    pub fn new(position:&Vector3) -> FlyCamera {
+        FlyCamera {position: *position, yaw: 0.0, pitch: 0.0, up: Vector3::new_z_up()}
+    }
+
+    /// Returns the unit view direction.
+    pub fn forward(&self) -> Vector3 {
+        direction(&self.up, self.yaw, self.pitch)
+    }
+
+    /// Returns the unit direction to the right of the view direction, parallel to the ground.
+    pub fn right(&self) -> Vector3 {
+        self.forward().cross(&self.up).normalize()
+    }
+
+    /// Turns the camera by the provided angles in radians, e.g. from mouse movement scaled by a
+    /// sensitivity. The pitch is clamped short of straight up and down.
+    pub fn rotate(&mut self, delta_yaw:f32, delta_pitch:f32) -> &mut FlyCamera {
+        self.yaw += delta_yaw;
+        self.pitch = (self.pitch + delta_pitch).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+        self
+    }
+
+    /// Moves the camera with the provided velocity for `dt` seconds. The velocity is relative
+    /// to the camera: `x` to the right, `y` along the world up axis and `z` along the view
+    /// direction, e.g. `(0, 0, speed)` while the forward key is pressed.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Vector3;
+    /// use js_linalg::camera::FlyCamera;
+    /// let mut camera = FlyCamera::new(&Vector3::new());
+    /// camera.rotate(std::f32::consts::FRAC_PI_2, 0.);
+    /// camera.update(&Vector3::from(0., 0., 2.), 0.5);
+    /// assert!((camera.position.y - 1.).abs() < 1e-6);
+    /// ```
+    pub fn update(&mut self, velocity:&Vector3, dt:f32) -> &mut FlyCamera {
+        let movement = self.right() * velocity.x + self.up * velocity.y + self.forward() * velocity.z;
+        self.position += movement * dt;
+        self
+    }
+
+    /// Returns the view matrix, see `Matrix4::look_at`.
+    pub fn view_matrix(&self) -> Matrix4 {
+        Matrix4::look_at(&self.position, &(self.position + self.forward()), &self.up)
+    }
+}
+
+impl Arcball {
+
+    /// Creates a new `Arcball` without rotation for a viewport of the provided size in pixels.
+    /// The ball is centered in the viewport and touches its shorter sides.
+    pub fn new(width:f32, height:f32) -> Arcball {
+        Arcball {width, height, rotation: Quaternion::new(), drag: None}
+    }
+
+    /// Updates the size of the viewport, e.g. when the window is resized.
+    pub fn resize(&mut self, width:f32, height:f32) -> &mut Arcball {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Maps screen coordinates (pixels from the upper-left corner) onto the unit ball in view
+    /// space. Points outside of the ball are mapped onto its rim.
+    pub fn map_to_sphere(&self, x:f32, y:f32) -> Vector3 {
+        let radius = 0.5 * self.width.min(self.height);
+        let px = (x - 0.5 * self.width) / radius;
+        let py = (0.5 * self.height - y) / radius;
+
+        let r = px * px + py * py;
+        if r > 1.0 {
+            let s = 1.0 / r.sqrt();
+            Vector3::from(px * s, py * s, 0.0)
+        } else {
+            Vector3::from(px, py, (1.0 - r).sqrt())
+        }
+    }
+
+    /// Starts a drag at the provided screen coordinates, e.g. on mouse down.
+    pub fn begin_drag(&mut self, x:f32, y:f32) -> &mut Arcball {
+        self.drag = Some((self.map_to_sphere(x, y), self.rotation));
+        self
+    }
+
+    /// Continues the drag at the provided screen coordinates, e.g. on mouse move. Like in
+    /// Shoemake's original, the ball turns twice the arc between the start and the current point,
+    /// so that a drag across the whole ball is a full turn. Does nothing without `begin_drag`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Vector3;
+    /// use js_linalg::camera::Arcball;
+    /// let mut arcball = Arcball::new(200., 200.);
+    /// arcball.begin_drag(100., 100.).drag(150., 100.);
+    /// // dragging to the right turns the front of the ball to the right
+    /// let front = arcball.rotation() * Vector3::from_i32(0, 0, 1);
+    /// assert!(front.x > 0.);
+    /// ```
+    pub fn drag(&mut self, x:f32, y:f32) -> &mut Arcball {
+        if let Some((from, start)) = self.drag {
+            let to = self.map_to_sphere(x, y);
+            let axis = from.cross(&to);
+            let q = Quaternion::from(axis.x, axis.y, axis.z, from.dot(&to));
+            self.rotation = q * start;
+            self.rotation.normalize_mut();
+        }
+        self
+    }
+
+    /// Ends the drag, e.g. on mouse up. The rotation is kept.
+    pub fn end_drag(&mut self) -> &mut Arcball {
+        self.drag = None;
+        self
+    }
+
+    /// Returns `true` between `begin_drag` and `end_drag`.
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// The current rotation in view space.
+    pub fn rotation(&self) -> Quaternion {
+        self.rotation
+    }
+
+    /// Replaces the current rotation, e.g. to reset it to `Quaternion::new()`.
+    pub fn set_rotation(&mut self, q:&Quaternion) -> &mut Arcball {
+        self.rotation = *q;
+        self
+    }
+
+    /// Returns the current rotation as `Matrix4`.
+    pub fn matrix(&self) -> Matrix4 {
+        self.rotation.to_matrix4()
+    }
+}
+
+#[test]
+fn test_orbit_camera() {
+    let target = Vector3::from_i32(1, 2, 3);
+    let mut camera = OrbitCamera::new(&target, 5.);
+    assert!(camera.eye().distance(&Vector3::from_i32(6, 2, 3)) < 1e-5);
+
+    camera.rotate(std::f32::consts::FRAC_PI_2, 0.);
+    assert!(camera.eye().distance(&Vector3::from_i32(1, 7, 3)) < 1e-5);
+
+    // the target ends up in front of the camera
+    let v = camera.view_matrix() * target;
+    assert!(v.x.abs() < 1e-5 && v.y.abs() < 1e-5);
+    assert!((v.z + 5.).abs() < 1e-5);
+
+    camera.rotate(0., 10.);
+    assert!(camera.pitch < std::f32::consts::FRAC_PI_2);
+    assert!(camera.eye().z > 7.9);
+
+    camera.zoom(0.1, 1.);
+    assert_eq!(camera.distance, 1.);
+
+    // panning keeps the view direction and moves the target sideways on screen
+    let mut camera = OrbitCamera::new(&Vector3::new(), 5.);
+    camera.pan(1., 0.);
+    let v = camera.view_matrix() * Vector3::new();
+    assert!((v.x + 1.).abs() < 1e-5);
+    assert!((camera.target.y - 1.).abs() < 1e-5);
+
+    // a y-up world
+    let mut camera = OrbitCamera::new(&Vector3::new(), 2.);
+    camera.up = Vector3::from_i32(0, 1, 0);
+    camera.rotate(0., 0.5);
+    assert!(camera.eye().y > 0.);
+}
+
+#[test]
+fn test_fly_camera() {
+    let mut camera = FlyCamera::new(&Vector3::new());
+    camera.update(&Vector3::from(1., 2., 3.), 1.);
+    assert!(camera.position.distance(&Vector3::from(3., -1., 2.)) < 1e-5);
+
+    // looking straight ahead keeps the camera on the view axis
+    let v = camera.view_matrix() * (camera.position + camera.forward() * 4.);
+    assert!((v.z + 4.).abs() < 1e-5);
+}
+
+#[test]
+fn test_arcball() {
+    let mut arcball = Arcball::new(200., 100.);
+    assert!((arcball.map_to_sphere(100., 50.).z - 1.).abs() < 1e-6);
+    assert!(arcball.map_to_sphere(0., 50.).z.abs() < 1e-6);
+
+    // dragging upwards on screen tilts the front of the ball upwards
+    arcball.begin_drag(100., 50.).drag(100., 25.);
+    assert!(arcball.is_dragging());
+    let front = arcball.rotation() * Vector3::from_i32(0, 0, 1);
+    assert!(front.y > 0.);
+    assert!(front.x.abs() < 1e-6);
+
+    // a drag from the center to the rim is a half turn
+    arcball.end_drag().set_rotation(&Quaternion::new());
+    arcball.begin_drag(100., 50.).drag(150., 50.).end_drag();
+    let front = &arcball.matrix() * Vector3::from_i32(0, 0, 1);
+    assert!((front.z + 1.).abs() < 1e-5);
+
+    // without begin_drag nothing happens
+    let mut idle = Arcball::new(100., 100.);
+    idle.drag(10., 10.);
+    assert_eq!(idle.rotation().w, 1.);
+}
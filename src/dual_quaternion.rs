@@ -0,0 +1,490 @@
+use super::{DualQuaternion, Quaternion, Matrix4, Vector3, Error};
+
+/// Returns `a * s + b * t` component-wise.
+fn weighted_sum(a:&Quaternion, s:f32, b:&Quaternion, t:f32) -> Quaternion {
+    Quaternion {
+        x: a.x * s + b.x * t,
+        y: a.y * s + b.y * t,
+        z: a.z * s + b.z * t,
+        w: a.w * s + b.w * t,
+    }
+}
+
+/// Returns `a * s` component-wise.
+fn scaled(a:&Quaternion, s:f32) -> Quaternion {
+    weighted_sum(a, s, a, 0.0)
+}
+
+impl DualQuaternion {
+
+    /// Creates a new identity `DualQuaternion`: no rotation, no translation.
+    pub fn new() -> DualQuaternion {
+        DualQuaternion {
+            real: Quaternion::new(),
+            dual: Quaternion {x: 0.0, y: 0.0, z: 0.0, w: 0.0},
+        }
+    }
+
+    /// Creates the transformation that first rotates by the (unit) quaternion `rotation` and
+    /// then translates by `translation`, i.e. the same as the matrix `T * R`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{DualQuaternion, Quaternion, Vector3};
+    /// let q = Quaternion::from_axis_angle(&Vector3::new_z_up(), std::f32::consts::FRAC_PI_2);
+    /// let dq = DualQuaternion::from_rotation_translation(&q, &Vector3::from_i32(0, 0, 5));
+    /// let v = dq * Vector3::from_i32(1, 0, 0);
+    /// assert!(v.x.abs() < 1e-6 && (v.y - 1.).abs() < 1e-6 && v.z == 5.);
+    /// ```
+    pub fn from_rotation_translation(rotation:&Quaternion, translation:&Vector3) -> DualQuaternion {
+        let t = Quaternion {x: translation.x, y: translation.y, z: translation.z, w: 0.0};
+        let dual = t * rotation;
+        DualQuaternion {
+            real: *rotation,
+            dual: scaled(&dual, 0.5),
+        }
+    }
+
+    /// Creates a pure translation.
+    pub fn from_translation(translation:&Vector3) -> DualQuaternion {
+        DualQuaternion::from_rotation_translation(&Quaternion::new(), translation)
+    }
+
+    /// Creates a pure rotation.
+    pub fn from_rotation(rotation:&Quaternion) -> DualQuaternion {
+        DualQuaternion {real: *rotation, dual: Quaternion {x: 0.0, y: 0.0, z: 0.0, w: 0.0}}
+    }
+
+    /// Creates a dual quaternion from a rigid transformation matrix, i.e. rotation and
+    /// translation only. Scaling and shear are not representable, see `Matrix4::decompose`
+    /// to separate them first.
+    pub fn from_matrix4(m:&Matrix4) -> DualQuaternion {
+        let mut rotation = Quaternion::from_matrix4(m);
+        rotation.normalize_mut();
+        DualQuaternion::from_rotation_translation(&rotation, &Vector3::from(m.m_3_0, m.m_3_1, m.m_3_2))
+    }
+
+    /// The rotation part.
+    pub fn rotation(&self) -> Quaternion {
+        self.real
+    }
+
+    /// The translation part, `2 * dual * conjugate(real)`.
+    pub fn translation(&self) -> Vector3 {
+        let t = self.dual * self.real.conjugate();
+        Vector3::from(2.0 * t.x, 2.0 * t.y, 2.0 * t.z)
+    }
+
+    /// Returns the rigid transformation as new `Matrix4`, e.g. for uploading.
+    pub fn to_matrix4(&self) -> Matrix4 {
+        let t = self.translation();
+        let mut m = Matrix4::new();
+        m.rotation_quaternion_mut(&self.real)
+         .set_column(3, t.x, t.y, t.z, 1.0);
+        m
+    }
+
+    /// Sets this dual quaternion to identity.
+    pub fn identity_mut(&mut self) -> &mut DualQuaternion {
+        self.real.identity_mut();
+        self.dual.set(0.0, 0.0, 0.0, 0.0);
+        self
+    }
+
+    /// In-place multiplication `self = self * that`. The resulting transformation first
+    /// applies `that` and then `self`, just like with matrices.
+    pub fn mult_mut(&mut self, that:&DualQuaternion) -> &mut DualQuaternion {
+        let real = self.real * that.real;
+        let a = self.real * that.dual;
+        let b = self.dual * that.real;
+        self.real = real;
+        self.dual = weighted_sum(&a, 1.0, &b, 1.0);
+        self
+    }
+
+    /// In-place conjugation of both parts. For unit dual quaternions this is the inverse.
+    pub fn conjugate_mut(&mut self) -> &mut DualQuaternion {
+        self.real.conjugate_mut();
+        self.dual.conjugate_mut();
+        self
+    }
+
+    /// Returns the conjugate of this dual quaternion, see `conjugate_mut`.
+    pub fn conjugate(&self) -> DualQuaternion {
+        let mut dq = *self;
+        dq.conjugate_mut();
+        dq
+    }
+
+    /// In-place inversion of this unit dual quaternion, which is the same as `conjugate_mut`.
+    pub fn inverse_mut(&mut self) -> &mut DualQuaternion {
+        self.conjugate_mut()
+    }
+
+    /// Returns the inverse of this unit dual quaternion as new `DualQuaternion`.
+    pub fn inverse(&self) -> DualQuaternion {
+        self.conjugate()
+    }
+
+    /// In-place normalization to a unit dual quaternion: the real part gets unit length and
+    /// the dual part is made orthogonal to it. A zero real part ends up with NaN components,
+    /// see `try_normalize_mut`.
+    pub fn normalize_mut(&mut self) -> &mut DualQuaternion {
+        let d = 1.0 / self.real.length();
+        self.real = scaled(&self.real, d);
+        self.dual = weighted_sum(&self.dual, d, &self.real, -d * self.real.dot(&self.dual));
+        self
+    }
+
+    /// Returns a normalized copy of this dual quaternion.
+    pub fn normalize(&self) -> DualQuaternion {
+        let mut dq = *self;
+        dq.normalize_mut();
+        dq
+    }
+
+    /// In-place normalization like `normalize_mut`, but returns `Error::ZeroLength` and leaves
+    /// this dual quaternion untouched if the real part has a length of (close to) zero.
+    pub fn try_normalize_mut(&mut self) -> Result<&mut DualQuaternion, Error> {
+        let d = self.real.length();
+        if !(d >= f32::EPSILON && d.is_finite()) {
+            return Err(Error::ZeroLength);
+        }
+        Ok(self.normalize_mut())
+    }
+
+    /// Returns a normalized copy like `normalize`, or `Error::ZeroLength`.
+    pub fn try_normalize(&self) -> Result<DualQuaternion, Error> {
+        let mut dq = *self;
+        dq.try_normalize_mut()?;
+        Ok(dq)
+    }
+
+    /// Dual quaternion linear blending (DLB, Kavan et al.): the weighted sum of the provided
+    /// transformations, normalized. Unlike blending matrices this keeps the result rigid, so
+    /// twisting joints do not collapse ("candy wrapper"). Dual quaternions on the opposite
+    /// hemisphere of the first one are negated to blend along the shortest path.
+    ///
+    /// Returns `Error::ZeroLength` if the weights cancel out, e.g. if they are all zero.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{DualQuaternion, Quaternion, Vector3};
+    /// let a = DualQuaternion::new();
+    /// let b = DualQuaternion::from_rotation(&Quaternion::from_axis_angle(&Vector3::from_i32(1, 0, 0), 3.));
+    /// let c = DualQuaternion::blend(&[a, b], &[0.5, 0.5]).unwrap();
+    /// // the arm keeps its length at the twisted joint
+    /// assert!(((c * Vector3::from_i32(0, 1, 0)).length() - 1.).abs() < 1e-6);
+    /// ```
+    pub fn blend(values:&[DualQuaternion], weights:&[f32]) -> Result<DualQuaternion, Error> {
+        let mut sum = DualQuaternion {
+            real: Quaternion {x: 0.0, y: 0.0, z: 0.0, w: 0.0},
+            dual: Quaternion {x: 0.0, y: 0.0, z: 0.0, w: 0.0},
+        };
+        if let Some(first) = values.first() {
+            for (dq, &w) in values.iter().zip(weights.iter()) {
+                let w = if first.real.dot(&dq.real) < 0.0 { -w } else { w };
+                sum.real = weighted_sum(&sum.real, 1.0, &dq.real, w);
+                sum.dual = weighted_sum(&sum.dual, 1.0, &dq.dual, w);
+            }
+        }
+        sum.try_normalize_mut()?;
+        Ok(sum)
+    }
+
+    /// Screw linear interpolation (ScLERP) between this and `that`: the motion rotates around
+    /// and slides along a single axis at constant speed, which is the natural interpolation of
+    /// rigid transformations. Takes the shortest path. Both dual quaternions must be of unit length.
+    pub fn sclerp(&self, that:&DualQuaternion, t:f32) -> DualQuaternion {
+        let mut end = *that;
+        if self.real.dot(&that.real) < 0.0 {
+            end.real = scaled(&that.real, -1.0);
+            end.dual = scaled(&that.dual, -1.0);
+        }
+        let mut difference = self.conjugate();
+        difference.mult_mut(&end);
+
+        let mut result = *self;
+        result.mult_mut(&difference.pow(t));
+        result
+    }
+
+    /// Raises this unit dual quaternion to the power `t` using its screw parameters. The
+    /// real part must have a non-negative scalar part, i.e. an angle of at most 180 degrees.
+    fn pow(&self, t:f32) -> DualQuaternion {
+        let r = &self.real;
+        let d = &self.dual;
+        let sin_half = (r.x * r.x + r.y * r.y + r.z * r.z).sqrt();
+
+        if sin_half < 1e-6 {
+            // pure translation, which scales linearly
+            return DualQuaternion {
+                real: Quaternion::new(),
+                dual: Quaternion {x: d.x * t, y: d.y * t, z: d.z * t, w: 0.0},
+            };
+        }
+
+        // screw axis direction l and moment m, half angle and half pitch
+        let half_angle = sin_half.atan2(r.w);
+        let l = Vector3::from(r.x, r.y, r.z) * (1.0 / sin_half);
+        let half_pitch = -d.w / sin_half;
+        let m = (Vector3::from(d.x, d.y, d.z) - l * (half_pitch * r.w)) * (1.0 / sin_half);
+
+        let (s, c) = (half_angle * t).sin_cos();
+        let half_pitch = half_pitch * t;
+        let dual = m * s + l * (half_pitch * c);
+        DualQuaternion {
+            real: Quaternion {x: l.x * s, y: l.y * s, z: l.z * s, w: c},
+            dual: Quaternion {x: dual.x, y: dual.y, z: dual.z, w: -half_pitch * s},
+        }
+    }
+
+    /// Transforms the provided point in-place: rotation followed by translation.
+    pub fn mult_to_vector3(&self, vec:&mut Vector3) {
+        self.real.mult_to_vector3(vec);
+        *vec += self.translation();
+    }
+
+    /// Returns the components as array reference without copying, `real` first. This is
+    /// possible since the memory layout of `DualQuaternion` is guaranteed to be `[f32; 8]`.
+    pub fn as_array(&self) -> &[f32; 8] {
+        // SAFETY: `DualQuaternion` is `#[repr(C)]` and consists of 8 `f32`'s without padding, so it
+        // has the size and alignment of `[f32; 8]`; the result borrows `self`.
+        unsafe { &*(self as *const DualQuaternion as *const [f32; 8]) }
+    }
+
+    /// Returns the components as mutable array reference without copying.
+    pub fn as_mut_array(&mut self) -> &mut [f32; 8] {
+        // SAFETY: same layout argument as in `as_array`, the exclusive borrow of `self` is
+        // handed over to the returned array.
+        unsafe { &mut *(self as *mut DualQuaternion as *mut [f32; 8]) }
+    }
+
+    /// Returns the components as slice without copying.
+    pub fn as_slice(&self) -> &[f32] {
+        self.as_array()
+    }
+
+    /// Returns a pointer to the first component (`real.x`), e.g. for `glUniform*` calls.
+    /// The pointer is valid for 8 `f32`'s as long as this `DualQuaternion` lives.
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const DualQuaternion as *const f32
+    }
+
+    /// Reinterprets a slice of `DualQuaternion`'s as one flat slice of `f32`'s without copying,
+    /// e.g. for uploading a skinning palette. The result has `8 * values.len()` elements.
+    pub fn slice_as_f32(values: &[DualQuaternion]) -> &[f32] {
+        // SAFETY: every element is 8 consecutive `f32`'s without padding (`#[repr(C)]`) and
+        // slice elements are contiguous, so the memory is exactly `8 * len` initialized `f32`'s
+        // borrowed for the lifetime of `values`.
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const f32, values.len() * 8) }
+    }
+}
+
+impl Default for DualQuaternion {
+    /// Same as `DualQuaternion::new()`.
+    fn default() -> DualQuaternion {
+        DualQuaternion::new()
+    }
+}
+
+impl std::ops::Mul<DualQuaternion> for DualQuaternion {
+    type Output = DualQuaternion;
+
+    /// Overrides '*' operator to concatenate two `DualQuaternion`s, see `mult_mut`.
+    fn mul(self, rhs: DualQuaternion) -> DualQuaternion {
+        let mut a = self;
+        a.mult_mut(&rhs);
+        a
+    }
+}
+
+impl std::ops::Mul<&DualQuaternion> for DualQuaternion {
+    type Output = DualQuaternion;
+
+    fn mul(self, rhs: &DualQuaternion) -> DualQuaternion {
+        let mut a = self;
+        a.mult_mut(rhs);
+        a
+    }
+}
+
+impl std::ops::Mul<&DualQuaternion> for &DualQuaternion {
+    type Output = DualQuaternion;
+
+    fn mul(self, rhs: &DualQuaternion) -> DualQuaternion {
+        let mut a = *self;
+        a.mult_mut(rhs);
+        a
+    }
+}
+
+impl std::ops::Mul<DualQuaternion> for &DualQuaternion {
+    type Output = DualQuaternion;
+
+    fn mul(self, rhs: DualQuaternion) -> DualQuaternion {
+        let mut a = *self;
+        a.mult_mut(&rhs);
+        a
+    }
+}
+
+impl std::ops::MulAssign<&DualQuaternion> for DualQuaternion {
+
+    fn mul_assign(&mut self, rhs: &DualQuaternion) {
+        self.mult_mut(rhs);
+    }
+}
+
+impl std::ops::MulAssign<DualQuaternion> for DualQuaternion {
+
+    fn mul_assign(&mut self, rhs: DualQuaternion) {
+        self.mult_mut(&rhs);
+    }
+}
+
+impl std::ops::Mul<Vector3> for DualQuaternion {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to transform a `Vector3` by a `DualQuaternion`.
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        let mut v = rhs;
+        self.mult_to_vector3(&mut v);
+        v
+    }
+}
+
+impl std::ops::Mul<&Vector3> for &DualQuaternion {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to transform a `Vector3` by a `DualQuaternion`.
+    fn mul(self, rhs: &Vector3) -> Vector3 {
+        let mut v = *rhs;
+        self.mult_to_vector3(&mut v);
+        v
+    }
+}
+
+impl std::ops::Mul<&Vector3> for DualQuaternion {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to transform a `Vector3` by a `DualQuaternion`.
+    fn mul(self, rhs: &Vector3) -> Vector3 {
+        let mut v = *rhs;
+        self.mult_to_vector3(&mut v);
+        v
+    }
+}
+
+impl std::ops::Mul<Vector3> for &DualQuaternion {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to transform a `Vector3` by a `DualQuaternion`.
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        let mut v = rhs;
+        self.mult_to_vector3(&mut v);
+        v
+    }
+}
+
+#[cfg(test)]
+fn assert_vector3_near(a:&Vector3, b:&Vector3) {
+    assert!(a.distance(b) < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn test_mul_dual_quaternion_completeness() {
+    let a = DualQuaternion::new();
+    let b = DualQuaternion::new();
+    let _c = &a * &b;
+    let _c = a * b;
+    let _c = a * &b;
+    let _c = &a * b;
+    let _v = a * Vector3::new();
+    let _v = &a * &Vector3::new();
+    let _v = a * &Vector3::new();
+    let _v = &a * Vector3::new();
+}
+
+#[test]
+fn test_dual_quaternion_matches_matrix4() {
+    let axis = Vector3::from(1., -2., 0.5).normalize();
+    let q = Quaternion::from_axis_angle(&axis, 1.3);
+    let t = Vector3::from(3., -1., 2.);
+    let dq = DualQuaternion::from_rotation_translation(&q, &t);
+
+    let mut m = Matrix4::new();
+    m.rotation_quaternion_mut(&q).set_column(3, t.x, t.y, t.z, 1.);
+    for (a, b) in dq.to_matrix4().as_array().iter().zip(m.as_array().iter()) {
+        assert!((a - b).abs() < 1e-5);
+    }
+    assert_vector3_near(&dq.translation(), &t);
+
+    let p = Vector3::from(0.5, 4., -2.);
+    assert_vector3_near(&(dq * p), &(&m * p));
+
+    let back = DualQuaternion::from_matrix4(&m);
+    assert_vector3_near(&(back * p), &(dq * p));
+
+    // concatenation and inverse behave like the matrix counterparts
+    let other = DualQuaternion::from_rotation_translation(&Quaternion::from_euler(0.3, 0.2, -0.9), &Vector3::from_i32(1, 1, 0));
+    let c = dq * other;
+    let mc = &m * &other.to_matrix4();
+    assert_vector3_near(&(c * p), &(&mc * p));
+    assert_vector3_near(&(c.inverse() * (c * p)), &p);
+}
+
+#[test]
+fn test_dual_quaternion_blend() {
+    let a = DualQuaternion::from_translation(&Vector3::from_i32(2, 0, 0));
+    let b = DualQuaternion::from_translation(&Vector3::from_i32(4, 0, 0));
+    let c = DualQuaternion::blend(&[a, b], &[0.25, 0.75]).unwrap();
+    assert_vector3_near(&c.translation(), &Vector3::from(3.5, 0., 0.));
+
+    // the opposite hemisphere representation of the same transformation blends identically
+    let r = DualQuaternion::from_rotation_translation(&Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.4), &Vector3::from_i32(1, 0, 0));
+    let mut neg = r;
+    neg.real = scaled(&r.real, -1.);
+    neg.dual = scaled(&r.dual, -1.);
+    let c = DualQuaternion::blend(&[r, neg], &[0.5, 0.5]).unwrap();
+    assert_vector3_near(&(c * Vector3::from_i32(0, 1, 0)), &(r * Vector3::from_i32(0, 1, 0)));
+
+    assert_eq!(DualQuaternion::blend(&[a, b], &[0., 0.]).unwrap_err(), Error::ZeroLength);
+    assert!(DualQuaternion::blend(&[], &[]).is_err());
+}
+
+#[test]
+fn test_dual_quaternion_sclerp() {
+    // a screw motion: quarter turn around z while moving up by 4
+    let a = DualQuaternion::new();
+    let b = DualQuaternion::from_rotation_translation(&Quaternion::from_axis_angle(&Vector3::new_z_up(), std::f32::consts::FRAC_PI_2), &Vector3::from_i32(0, 0, 4));
+
+    let p = Vector3::from_i32(1, 0, 0);
+    assert_vector3_near(&(a.sclerp(&b, 0.) * p), &p);
+    assert_vector3_near(&(a.sclerp(&b, 1.) * p), &(b * p));
+
+    let half = std::f32::consts::FRAC_PI_4;
+    assert_vector3_near(&(a.sclerp(&b, 0.5) * p), &Vector3::from(half.cos(), half.sin(), 2.));
+
+    // pure translations
+    let c = DualQuaternion::from_translation(&Vector3::from_i32(2, 4, 0));
+    assert_vector3_near(&a.sclerp(&c, 0.25).translation(), &Vector3::from(0.5, 1., 0.));
+
+    // a rotation around an axis not through the origin keeps the distance to the axis
+    let pivot = DualQuaternion::from_translation(&Vector3::from_i32(5, 0, 0));
+    let d = pivot * b * pivot.inverse();
+    let q = d.sclerp(&DualQuaternion::new(), 0.3) * Vector3::from_i32(6, 0, 0);
+    assert!(((q.x - 5.).hypot(q.y) - 1.).abs() < 1e-4);
+}
+
+#[test]
+fn test_dual_quaternion_memory_layout() {
+    assert_eq!(std::mem::size_of::<DualQuaternion>(), 8 * std::mem::size_of::<f32>());
+
+    let mut dq = DualQuaternion::new();
+    dq.as_mut_array()[4] = 2.;
+    assert_eq!(dq.dual.x, 2.);
+    assert_eq!(dq.as_slice(), &[0., 0., 0., 1., 2., 0., 0., 0.]);
+    assert_eq!(DualQuaternion::slice_as_f32(&[dq, dq]).len(), 16);
+}
@@ -0,0 +1,27 @@
+use super::Error;
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::SingularMatrix => write!(f, "matrix is singular and cannot be inverted"),
+            Error::ZeroLength => write!(f, "vector has zero length"),
+            Error::IndexOutOfRange {index, len} => write!(f, "index {} is out of range for length {}", index, len),
+            Error::ParallelUpVector => write!(f, "up vector is parallel to the viewing direction"),
+            Error::InvalidPointCount {count} => write!(f, "{} control points do not fit the kind of curve", count),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[test]
+fn test_error_display() {
+    let e = Error::IndexOutOfRange {index: 4, len: 4};
+    assert_eq!(e.to_string(), "index 4 is out of range for length 4");
+    let e = Error::IndexOutOfRange {index: 0, len: 0};
+    assert_eq!(e.to_string(), "index 0 is out of range for length 0");
+    assert_eq!(Error::ZeroLength.to_string(), "vector has zero length");
+
+    let boxed: Box<dyn std::error::Error> = Box::new(Error::SingularMatrix);
+    assert_eq!(boxed.to_string(), "matrix is singular and cannot be inverted");
+}
@@ -0,0 +1,107 @@
+use super::{EulerOrder, Matrix3};
+#[cfg(test)]
+use super::{Matrix4, Quaternion, Vector3};
+
+impl EulerOrder {
+
+    /// All twelve orders, e.g. for UI drop-downs.
+    pub const ALL: [EulerOrder; 12] = [
+        EulerOrder::XYZ, EulerOrder::XZY, EulerOrder::YXZ, EulerOrder::YZX, EulerOrder::ZXY, EulerOrder::ZYX,
+        EulerOrder::XYX, EulerOrder::XZX, EulerOrder::YXY, EulerOrder::YZY, EulerOrder::ZXZ, EulerOrder::ZYZ,
+    ];
+
+    /// The axes (`0` = x, `1` = y, `2` = z) of the first, second and third rotation.
+    pub fn axes(self) -> (usize, usize, usize) {
+        match self {
+            EulerOrder::XYZ => (0, 1, 2),
+            EulerOrder::XZY => (0, 2, 1),
+            EulerOrder::YXZ => (1, 0, 2),
+            EulerOrder::YZX => (1, 2, 0),
+            EulerOrder::ZXY => (2, 0, 1),
+            EulerOrder::ZYX => (2, 1, 0),
+            EulerOrder::XYX => (0, 1, 0),
+            EulerOrder::XZX => (0, 2, 0),
+            EulerOrder::YXY => (1, 0, 1),
+            EulerOrder::YZY => (1, 2, 1),
+            EulerOrder::ZXZ => (2, 0, 2),
+            EulerOrder::ZYZ => (2, 1, 2),
+        }
+    }
+
+    /// Returns `true` for proper Euler angles, i.e. if the first and the last axis are the same.
+    pub fn is_proper(self) -> bool {
+        let (i, _, k) = self.axes();
+        i == k
+    }
+}
+
+/// Sets `m` to a rotation of `alpha` radians about the axis with index `axis`.
+pub(crate) fn axis_rotation_mut(m: &mut Matrix3, axis: usize, alpha: f32) -> &mut Matrix3 {
+    match axis {
+        0 => m.rotation_x_mut(alpha),
+        1 => m.rotation_y_mut(alpha),
+        _ => m.rotation_z_mut(alpha),
+    }
+}
+
+#[cfg(test)]
+fn assert_matrix3_near(a: &Matrix3, b: &Matrix3) {
+    for (x, y) in a.as_array().iter().zip(b.as_array().iter()) {
+        assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_euler_round_trip_all_orders() {
+    let samples = [
+        Vector3::from(0.3, -0.7, 1.2),
+        Vector3::from(-2.5, 1.1, -0.4),
+        Vector3::from(3.0, -1.4, 2.9),
+    ];
+
+    for order in EulerOrder::ALL.iter() {
+        for sample in samples.iter() {
+            let mut angles = *sample;
+            // keep the middle angle in the unique range of the order
+            if order.is_proper() {
+                angles.y = angles.y.abs();
+            }
+
+            let m = Matrix3::from_euler(*order, &angles);
+            let back = m.to_euler(*order);
+            assert!((back.x - angles.x).abs() < 1e-4, "{:?} {:?} {:?}", order, angles, back);
+            assert!((back.y - angles.y).abs() < 1e-4, "{:?} {:?} {:?}", order, angles, back);
+            assert!((back.z - angles.z).abs() < 1e-4, "{:?} {:?} {:?}", order, angles, back);
+
+            let m4 = Matrix4::from_euler(*order, &angles);
+            assert_matrix3_near(&Matrix3::from_matrix4(&m4), &m);
+            let back = m4.to_euler(*order);
+            assert!((back.y - angles.y).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn test_euler_gimbal_lock() {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    for order in EulerOrder::ALL.iter() {
+        let locked = if order.is_proper() { [0., PI] } else { [FRAC_PI_2, -FRAC_PI_2] };
+        for b in locked.iter() {
+            let m = Matrix3::from_euler(*order, &Vector3::from(0.4, *b, -0.9));
+            let back = m.to_euler(*order);
+
+            // the angles are ambiguous, but must describe the same rotation
+            assert!(back.x.is_finite() && back.y.is_finite() && back.z.is_finite());
+            assert_eq!(back.z, 0.);
+            assert_matrix3_near(&Matrix3::from_euler(*order, &back), &m);
+        }
+    }
+}
+
+#[test]
+fn test_euler_matches_quaternion() {
+    let q = Quaternion::from_euler(0.3, -1.1, 2.0);
+    let m = Matrix3::from_euler(EulerOrder::XYZ, &Vector3::from(0.3, -1.1, 2.0));
+    assert_matrix3_near(&q.to_matrix3(), &m);
+}
@@ -0,0 +1,199 @@
+use super::{Frustum, DepthRange, Matrix4, Vector3};
+use super::geometry::{Aabb, Containment, Plane, Sphere};
+
+impl Frustum {
+
+    /// Extracts the frustum planes from a projection (times view, times model) matrix with
+    /// the OpenGL depth range `-1..1`, i.e. any matrix built with `projection_mut`, `frustum_mut`
+    /// or `orthographic_mut`. The planes are in the space the matrix maps from, so with
+    /// `projection * view` they are in world space.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Frustum, Matrix4, Vector3};
+    /// use js_linalg::geometry::{Containment, Sphere};
+    /// let mut projection = Matrix4::new();
+    /// projection.projection_mut(45., 800., 600., 0.1, 100.);
+    /// let view = Matrix4::look_at(&Vector3::from_i32(0, -5, 0), &Vector3::new(), &Vector3::new_z_up());
+    ///
+    /// let frustum = Frustum::from_matrix4(&(&projection * &view));
+    /// let sphere = Sphere::new(&Vector3::new(), 1.);
+    /// assert_eq!(frustum.test_sphere(&sphere), Containment::Inside);
+    /// ```
+    pub fn from_matrix4(m:&Matrix4) -> Frustum {
+        Frustum::from_matrix4_depth_range(m, DepthRange::NegativeOneToOne)
+    }
+
+    /// Extracts the frustum planes from a projection matrix producing the provided depth range,
+    /// see `Matrix4::depth_range_mut`. For reversed-Z projections the near and the far plane
+    /// swap places. The far plane of infinite projections has a zero normal and never culls.
+    pub fn from_matrix4_depth_range(m:&Matrix4, range:DepthRange) -> Frustum {
+        // Gribb/Hartmann: each plane is a combination of the rows of the matrix
+        let row0 = [m.m_0_0, m.m_1_0, m.m_2_0, m.m_3_0];
+        let row1 = [m.m_0_1, m.m_1_1, m.m_2_1, m.m_3_1];
+        let row2 = [m.m_0_2, m.m_1_2, m.m_2_2, m.m_3_2];
+        let row3 = [m.m_0_3, m.m_1_3, m.m_2_3, m.m_3_3];
+
+        let plane = |a:&[f32; 4], sign:f32| {
+            let mut p = Plane::from_coefficients(
+                row3[0] + sign * a[0],
+                row3[1] + sign * a[1],
+                row3[2] + sign * a[2],
+                row3[3] + sign * a[3]);
+            // degenerate planes, e.g. the far plane at infinity, must not cull anything
+            if p.normal.length_squared() == 0.0 {
+                p.d = p.d.abs();
+            }
+            p
+        };
+
+        let near = match range {
+            DepthRange::NegativeOneToOne => plane(&row2, 1.0),
+            DepthRange::ZeroToOne => Plane::from_coefficients(row2[0], row2[1], row2[2], row2[3]),
+        };
+
+        Frustum {
+            planes: [
+                plane(&row0, 1.0),
+                plane(&row0, -1.0),
+                plane(&row1, 1.0),
+                plane(&row1, -1.0),
+                near,
+                plane(&row2, -1.0),
+            ]
+        }
+    }
+
+    /// Tests whether the provided point is inside the frustum. Points on a plane count as inside.
+    /// Returns either `Containment::Inside` or `Containment::Outside`.
+    pub fn test_point(&self, point:&Vector3) -> Containment {
+        if self.planes.iter().any(|p| p.signed_distance(point) < 0.0) {
+            Containment::Outside
+        } else {
+            Containment::Inside
+        }
+    }
+
+    /// Tests the provided sphere against the frustum. Spheres close to the corners of the frustum
+    /// may be reported as intersecting although they are outside, which is fine for culling.
+    pub fn test_sphere(&self, sphere:&Sphere) -> Containment {
+        let mut result = Containment::Inside;
+        for p in self.planes.iter() {
+            let d = p.signed_distance(&sphere.center);
+            if d < -sphere.radius {
+                return Containment::Outside;
+            }
+            if d < sphere.radius {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    /// Tests the provided axis-aligned box against the frustum by checking the corners furthest
+    /// along and against each plane normal. Like `test_sphere`, boxes close to the corners of the
+    /// frustum may be reported as intersecting although they are outside.
+    pub fn test_aabb(&self, aabb:&Aabb) -> Containment {
+        let mut result = Containment::Inside;
+        for p in self.planes.iter() {
+            let n = &p.normal;
+            let furthest = Vector3::from(
+                if n.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if n.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if n.z >= 0.0 { aabb.max.z } else { aabb.min.z });
+            if p.signed_distance(&furthest) < 0.0 {
+                return Containment::Outside;
+            }
+            let nearest = Vector3::from(
+                if n.x >= 0.0 { aabb.min.x } else { aabb.max.x },
+                if n.y >= 0.0 { aabb.min.y } else { aabb.max.y },
+                if n.z >= 0.0 { aabb.min.z } else { aabb.max.z });
+            if p.signed_distance(&nearest) < 0.0 {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    /// Tests a whole slice of points and writes the results to `out`, e.g. to reuse one buffer
+    /// every frame. Panics if `out` is shorter than `points`.
+    pub fn test_points(&self, points:&[Vector3], out:&mut [Containment]) {
+        for (point, o) in points.iter().zip(out[..points.len()].iter_mut()) {
+            *o = self.test_point(point);
+        }
+    }
+
+    /// Tests a whole slice of spheres and writes the results to `out`. Panics if `out` is shorter
+    /// than `spheres`.
+    pub fn test_spheres(&self, spheres:&[Sphere], out:&mut [Containment]) {
+        for (sphere, o) in spheres.iter().zip(out[..spheres.len()].iter_mut()) {
+            *o = self.test_sphere(sphere);
+        }
+    }
+
+    /// Tests a whole slice of boxes and writes the results to `out`. Panics if `out` is shorter
+    /// than `aabbs`.
+    pub fn test_aabbs(&self, aabbs:&[Aabb], out:&mut [Containment]) {
+        for (aabb, o) in aabbs.iter().zip(out[..aabbs.len()].iter_mut()) {
+            *o = self.test_aabb(aabb);
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_frustum(range:DepthRange, reversed:bool, infinite:bool) -> Frustum {
+    let mut projection = Matrix4::new();
+    if infinite {
+        projection.projection_infinite_mut(90., 100., 100., 1.);
+    } else {
+        projection.projection_mut(90., 100., 100., 1., 10.);
+    }
+    projection.depth_range_mut(range, reversed);
+    // camera at (0, 0, 5) looking down -z
+    let view = Matrix4::look_at(&Vector3::from_i32(0, 0, 5), &Vector3::new(), &Vector3::from_i32(0, 1, 0));
+    Frustum::from_matrix4_depth_range(&(&projection * &view), range)
+}
+
+#[test]
+fn test_frustum_points_spheres_aabbs() {
+    use Containment::*;
+
+    let frustums = [
+        test_frustum(DepthRange::NegativeOneToOne, false, false),
+        test_frustum(DepthRange::ZeroToOne, false, false),
+        test_frustum(DepthRange::ZeroToOne, true, false),
+    ];
+
+    for f in frustums.iter() {
+        for p in f.planes.iter() {
+            assert!((p.normal.length() - 1.).abs() < 1e-5);
+        }
+
+        // view space z from -1 (near) to -10 (far), i.e. world z from 4 to -5
+        assert_eq!(f.test_point(&Vector3::from_i32(0, 0, 0)), Inside);
+        assert_eq!(f.test_point(&Vector3::from(0., 0., 4.5)), Outside);
+        assert_eq!(f.test_point(&Vector3::from(0., 0., -6.)), Outside);
+        assert_eq!(f.test_point(&Vector3::from(6., 0., 0.)), Outside);
+
+        assert_eq!(f.test_sphere(&Sphere::new(&Vector3::new(), 1.)), Inside);
+        assert_eq!(f.test_sphere(&Sphere::new(&Vector3::from(0., 0., -5.), 1.)), Intersecting);
+        assert_eq!(f.test_sphere(&Sphere::new(&Vector3::from(20., 0., 0.), 1.)), Outside);
+
+        let aabb = |x:f32, y:f32, z:f32| Aabb::new(&Vector3::from(x - 0.5, y - 0.5, z - 0.5), &Vector3::from(x + 0.5, y + 0.5, z + 0.5));
+        assert_eq!(f.test_aabb(&aabb(0., 0., 0.)), Inside);
+        assert_eq!(f.test_aabb(&aabb(5., 0., 0.)), Intersecting);
+        assert_eq!(f.test_aabb(&aabb(0., 20., 0.)), Outside);
+
+        let spheres = [Sphere::new(&Vector3::new(), 1.), Sphere::new(&Vector3::from(0., 0., 20.), 1.)];
+        let mut out = [Intersecting; 2];
+        f.test_spheres(&spheres, &mut out);
+        assert_eq!(out, [Inside, Outside]);
+    }
+}
+
+#[test]
+fn test_frustum_infinite_far_plane() {
+    let f = test_frustum(DepthRange::ZeroToOne, true, true);
+    assert_eq!(f.test_point(&Vector3::from(0., 0., -1000.)), Containment::Inside);
+    assert_eq!(f.test_point(&Vector3::from(0., 0., 4.5)), Containment::Outside);
+}
@@ -0,0 +1,897 @@
+//! Bounding volumes and other simple shapes, e.g. for culling with `Frustum` and picking
+//! with `Ray`.
+//!
+//! All shapes can be transformed by a `Matrix4` (`transform_mut`), tell the point within
+//! them closest to a provided point (`closest_point`) and, where meaningful, test containment
+//! (`contains_point`, ...) and be merged into one volume enclosing both (`merge`).
+//!
+//! # Example
+//! ```
+//! use js_linalg::{Matrix4, Vector3};
+//! use js_linalg::geometry::Aabb;
+//! let mut bounds = Aabb::from_points(&[Vector3::from_i32(-1, -1, -1), Vector3::from_i32(1, 1, 1)]);
+//! let mut m = Matrix4::new();
+//! m.translation_mut(10., 0., 0.);
+//! bounds.transform_mut(&m);
+//! assert!(bounds.contains_point(&Vector3::from_i32(10, 0, 0)));
+//! ```
+
+use super::{Matrix4, Vector3, Error};
+
+/// A plane given by its `normal` and the distance `d`, i.e. all points `p` with
+/// `normal.dot(p) + d == 0`. The side the normal points to is the positive side.
+#[derive(Debug, Copy, Clone)]
+pub struct Plane {
+    /// The normal of the plane, usually of unit length.
+    pub normal: Vector3,
+
+    /// The negative distance of the plane from the origin along the normal.
+    pub d: f32
+}
+
+/// A sphere given by its center and radius.
+#[derive(Debug, Copy, Clone)]
+pub struct Sphere {
+    pub center: Vector3,
+    pub radius: f32
+}
+
+/// An axis-aligned bounding box given by its minimum and maximum corner.
+#[derive(Debug, Copy, Clone)]
+pub struct Aabb {
+    pub min: Vector3,
+    pub max: Vector3
+}
+
+/// An oriented bounding box given by its center, three orthonormal axes and the half size
+/// along each axis.
+#[derive(Debug, Copy, Clone)]
+pub struct Obb {
+    pub center: Vector3,
+
+    /// The local `x`, `y` and `z` axes of the box, of unit length and orthogonal to each other.
+    pub axes: [Vector3; 3],
+
+    /// The half size of the box along each of its `axes`.
+    pub half_extents: Vector3
+}
+
+/// A triangle given by its three corners. Counter-clockwise corners are front facing, see
+/// `Triangle::normal`.
+#[derive(Debug, Copy, Clone)]
+pub struct Triangle {
+    pub a: Vector3,
+    pub b: Vector3,
+    pub c: Vector3
+}
+
+/// A line segment between two points.
+#[derive(Debug, Copy, Clone)]
+pub struct Segment {
+    pub start: Vector3,
+    pub end: Vector3
+}
+
+/// A capsule, i.e. all points within `radius` of a line segment.
+#[derive(Debug, Copy, Clone)]
+pub struct Capsule {
+    pub segment: Segment,
+    pub radius: f32
+}
+
+/// The result of testing a shape against a volume such as a `Frustum`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Containment {
+    /// The shape lies completely inside the volume.
+    Inside,
+
+    /// The shape lies completely outside the volume.
+    Outside,
+
+    /// The shape is partially inside the volume.
+    Intersecting
+}
+
+fn transform_point(m:&Matrix4, p:&Vector3) -> Vector3 {
+    let mut v = *p;
+    m.mult_to_vector3(&mut v, 1.0);
+    v
+}
+
+/// The largest factor the matrix scales a length with, assuming no shear.
+fn max_scale(m:&Matrix4) -> f32 {
+    let x = Vector3::from(m.m_0_0, m.m_0_1, m.m_0_2).length_squared();
+    let y = Vector3::from(m.m_1_0, m.m_1_1, m.m_1_2).length_squared();
+    let z = Vector3::from(m.m_2_0, m.m_2_1, m.m_2_2).length_squared();
+    x.max(y).max(z).sqrt()
+}
+
+fn min_vector3(a:&Vector3, b:&Vector3) -> Vector3 {
+    Vector3::from(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))
+}
+
+fn max_vector3(a:&Vector3, b:&Vector3) -> Vector3 {
+    Vector3::from(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+}
+
+impl Plane {
+
+    /// Creates a new `Plane` from its normal and the distance `d`. The normal is copied as is,
+    /// i.e. it is not normalized.
+    pub fn new(normal:&Vector3, d:f32) -> Plane {
+        Plane {normal: *normal, d}
+    }
+
+    /// Creates a new `Plane` through `point` with the provided (unit) normal.
+    pub fn from_point_normal(point:&Vector3, normal:&Vector3) -> Plane {
+        Plane {normal: *normal, d: -normal.dot(point)}
+    }
+
+    /// Creates a new `Plane` through three points. The normal is normalized and points to the
+    /// side from which the points appear counter-clockwise. Collinear points yield a NaN plane,
+    /// see `try_from_points`.
+    pub fn from_points(a:&Vector3, b:&Vector3, c:&Vector3) -> Plane {
+        let normal = (b - a).cross(&(c - a)).normalize();
+        Plane::from_point_normal(a, &normal)
+    }
+
+    /// Like `from_points`, but returns `Error::ZeroLength` if the points are collinear (or
+    /// coincide), i.e. if they do not span a plane.
+    pub fn try_from_points(a:&Vector3, b:&Vector3, c:&Vector3) -> Result<Plane, Error> {
+        let normal = (b - a).cross(&(c - a)).try_normalize()?;
+        Ok(Plane::from_point_normal(a, &normal))
+    }
+
+    /// Creates a new `Plane` from the coefficients of the plane equation `a*x + b*y + c*z + d = 0`
+    /// and normalizes it. A plane with a zero normal is kept as is.
+    pub fn from_coefficients(a:f32, b:f32, c:f32, d:f32) -> Plane {
+        let mut p = Plane {normal: Vector3::from(a, b, c), d};
+        p.normalize_mut();
+        p
+    }
+
+    /// In-place scaling of the plane equation such that the normal is of unit length. A plane
+    /// with a zero normal is left unchanged.
+    pub fn normalize_mut(&mut self) -> &mut Plane {
+        let l = self.normal.length();
+        if l > 0.0 {
+            self.normal *= 1.0 / l;
+            self.d /= l;
+        }
+        self
+    }
+
+    /// Returns the signed distance of `point` to this plane, positive on the side the normal
+    /// points to. The result is only a true distance if the normal is of unit length.
+    pub fn signed_distance(&self, point:&Vector3) -> f32 {
+        self.normal.dot(point) + self.d
+    }
+
+    /// Returns the projection of `point` onto this plane. The normal must be of unit length.
+    pub fn closest_point(&self, point:&Vector3) -> Vector3 {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    /// In-place transformation of this plane by the provided matrix, which must be invertible.
+    /// The plane equation is multiplied with the inverse transpose and normalized again.
+    /// A singular matrix yields a NaN plane, see `try_transform_mut`.
+    pub fn transform_mut(&mut self, m:&Matrix4) -> &mut Plane {
+        self.transform_inverse_transpose_mut(&m.inverse())
+    }
+
+    /// Like `transform_mut`, but leaves this plane untouched and returns `Error::SingularMatrix`
+    /// if the matrix cannot be inverted.
+    pub fn try_transform_mut(&mut self, m:&Matrix4) -> Result<&mut Plane, Error> {
+        let i = m.try_inverse()?;
+        Ok(self.transform_inverse_transpose_mut(&i))
+    }
+
+    fn transform_inverse_transpose_mut(&mut self, i:&Matrix4) -> &mut Plane {
+        let (n, d) = (self.normal, self.d);
+
+        self.normal.x = n.x * i.m_0_0 + n.y * i.m_0_1 + n.z * i.m_0_2 + d * i.m_0_3;
+        self.normal.y = n.x * i.m_1_0 + n.y * i.m_1_1 + n.z * i.m_1_2 + d * i.m_1_3;
+        self.normal.z = n.x * i.m_2_0 + n.y * i.m_2_1 + n.z * i.m_2_2 + d * i.m_2_3;
+        self.d        = n.x * i.m_3_0 + n.y * i.m_3_1 + n.z * i.m_3_2 + d * i.m_3_3;
+
+        self.normalize_mut()
+    }
+}
+
+impl Sphere {
+
+    /// Creates a new `Sphere` from its center and radius.
+    pub fn new(center:&Vector3, radius:f32) -> Sphere {
+        Sphere {center: *center, radius}
+    }
+
+    /// Creates the smallest `Sphere` around the provided box.
+    pub fn from_aabb(aabb:&Aabb) -> Sphere {
+        Sphere {center: aabb.center(), radius: aabb.extents().length()}
+    }
+
+    /// Returns `true` if the provided point is inside or on this sphere.
+    pub fn contains_point(&self, point:&Vector3) -> bool {
+        self.center.distance_squared(point) <= self.radius * self.radius
+    }
+
+    /// Returns `true` if the provided sphere is completely inside this sphere.
+    pub fn contains_sphere(&self, sphere:&Sphere) -> bool {
+        self.center.distance(&sphere.center) + sphere.radius <= self.radius
+    }
+
+    /// Returns `true` if this and the provided sphere overlap.
+    pub fn intersects_sphere(&self, sphere:&Sphere) -> bool {
+        let r = self.radius + sphere.radius;
+        self.center.distance_squared(&sphere.center) <= r * r
+    }
+
+    /// Returns the point inside or on this sphere closest to `point`, which is `point` itself
+    /// if it is inside.
+    pub fn closest_point(&self, point:&Vector3) -> Vector3 {
+        let d = point - self.center;
+        let l = d.length();
+        if l <= self.radius {
+            return *point;
+        }
+        self.center + d * (self.radius / l)
+    }
+
+    /// In-place growth of this sphere such that it also encloses the provided one.
+    pub fn merge_mut(&mut self, sphere:&Sphere) -> &mut Sphere {
+        let d = sphere.center - self.center;
+        let l = d.length();
+
+        if l + sphere.radius <= self.radius {
+            return self;
+        }
+        if l + self.radius <= sphere.radius {
+            self.center = sphere.center;
+            self.radius = sphere.radius;
+            return self;
+        }
+
+        let radius = (l + self.radius + sphere.radius) * 0.5;
+        self.center += d * ((radius - self.radius) / l);
+        self.radius = radius;
+
+        self
+    }
+
+    /// Returns the smallest sphere enclosing this and the provided sphere.
+    pub fn merge(&self, sphere:&Sphere) -> Sphere {
+        let mut s = *self;
+        s.merge_mut(sphere);
+        s
+    }
+
+    /// In-place transformation of this sphere by the provided matrix. The radius is scaled by the
+    /// largest scale of the matrix, so the result still encloses the transformed sphere for
+    /// non-uniform scaling.
+    pub fn transform_mut(&mut self, m:&Matrix4) -> &mut Sphere {
+        m.mult_to_vector3(&mut self.center, 1.0);
+        self.radius *= max_scale(m);
+        self
+    }
+
+    /// Returns the axis-aligned box enclosing this sphere.
+    pub fn to_aabb(&self) -> Aabb {
+        let r = Vector3::from(self.radius, self.radius, self.radius);
+        Aabb {min: self.center - r, max: self.center + r}
+    }
+}
+
+impl Aabb {
+
+    /// Creates a new `Aabb` from its minimum and maximum corner.
+    pub fn new(min:&Vector3, max:&Vector3) -> Aabb {
+        Aabb {min: *min, max: *max}
+    }
+
+    /// Creates an empty `Aabb` with `min` at infinity and `max` at negative infinity, which
+    /// becomes a valid box with the first `expand_mut` or `merge_mut`.
+    pub fn empty() -> Aabb {
+        Aabb {
+            min: Vector3::from(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Vector3::from(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    /// Creates the smallest `Aabb` enclosing all provided points. Returns an empty box for an
+    /// empty slice, see `empty`.
+    pub fn from_points(points:&[Vector3]) -> Aabb {
+        let mut aabb = Aabb::empty();
+        for p in points {
+            aabb.expand_mut(p);
+        }
+        aabb
+    }
+
+    /// Returns `true` if `min` is larger than `max` in any dimension, e.g. for `Aabb::empty()`.
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    /// Returns the center of this box.
+    pub fn center(&self) -> Vector3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns the half size of this box along each axis.
+    pub fn extents(&self) -> Vector3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Returns the surface area of this box, e.g. for the surface area heuristic of a bounding
+    /// volume hierarchy.
+    pub fn surface_area(&self) -> f32 {
+        let d = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    /// In-place growth of this box such that it also encloses the provided point.
+    pub fn expand_mut(&mut self, point:&Vector3) -> &mut Aabb {
+        self.min = min_vector3(&self.min, point);
+        self.max = max_vector3(&self.max, point);
+        self
+    }
+
+    /// In-place growth of this box such that it also encloses the provided one.
+    pub fn merge_mut(&mut self, aabb:&Aabb) -> &mut Aabb {
+        self.min = min_vector3(&self.min, &aabb.min);
+        self.max = max_vector3(&self.max, &aabb.max);
+        self
+    }
+
+    /// Returns the smallest box enclosing this and the provided box.
+    pub fn merge(&self, aabb:&Aabb) -> Aabb {
+        let mut a = *self;
+        a.merge_mut(aabb);
+        a
+    }
+
+    /// Returns `true` if the provided point is inside or on this box.
+    pub fn contains_point(&self, point:&Vector3) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x &&
+        point.y >= self.min.y && point.y <= self.max.y &&
+        point.z >= self.min.z && point.z <= self.max.z
+    }
+
+    /// Returns `true` if the provided box is completely inside this box.
+    pub fn contains_aabb(&self, aabb:&Aabb) -> bool {
+        self.contains_point(&aabb.min) && self.contains_point(&aabb.max)
+    }
+
+    /// Returns `true` if this and the provided box overlap. Touching boxes overlap.
+    pub fn intersects_aabb(&self, aabb:&Aabb) -> bool {
+        self.min.x <= aabb.max.x && self.max.x >= aabb.min.x &&
+        self.min.y <= aabb.max.y && self.max.y >= aabb.min.y &&
+        self.min.z <= aabb.max.z && self.max.z >= aabb.min.z
+    }
+
+    /// Returns the point inside or on this box closest to `point`.
+    pub fn closest_point(&self, point:&Vector3) -> Vector3 {
+        max_vector3(&self.min, &min_vector3(&self.max, point))
+    }
+
+    /// In-place transformation of this box by the provided matrix. The result is the axis-aligned
+    /// box enclosing the transformed box, which is larger than necessary for rotations. An empty
+    /// box stays empty.
+    pub fn transform_mut(&mut self, m:&Matrix4) -> &mut Aabb {
+        if self.is_empty() {
+            return self;
+        }
+
+        // Arvo: the extents of the new box are the extents mapped through |M|
+        let c = transform_point(m, &self.center());
+        let e = self.extents();
+        let extents = Vector3::from(
+            m.m_0_0.abs() * e.x + m.m_1_0.abs() * e.y + m.m_2_0.abs() * e.z,
+            m.m_0_1.abs() * e.x + m.m_1_1.abs() * e.y + m.m_2_1.abs() * e.z,
+            m.m_0_2.abs() * e.x + m.m_1_2.abs() * e.y + m.m_2_2.abs() * e.z);
+
+        self.min = c - extents;
+        self.max = c + extents;
+
+        self
+    }
+}
+
+impl Obb {
+
+    /// Creates a new `Obb` from its center, orthonormal axes and half size along each axis.
+    pub fn new(center:&Vector3, axes:&[Vector3; 3], half_extents:&Vector3) -> Obb {
+        Obb {center: *center, axes: *axes, half_extents: *half_extents}
+    }
+
+    /// Creates a new `Obb` covering the same space as the provided box.
+    pub fn from_aabb(aabb:&Aabb) -> Obb {
+        Obb {
+            center: aabb.center(),
+            axes: [Vector3::from(1., 0., 0.), Vector3::from(0., 1., 0.), Vector3::from(0., 0., 1.)],
+            half_extents: aabb.extents(),
+        }
+    }
+
+    /// Returns the point in the local coordinates of this box, i.e. relative to the center and
+    /// along the axes.
+    pub fn to_local(&self, point:&Vector3) -> Vector3 {
+        let d = point - self.center;
+        Vector3::from(d.dot(&self.axes[0]), d.dot(&self.axes[1]), d.dot(&self.axes[2]))
+    }
+
+    /// Returns the point in world coordinates of a point in local coordinates, see `to_local`.
+    pub fn from_local(&self, local:&Vector3) -> Vector3 {
+        self.center + self.axes[0] * local.x + self.axes[1] * local.y + self.axes[2] * local.z
+    }
+
+    /// Returns `true` if the provided point is inside or on this box.
+    pub fn contains_point(&self, point:&Vector3) -> bool {
+        let l = self.to_local(point);
+        l.x.abs() <= self.half_extents.x && l.y.abs() <= self.half_extents.y && l.z.abs() <= self.half_extents.z
+    }
+
+    /// Returns the point inside or on this box closest to `point`.
+    pub fn closest_point(&self, point:&Vector3) -> Vector3 {
+        let l = self.to_local(point);
+        let h = &self.half_extents;
+        self.from_local(&Vector3::from(l.x.clamp(-h.x, h.x), l.y.clamp(-h.y, h.y), l.z.clamp(-h.z, h.z)))
+    }
+
+    /// Returns the eight corners of this box.
+    pub fn corners(&self) -> [Vector3; 8] {
+        let mut corners = [self.center; 8];
+        for (i, c) in corners.iter_mut().enumerate() {
+            let sx = if i & 1 == 0 { -1.0 } else { 1.0 };
+            let sy = if i & 2 == 0 { -1.0 } else { 1.0 };
+            let sz = if i & 4 == 0 { -1.0 } else { 1.0 };
+            *c = self.from_local(&Vector3::from(sx * self.half_extents.x, sy * self.half_extents.y, sz * self.half_extents.z));
+        }
+        corners
+    }
+
+    /// In-place transformation of this box by the provided matrix. The axes are re-normalized and
+    /// the half extents scaled accordingly. The result is exact for rotations, translations and
+    /// uniform scaling, non-uniform scaling of a rotated box would need a sheared box. An axis
+    /// collapsed by a singular matrix ends up NaN, see `try_transform_mut`.
+    pub fn transform_mut(&mut self, m:&Matrix4) -> &mut Obb {
+        m.mult_to_vector3(&mut self.center, 1.0);
+
+        let h = [self.half_extents.x, self.half_extents.y, self.half_extents.z];
+        let mut scaled = [0.0; 3];
+        for (i, axis) in self.axes.iter_mut().enumerate() {
+            m.mult_to_vector3(axis, 0.0);
+            let l = axis.length();
+            *axis *= 1.0 / l;
+            scaled[i] = h[i] * l;
+        }
+        self.half_extents.set(scaled[0], scaled[1], scaled[2]);
+
+        self
+    }
+
+    /// Like `transform_mut`, but leaves this box untouched and returns `Error::SingularMatrix`
+    /// if the matrix collapses one of the axes.
+    pub fn try_transform_mut(&mut self, m:&Matrix4) -> Result<&mut Obb, Error> {
+        for axis in &self.axes {
+            let mut a = *axis;
+            m.mult_to_vector3(&mut a, 0.0);
+            let l = a.length();
+            if !(l > 0.0 && l.is_finite()) {
+                return Err(Error::SingularMatrix);
+            }
+        }
+        Ok(self.transform_mut(m))
+    }
+
+    /// Returns the axis-aligned box enclosing this box.
+    pub fn to_aabb(&self) -> Aabb {
+        let h = &self.half_extents;
+        let a = &self.axes;
+        let extents = Vector3::from(
+            a[0].x.abs() * h.x + a[1].x.abs() * h.y + a[2].x.abs() * h.z,
+            a[0].y.abs() * h.x + a[1].y.abs() * h.y + a[2].y.abs() * h.z,
+            a[0].z.abs() * h.x + a[1].z.abs() * h.y + a[2].z.abs() * h.z);
+        Aabb {min: self.center - extents, max: self.center + extents}
+    }
+}
+
+impl Triangle {
+
+    /// Creates a new `Triangle` from its three corners.
+    pub fn new(a:&Vector3, b:&Vector3, c:&Vector3) -> Triangle {
+        Triangle {a: *a, b: *b, c: *c}
+    }
+
+    /// Returns the unit normal, pointing to the side from which the corners appear counter-clockwise.
+    /// A degenerate (zero area) triangle yields a NaN normal, see `try_normal`.
+    pub fn normal(&self) -> Vector3 {
+        (self.b - self.a).cross(&(self.c - self.a)).normalize()
+    }
+
+    /// Like `normal`, but returns `Error::ZeroLength` for a degenerate triangle whose corners are
+    /// collinear.
+    pub fn try_normal(&self) -> Result<Vector3, Error> {
+        (self.b - self.a).cross(&(self.c - self.a)).try_normalize()
+    }
+
+    /// Returns the area of this triangle.
+    pub fn area(&self) -> f32 {
+        (self.b - self.a).cross(&(self.c - self.a)).length() * 0.5
+    }
+
+    /// Returns the centroid of this triangle.
+    pub fn centroid(&self) -> Vector3 {
+        (self.a + self.b + self.c) * (1.0 / 3.0)
+    }
+
+    /// Returns the plane the triangle lies in, see `normal` and `Plane::try_from_points`.
+    pub fn plane(&self) -> Plane {
+        Plane::from_points(&self.a, &self.b, &self.c)
+    }
+
+    /// Returns the barycentric coordinates `(u, v, w)` of the provided point projected onto the
+    /// plane of this triangle, such that the point is `u * a + v * b + w * c`.
+    pub fn barycentric(&self, point:&Vector3) -> Vector3 {
+        let v0 = self.b - self.a;
+        let v1 = self.c - self.a;
+        let v2 = point - self.a;
+
+        let d00 = v0.dot(&v0);
+        let d01 = v0.dot(&v1);
+        let d11 = v1.dot(&v1);
+        let d20 = v2.dot(&v0);
+        let d21 = v2.dot(&v1);
+        let denom = d00 * d11 - d01 * d01;
+
+        let v = (d11 * d20 - d01 * d21) / denom;
+        let w = (d00 * d21 - d01 * d20) / denom;
+        Vector3::from(1.0 - v - w, v, w)
+    }
+
+    /// Returns the point on this triangle (including its inside) closest to `point`.
+    pub fn closest_point(&self, point:&Vector3) -> Vector3 {
+        // Ericson, Real-Time Collision Detection 5.1.5: find the Voronoi region of the point
+        let (a, b, c) = (&self.a, &self.b, &self.c);
+        let ab = b - a;
+        let ac = c - a;
+
+        let ap = point - a;
+        let d1 = ab.dot(&ap);
+        let d2 = ac.dot(&ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return *a;
+        }
+
+        let bp = point - b;
+        let d3 = ab.dot(&bp);
+        let d4 = ac.dot(&bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return *b;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = point - c;
+        let d5 = ab.dot(&cp);
+        let d6 = ac.dot(&cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return *c;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        let denom = 1.0 / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+
+    /// In-place transformation of the corners of this triangle by the provided matrix.
+    pub fn transform_mut(&mut self, m:&Matrix4) -> &mut Triangle {
+        m.mult_to_vector3(&mut self.a, 1.0);
+        m.mult_to_vector3(&mut self.b, 1.0);
+        m.mult_to_vector3(&mut self.c, 1.0);
+        self
+    }
+
+    /// Returns the axis-aligned box enclosing this triangle.
+    pub fn to_aabb(&self) -> Aabb {
+        Aabb::from_points(&[self.a, self.b, self.c])
+    }
+}
+
+impl Segment {
+
+    /// Creates a new `Segment` from its two end points.
+    pub fn new(start:&Vector3, end:&Vector3) -> Segment {
+        Segment {start: *start, end: *end}
+    }
+
+    /// Returns the length of this segment.
+    pub fn length(&self) -> f32 {
+        self.start.distance(&self.end)
+    }
+
+    /// Returns the point at `t`, which is `start` for `t = 0` and `end` for `t = 1`.
+    pub fn point_at(&self, t:f32) -> Vector3 {
+        self.start + (self.end - self.start) * t
+    }
+
+    /// Returns the parameter `t` (see `point_at`) of the point on this segment closest to `point`.
+    pub fn closest_parameter(&self, point:&Vector3) -> f32 {
+        let d = self.end - self.start;
+        let l = d.length_squared();
+        if l == 0.0 {
+            return 0.0;
+        }
+        ((point - self.start).dot(&d) / l).clamp(0.0, 1.0)
+    }
+
+    /// Returns the point on this segment closest to `point`.
+    pub fn closest_point(&self, point:&Vector3) -> Vector3 {
+        self.point_at(self.closest_parameter(point))
+    }
+
+    /// Returns the closest points between this and the provided segment, the first one on this
+    /// segment and the second one on `segment`.
+    pub fn closest_points_segment(&self, segment:&Segment) -> (Vector3, Vector3) {
+        // Ericson, Real-Time Collision Detection 5.1.9
+        let d1 = self.end - self.start;
+        let d2 = segment.end - segment.start;
+        let r = self.start - segment.start;
+        let a = d1.length_squared();
+        let e = d2.length_squared();
+        let f = d2.dot(&r);
+
+        let (s, t) = if a == 0.0 && e == 0.0 {
+            (0.0, 0.0)
+        } else if a == 0.0 {
+            (0.0, (f / e).clamp(0.0, 1.0))
+        } else {
+            let c = d1.dot(&r);
+            if e == 0.0 {
+                ((-c / a).clamp(0.0, 1.0), 0.0)
+            } else {
+                let b = d1.dot(&d2);
+                let denom = a * e - b * b;
+                let mut s = if denom != 0.0 { ((b * f - c * e) / denom).clamp(0.0, 1.0) } else { 0.0 };
+                let mut t = (b * s + f) / e;
+                if t < 0.0 {
+                    t = 0.0;
+                    s = (-c / a).clamp(0.0, 1.0);
+                } else if t > 1.0 {
+                    t = 1.0;
+                    s = ((b - c) / a).clamp(0.0, 1.0);
+                }
+                (s, t)
+            }
+        };
+
+        (self.point_at(s), segment.point_at(t))
+    }
+
+    /// In-place transformation of the end points of this segment by the provided matrix.
+    pub fn transform_mut(&mut self, m:&Matrix4) -> &mut Segment {
+        m.mult_to_vector3(&mut self.start, 1.0);
+        m.mult_to_vector3(&mut self.end, 1.0);
+        self
+    }
+
+    /// Returns the axis-aligned box enclosing this segment.
+    pub fn to_aabb(&self) -> Aabb {
+        Aabb {min: min_vector3(&self.start, &self.end), max: max_vector3(&self.start, &self.end)}
+    }
+}
+
+impl Capsule {
+
+    /// Creates a new `Capsule` around the segment from `start` to `end`.
+    pub fn new(start:&Vector3, end:&Vector3, radius:f32) -> Capsule {
+        Capsule {segment: Segment::new(start, end), radius}
+    }
+
+    /// Returns `true` if the provided point is inside or on this capsule.
+    pub fn contains_point(&self, point:&Vector3) -> bool {
+        self.segment.closest_point(point).distance_squared(point) <= self.radius * self.radius
+    }
+
+    /// Returns `true` if this and the provided capsule overlap.
+    pub fn intersects_capsule(&self, capsule:&Capsule) -> bool {
+        let (a, b) = self.segment.closest_points_segment(&capsule.segment);
+        let r = self.radius + capsule.radius;
+        a.distance_squared(&b) <= r * r
+    }
+
+    /// Returns the point inside or on this capsule closest to `point`, which is `point` itself
+    /// if it is inside.
+    pub fn closest_point(&self, point:&Vector3) -> Vector3 {
+        Sphere::new(&self.segment.closest_point(point), self.radius).closest_point(point)
+    }
+
+    /// In-place transformation of this capsule by the provided matrix. Like for `Sphere`, the
+    /// radius is scaled by the largest scale of the matrix.
+    pub fn transform_mut(&mut self, m:&Matrix4) -> &mut Capsule {
+        self.segment.transform_mut(m);
+        self.radius *= max_scale(m);
+        self
+    }
+
+    /// Returns the axis-aligned box enclosing this capsule.
+    pub fn to_aabb(&self) -> Aabb {
+        let r = Vector3::from(self.radius, self.radius, self.radius);
+        let a = self.segment.to_aabb();
+        Aabb {min: a.min - r, max: a.max + r}
+    }
+}
+
+#[cfg(test)]
+fn assert_vector3_near(a:&Vector3, b:&Vector3) {
+    assert!(a.distance(b) < 1e-5, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn test_plane_signed_distance() {
+    let p = Plane::from_coefficients(0., 2., 0., -4.);
+    assert_eq!(p.normal.y, 1.);
+    assert_eq!(p.signed_distance(&Vector3::from_i32(5, 3, 1)), 1.);
+    assert_eq!(p.signed_distance(&Vector3::new()), -2.);
+
+    let p = Plane::from_point_normal(&Vector3::from_i32(0, 0, 2), &Vector3::new_z_up());
+    assert_eq!(p.signed_distance(&Vector3::from_i32(1, 1, 1)), -1.);
+    assert_vector3_near(&p.closest_point(&Vector3::from_i32(1, 1, 1)), &Vector3::from_i32(1, 1, 2));
+}
+
+#[test]
+fn test_plane_transform() {
+    let mut p = Plane::from_point_normal(&Vector3::from_i32(0, 0, 2), &Vector3::new_z_up());
+    let mut m = Matrix4::new();
+    m.translation_mut(0., 0., 3.);
+    m.mult_mut(Matrix4::new().rotation_quaternion_mut(&super::Quaternion::from_axis_angle(&Vector3::from_i32(1, 0, 0), std::f32::consts::FRAC_PI_2)));
+    p.transform_mut(&m);
+
+    // the plane z = 2 rotated about x becomes y = -2, then moved up
+    assert_vector3_near(&p.normal, &Vector3::from_i32(0, -1, 0));
+    assert!(p.signed_distance(&Vector3::from_i32(7, -2, 9)).abs() < 1e-5);
+}
+
+#[test]
+fn test_sphere_merge_and_transform() {
+    let a = Sphere::new(&Vector3::new(), 1.);
+    let b = Sphere::new(&Vector3::from_i32(4, 0, 0), 1.);
+    let m = a.merge(&b);
+    assert_vector3_near(&m.center, &Vector3::from_i32(2, 0, 0));
+    assert_eq!(m.radius, 3.);
+    assert!(m.contains_sphere(&a) && m.contains_sphere(&b));
+    assert!(!a.intersects_sphere(&b));
+
+    let inner = Sphere::new(&Vector3::from(0.5, 0., 0.), 0.2);
+    assert_eq!(a.merge(&inner).radius, 1.);
+    assert_eq!(inner.merge(&a).radius, 1.);
+
+    assert_vector3_near(&a.closest_point(&Vector3::from_i32(0, 3, 0)), &Vector3::from_i32(0, 1, 0));
+
+    let mut s = a;
+    let mut t = Matrix4::new();
+    t.scale_mut(1., 3., 2.);
+    s.transform_mut(&t);
+    assert_eq!(s.radius, 3.);
+}
+
+#[test]
+fn test_aabb() {
+    let mut a = Aabb::empty();
+    assert!(a.is_empty());
+    a.expand_mut(&Vector3::from_i32(1, 2, 3)).expand_mut(&Vector3::from_i32(-1, 0, 1));
+    assert!(!a.is_empty());
+    assert_vector3_near(&a.center(), &Vector3::from_i32(0, 1, 2));
+    assert_eq!(a.surface_area(), 2. * (4. + 4. + 4.));
+
+    let b = Aabb::new(&Vector3::from_i32(0, 0, 0), &Vector3::from_i32(5, 5, 5));
+    assert!(a.intersects_aabb(&b));
+    assert!(!b.contains_aabb(&a));
+    assert!(a.merge(&b).contains_aabb(&a));
+    assert_vector3_near(&b.closest_point(&Vector3::from_i32(-3, 2, 9)), &Vector3::from_i32(0, 2, 5));
+
+    // rotating a unit cube by 45 degrees around z widens it by sqrt(2)
+    let mut c = Aabb::new(&Vector3::from_i32(-1, -1, -1), &Vector3::from_i32(1, 1, 1));
+    let mut r = Matrix4::new();
+    r.rotation_mut(std::f32::consts::FRAC_PI_4, &Vector3::new_z_up());
+    c.transform_mut(&r);
+    assert!((c.max.x - 2f32.sqrt()).abs() < 1e-5);
+    assert!((c.max.z - 1.).abs() < 1e-5);
+}
+
+#[test]
+fn test_obb() {
+    let mut o = Obb::from_aabb(&Aabb::new(&Vector3::from_i32(-2, -1, -1), &Vector3::from_i32(2, 1, 1)));
+    let mut m = Matrix4::new();
+    m.translation_mut(10., 0., 0.);
+    let mut r = Matrix4::new();
+    r.rotation_mut(std::f32::consts::FRAC_PI_2, &Vector3::new_z_up());
+    m.mult_mut(&r);
+    o.transform_mut(&m);
+
+    // the long side now points along y
+    assert!(o.contains_point(&Vector3::from(10., 1.9, 0.)));
+    assert!(!o.contains_point(&Vector3::from(11.9, 0., 0.)));
+    assert_vector3_near(&o.closest_point(&Vector3::from_i32(10, 5, 0)), &Vector3::from_i32(10, 2, 0));
+    assert!((o.to_aabb().max.y - 2.).abs() < 1e-5);
+    assert_eq!(o.corners().len(), 8);
+}
+
+#[test]
+fn test_degenerate_shapes() {
+    let (a, b, c) = (Vector3::from_i32(0, 0, 0), Vector3::from_i32(1, 1, 1), Vector3::from_i32(3, 3, 3));
+    assert_eq!(Plane::try_from_points(&a, &b, &c).unwrap_err(), Error::ZeroLength);
+    assert!(Plane::from_points(&a, &b, &c).normal.x.is_nan());
+    assert_eq!(Triangle::new(&a, &b, &c).try_normal().unwrap_err(), Error::ZeroLength);
+    assert_vector3_near(&Triangle::new(&a, &Vector3::from_i32(1, 0, 0), &Vector3::from_i32(0, 1, 0)).try_normal().unwrap(),
+                        &Vector3::new_z_up());
+
+    let mut flatten = Matrix4::new();
+    flatten.scale_mut(1., 1., 0.);
+
+    let mut p = Plane::from_point_normal(&Vector3::from_i32(0, 0, 2), &Vector3::new_z_up());
+    assert_eq!(p.try_transform_mut(&flatten).unwrap_err(), Error::SingularMatrix);
+    assert_eq!(p.d, -2.);
+
+    let mut o = Obb::from_aabb(&Aabb::new(&Vector3::from_i32(-1, -1, -1), &Vector3::from_i32(1, 1, 1)));
+    assert_eq!(o.try_transform_mut(&flatten).unwrap_err(), Error::SingularMatrix);
+    assert_eq!(o.half_extents.z, 1.);
+    assert!(o.try_transform_mut(&Matrix4::new()).is_ok());
+
+    let mut e = Aabb::empty();
+    let mut t = Matrix4::new();
+    t.translation_mut(1., 2., 3.);
+    e.transform_mut(&t);
+    assert!(e.is_empty());
+    e.expand_mut(&Vector3::from_i32(1, 1, 1));
+    assert_vector3_near(&e.center(), &Vector3::from_i32(1, 1, 1));
+}
+
+#[test]
+fn test_triangle_closest_point() {
+    let t = Triangle::new(&Vector3::from_i32(0, 0, 0), &Vector3::from_i32(2, 0, 0), &Vector3::from_i32(0, 2, 0));
+    assert_vector3_near(&t.normal(), &Vector3::new_z_up());
+    assert_eq!(t.area(), 2.);
+
+    // inside the face, at a corner, on an edge
+    assert_vector3_near(&t.closest_point(&Vector3::from(0.5, 0.5, 3.)), &Vector3::from(0.5, 0.5, 0.));
+    assert_vector3_near(&t.closest_point(&Vector3::from(-1., -1., 0.)), &Vector3::new());
+    assert_vector3_near(&t.closest_point(&Vector3::from(2., 2., 0.)), &Vector3::from(1., 1., 0.));
+    assert_vector3_near(&t.closest_point(&Vector3::from(1., -1., 0.)), &Vector3::from(1., 0., 0.));
+
+    let b = t.barycentric(&Vector3::from(0.5, 0.5, 0.));
+    assert_vector3_near(&b, &Vector3::from(0.5, 0.25, 0.25));
+}
+
+#[test]
+fn test_segment_and_capsule() {
+    let s = Segment::new(&Vector3::new(), &Vector3::from_i32(0, 0, 4));
+    assert_eq!(s.length(), 4.);
+    assert_vector3_near(&s.closest_point(&Vector3::from_i32(1, 0, 2)), &Vector3::from_i32(0, 0, 2));
+    assert_vector3_near(&s.closest_point(&Vector3::from_i32(1, 0, 9)), &Vector3::from_i32(0, 0, 4));
+
+    let other = Segment::new(&Vector3::from_i32(-1, 3, 1), &Vector3::from_i32(1, 3, 1));
+    let (a, b) = s.closest_points_segment(&other);
+    assert_vector3_near(&a, &Vector3::from_i32(0, 0, 1));
+    assert_vector3_near(&b, &Vector3::from_i32(0, 3, 1));
+
+    let c = Capsule::new(&Vector3::new(), &Vector3::from_i32(0, 0, 4), 1.);
+    assert!(c.contains_point(&Vector3::from(0., 0.9, 4.)));
+    assert!(!c.contains_point(&Vector3::from(0., 0., 5.1)));
+    assert_vector3_near(&c.closest_point(&Vector3::from_i32(3, 0, 2)), &Vector3::from_i32(1, 0, 2));
+    assert!(!c.intersects_capsule(&Capsule {segment: other, radius: 1.}));
+    assert!(c.intersects_capsule(&Capsule {segment: other, radius: 2.}));
+    assert_eq!(c.to_aabb().min.z, -1.);
+}
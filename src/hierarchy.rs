@@ -0,0 +1,299 @@
+//! A lightweight transform hierarchy (scene graph) with cached world matrices.
+//!
+//! Nodes are addressed by the index returned from `TransformHierarchy::add`. Each node stores
+//! its local `Transform` relative to its parent. Changing a local transform only marks the node
+//! and its descendants dirty, the world matrices are recomputed when they are asked for.
+//!
+//! # Example
+//! ```
+//! use js_linalg::{Quaternion, Transform, Vector3};
+//! use js_linalg::hierarchy::TransformHierarchy;
+//! let mut scene = TransformHierarchy::new();
+//! let car = scene.add(None, &Transform::from(&Vector3::from_i32(10, 0, 0), &Quaternion::new(), &Vector3::from_i32(1, 1, 1)));
+//! let wheel = scene.add(Some(car), &Transform::from(&Vector3::from_i32(1, 0, 0), &Quaternion::new(), &Vector3::from_i32(1, 1, 1)));
+//!
+//! assert_eq!(scene.world_matrix(wheel).m_3_0, 11.);
+//!
+//! let mut moved = *scene.local(car);
+//! moved.translation.x = 20.;
+//! scene.set_local(car, &moved);
+//! assert_eq!(scene.world_matrix(wheel).m_3_0, 21.);
+//! ```
+
+use super::{Matrix3, Matrix4, Transform, Error};
+
+/// A forest of transforms addressed by indices, see the module documentation.
+#[derive(Debug, Clone, Default)]
+pub struct TransformHierarchy {
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    locals: Vec<Transform>,
+    worlds: Vec<Matrix4>,
+    normals: Vec<Matrix3>,
+    dirty: Vec<bool>,
+}
+
+impl TransformHierarchy {
+
+    /// Creates a new, empty hierarchy.
+    pub fn new() -> TransformHierarchy {
+        TransformHierarchy::default()
+    }
+
+    /// Adds a node with the provided local transform below `parent`, or as a root for `None`.
+    /// Returns the index of the new node. Panics if `parent` is not a valid index.
+    pub fn add(&mut self, parent:Option<usize>, local:&Transform) -> usize {
+        let index = self.parents.len();
+        if let Some(p) = parent {
+            assert!(p < index, "invalid parent index {}", p);
+            self.children[p].push(index);
+        }
+
+        self.parents.push(parent);
+        self.children.push(Vec::new());
+        self.locals.push(*local);
+        self.worlds.push(Matrix4::new());
+        self.normals.push(Matrix3::new());
+        self.dirty.push(true);
+
+        index
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns `true` if there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The parent of the provided node, `None` for roots.
+    pub fn parent(&self, index:usize) -> Option<usize> {
+        self.parents[index]
+    }
+
+    /// The direct children of the provided node.
+    pub fn children(&self, index:usize) -> &[usize] {
+        &self.children[index]
+    }
+
+    /// The transform of the provided node relative to its parent.
+    pub fn local(&self, index:usize) -> &Transform {
+        &self.locals[index]
+    }
+
+    /// Replaces the local transform of the provided node and marks it and its descendants dirty.
+    pub fn set_local(&mut self, index:usize, local:&Transform) -> &mut TransformHierarchy {
+        self.locals[index] = *local;
+        self.mark_dirty(index);
+        self
+    }
+
+    /// Returns `true` if the world matrix of the provided node needs to be recomputed.
+    pub fn is_dirty(&self, index:usize) -> bool {
+        self.dirty[index]
+    }
+
+    fn mark_dirty(&mut self, index:usize) {
+        // descendants of a dirty node are always dirty, so already dirty subtrees can be skipped
+        let mut stack = vec![index];
+        while let Some(i) = stack.pop() {
+            if self.dirty[i] && i != index {
+                continue;
+            }
+            self.dirty[i] = true;
+            stack.extend_from_slice(&self.children[i]);
+        }
+    }
+
+    /// Recomputes the world matrix of the provided node and its dirty ancestors.
+    fn update_node(&mut self, index:usize) {
+        // walk up to the first clean ancestor, then back down, without recursing per level
+        let mut path = Vec::new();
+        let mut node = Some(index);
+        while let Some(i) = node {
+            if !self.dirty[i] {
+                break;
+            }
+            path.push(i);
+            node = self.parents[i];
+        }
+
+        for &i in path.iter().rev() {
+            let mut world = self.locals[i].to_matrix4();
+            if let Some(p) = self.parents[i] {
+                world = &self.worlds[p] * world;
+            }
+
+            self.normals[i] = Matrix3::normal_matrix(&world);
+            self.worlds[i] = world;
+            self.dirty[i] = false;
+        }
+    }
+
+    /// Recomputes all dirty world matrices, e.g. once per frame before reading `world_matrices`.
+    pub fn update(&mut self) -> &mut TransformHierarchy {
+        for i in 0..self.len() {
+            self.update_node(i);
+        }
+        self
+    }
+
+    /// The transform of the provided node relative to the world, i.e. the product of the local
+    /// matrices from the root down to the node. Recomputed only if the node is dirty.
+    pub fn world_matrix(&mut self, index:usize) -> &Matrix4 {
+        self.update_node(index);
+        &self.worlds[index]
+    }
+
+    /// The normal matrix of the world matrix of the provided node, see `Matrix3::normal_matrix`.
+    pub fn normal_matrix(&mut self, index:usize) -> &Matrix3 {
+        self.update_node(index);
+        &self.normals[index]
+    }
+
+    /// All world matrices in node order, e.g. for uploading them at once. Call `update` first,
+    /// otherwise dirty nodes have outdated matrices.
+    pub fn world_matrices(&self) -> &[Matrix4] {
+        &self.worlds
+    }
+
+    /// Moves the provided node (with its descendants) below a new parent, or makes it a root for
+    /// `None`. The local transform is adjusted such that the node keeps its world transform.
+    /// Panics if the new parent is the node itself or one of its descendants.
+    ///
+    /// Returns `Error::SingularMatrix` and leaves the hierarchy unchanged if the world transform
+    /// of the new parent cannot be inverted (e.g. a zero scale), or if the resulting local matrix
+    /// cannot be split up into a `Transform`.
+    pub fn set_parent(&mut self, index:usize, parent:Option<usize>) -> Result<&mut TransformHierarchy, Error> {
+        if let Some(p) = parent {
+            let mut ancestor = Some(p);
+            while let Some(a) = ancestor {
+                assert!(a != index, "cannot move node {} below its own descendant {}", index, p);
+                ancestor = self.parents[a];
+            }
+        }
+
+        let world = Matrix4::from_matrix4(self.world_matrix(index));
+        let local = match parent {
+            Some(p) => &self.world_matrix(p).try_inverse()? * &world,
+            None => world,
+        };
+        let local = local.decompose().ok_or(Error::SingularMatrix)?;
+
+        if let Some(old) = self.parents[index] {
+            self.children[old].retain(|&c| c != index);
+        }
+        if let Some(p) = parent {
+            self.children[p].push(index);
+        }
+        self.parents[index] = parent;
+        self.locals[index] = local;
+        self.mark_dirty(index);
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+fn assert_matrix4_near(a:&Matrix4, b:&Matrix4) {
+    for (x, y) in a.as_array().iter().zip(b.as_array().iter()) {
+        assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_hierarchy_world_matrices() {
+    use super::{Quaternion, Vector3};
+
+    let mut h = TransformHierarchy::new();
+    let root = h.add(None, &Transform::from(&Vector3::from_i32(0, 0, 5), &Quaternion::from_axis_angle(&Vector3::new_z_up(), std::f32::consts::FRAC_PI_2), &Vector3::from_i32(2, 2, 2)));
+    let child = h.add(Some(root), &Transform::from(&Vector3::from_i32(1, 0, 0), &Quaternion::new(), &Vector3::from_i32(1, 1, 1)));
+    let grandchild = h.add(Some(child), &Transform::new());
+
+    let p = h.world_matrix(grandchild) * Vector3::new();
+    assert!(p.distance(&Vector3::from_i32(0, 2, 5)) < 1e-5);
+    assert!(!h.is_dirty(root) && !h.is_dirty(child));
+
+    // moving the root only marks its subtree dirty
+    let other = h.add(None, &Transform::new());
+    h.update();
+    let mut t = *h.local(root);
+    t.translation.z = 0.;
+    h.set_local(root, &t);
+    assert!(h.is_dirty(root) && h.is_dirty(child) && h.is_dirty(grandchild));
+    assert!(!h.is_dirty(other));
+
+    let p = h.world_matrix(grandchild) * Vector3::new();
+    assert!(p.distance(&Vector3::from_i32(0, 2, 0)) < 1e-5);
+
+    // uniform scale 2 makes the normal matrix scale by 1/2
+    let n = h.normal_matrix(child) * Vector3::from_i32(1, 0, 0);
+    assert!((n.length() - 0.5).abs() < 1e-5);
+
+    h.update();
+    assert_eq!(h.world_matrices().len(), 4);
+    assert_eq!(h.children(root), &[child]);
+}
+
+#[test]
+fn test_hierarchy_reparent_keeps_world_pose() {
+    use super::{Quaternion, Vector3};
+
+    let mut h = TransformHierarchy::new();
+    let a = h.add(None, &Transform::from(&Vector3::from_i32(5, 0, 0), &Quaternion::from_euler(0.3, 0., 0.), &Vector3::from_i32(1, 1, 1)));
+    let b = h.add(None, &Transform::from(&Vector3::from_i32(0, 3, 0), &Quaternion::from_euler(0., 0., 1.2), &Vector3::from(2., 2., 2.)));
+    let c = h.add(Some(a), &Transform::from(&Vector3::from_i32(1, 1, 1), &Quaternion::from_euler(0.5, 0.1, 0.), &Vector3::from(1., 3., 1.)));
+    let d = h.add(Some(c), &Transform::from(&Vector3::from_i32(0, 0, 2), &Quaternion::new(), &Vector3::from_i32(1, 1, 1)));
+
+    let world_c = Matrix4::from_matrix4(h.world_matrix(c));
+    let world_d = Matrix4::from_matrix4(h.world_matrix(d));
+
+    h.set_parent(c, Some(b)).unwrap();
+    assert_eq!(h.parent(c), Some(b));
+    assert!(h.children(a).is_empty());
+    assert_matrix4_near(h.world_matrix(c), &world_c);
+    assert_matrix4_near(h.world_matrix(d), &world_d);
+
+    h.set_parent(c, None).unwrap();
+    assert_matrix4_near(h.world_matrix(d), &world_d);
+}
+
+#[test]
+fn test_hierarchy_reparent_below_zero_scale() {
+    use super::{Quaternion, Vector3};
+
+    let mut h = TransformHierarchy::new();
+    let flat = h.add(None, &Transform::from(&Vector3::from_i32(1, 0, 0), &Quaternion::new(), &Vector3::from(1., 0., 1.)));
+    let node = h.add(None, &Transform::from(&Vector3::from_i32(0, 2, 0), &Quaternion::new(), &Vector3::from_i32(1, 1, 1)));
+
+    assert_eq!(h.set_parent(node, Some(flat)).unwrap_err(), Error::SingularMatrix);
+    assert_eq!(h.parent(node), None);
+    assert!(h.children(flat).is_empty());
+    assert_eq!(h.local(node).translation.y, 2.);
+    assert_eq!(h.world_matrix(node).m_3_1, 2.);
+}
+
+#[test]
+fn test_hierarchy_deep_chain() {
+    use super::{Quaternion, Vector3};
+
+    // deep chains are updated without recursing once per level
+    let mut h = TransformHierarchy::new();
+    let step = Transform::from(&Vector3::from_i32(1, 0, 0), &Quaternion::new(), &Vector3::from_i32(1, 1, 1));
+    let mut last = h.add(None, &step);
+    for _ in 1..100_000 {
+        last = h.add(Some(last), &step);
+    }
+    assert_eq!(h.world_matrix(last).m_3_0, 100_000.);
+}
+
+#[test]
+#[should_panic]
+fn test_hierarchy_reparent_cycle() {
+    let mut h = TransformHierarchy::new();
+    let a = h.add(None, &Transform::new());
+    let b = h.add(Some(a), &Transform::new());
+    let _ = h.set_parent(a, Some(b));
+}
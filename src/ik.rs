@@ -0,0 +1,459 @@
+//! Inverse kinematics: rotating the joints of a `Chain` such that its end reaches a target,
+//! e.g. for foot placement or reaching for objects.
+//!
+//! A `Chain` starts at `origin` and consists of joints, each with a local rotation relative to
+//! the previous joint and a bone vector pointing to the next joint (or to the end effector for
+//! the last joint) in the space of the joint. The solvers only change the local rotations,
+//! which can be copied back into e.g. a `skeleton::Pose`.
+//!
+//! - `Chain::solve_two_bone`: analytic solution for two joints (upper arm and forearm, thigh
+//!   and shin) bending towards a pole vector, e.g. the knee.
+//! - `Chain::solve_ccd`: cyclic coordinate descent, rotating one joint at a time to point the
+//!   end effector at the target. Cheap per iteration, tends to curl the end of the chain.
+//! - `Chain::solve_fabrik`: forward and backward reaching, moving the joint positions and
+//!   deriving the rotations. Converges quickly with natural looking results.
+//!
+//! # Example
+//! ```
+//! use js_linalg::Vector3;
+//! use js_linalg::ik::{Chain, IkSettings};
+//!
+//! // a leg from the hip down to the ankle
+//! let mut leg = Chain::from_positions(&[
+//!     Vector3::from_i32(0, 0, 4),
+//!     Vector3::from_i32(0, 0, 2),
+//!     Vector3::from_i32(0, 0, 0),
+//! ]);
+//!
+//! // lift the foot, the knee bends forwards (+y)
+//! let result = leg.solve_two_bone(&Vector3::from(0., 0.5, 1.), &Vector3::from_i32(0, 10, 2), &IkSettings::default());
+//! assert!(result.reached);
+//! assert!(leg.positions()[1].y > 0.);
+//! ```
+
+use super::{Quaternion, Vector3};
+
+/// Restricts the local rotation of a joint.
+#[derive(Debug, Copy, Clone)]
+pub enum JointLimit {
+    /// A ball joint: the bone may point anywhere within `max_angle` radians of `axis`, which
+    /// is given in the space of the previous joint. Twist around the bone is not restricted.
+    Cone {
+        axis: Vector3,
+        max_angle: f32
+    },
+
+    /// A hinge joint like an elbow or a knee: the joint only rotates about `axis`, given in the
+    /// space of the previous joint, by an angle between `min` and `max` radians.
+    Hinge {
+        axis: Vector3,
+        min: f32,
+        max: f32
+    }
+}
+
+impl JointLimit {
+
+    /// Returns the local rotation `q` of a joint with the provided bone restricted to this limit.
+    pub fn apply(&self, q:&Quaternion, bone:&Vector3) -> Quaternion {
+        match self {
+            JointLimit::Cone {axis, max_angle} => {
+                let direction = q * bone;
+                let angle = direction.angle_between(axis);
+                if angle <= *max_angle {
+                    return *q;
+                }
+                // turn the axis towards the bone direction by the maximum angle
+                let mut arc = Quaternion::from_to(axis, &direction);
+                let limited = Quaternion::new().slerp(&arc, max_angle / angle) * axis.normalize();
+                arc = Quaternion::from_to(&direction, &limited);
+                let mut r = arc * q;
+                r.normalize_mut();
+                r
+            },
+            JointLimit::Hinge {axis, min, max} => {
+                // the twist of q about the axis, everything else is removed
+                let axis = axis.normalize();
+                let p = q.x * axis.x + q.y * axis.y + q.z * axis.z;
+                let mut angle = 2.0 * p.atan2(q.w);
+                if angle > std::f32::consts::PI {
+                    angle -= 2.0 * std::f32::consts::PI;
+                } else if angle < -std::f32::consts::PI {
+                    angle += 2.0 * std::f32::consts::PI;
+                }
+                Quaternion::from_axis_angle(&axis, angle.clamp(*min, *max))
+            }
+        }
+    }
+}
+
+/// A single joint of a `Chain`.
+#[derive(Debug, Copy, Clone)]
+pub struct Joint {
+    /// The rotation relative to the previous joint, or to `Chain::parent_rotation` for the first one.
+    pub rotation: Quaternion,
+
+    /// The offset to the next joint in the space of this joint.
+    pub bone: Vector3,
+
+    /// An optional restriction of `rotation`.
+    pub limit: Option<JointLimit>
+}
+
+impl Joint {
+
+    /// Creates an unrestricted joint without rotation.
+    pub fn new(bone:&Vector3) -> Joint {
+        Joint {rotation: Quaternion::new(), bone: *bone, limit: None}
+    }
+}
+
+/// Termination criteria of the iterative solvers.
+#[derive(Debug, Copy, Clone)]
+pub struct IkSettings {
+    /// The solver stops once the end effector is closer to the target than this distance.
+    pub tolerance: f32,
+
+    /// The maximum number of iterations.
+    pub max_iterations: usize
+}
+
+impl IkSettings {
+
+    /// Creates settings with a tolerance of `0.001` and at most 16 iterations.
+    pub fn new() -> IkSettings {
+        IkSettings {tolerance: 1e-3, max_iterations: 16}
+    }
+}
+
+impl Default for IkSettings {
+    /// Same as `IkSettings::new()`.
+    fn default() -> IkSettings {
+        IkSettings::new()
+    }
+}
+
+/// The outcome of a solver.
+#[derive(Debug, Copy, Clone)]
+pub struct IkResult {
+    /// The number of iterations performed.
+    pub iterations: usize,
+
+    /// The distance between the end effector and the target after solving.
+    pub distance: f32,
+
+    /// Whether `distance` is within the tolerance. Unreachable targets are never reached,
+    /// the chain then points at the target as far as its limits allow.
+    pub reached: bool
+}
+
+/// A chain of joints, see the module documentation.
+#[derive(Debug, Clone)]
+pub struct Chain {
+    /// The position of the first joint.
+    pub origin: Vector3,
+
+    /// The world rotation of the parent of the first joint.
+    pub parent_rotation: Quaternion,
+
+    /// The joints from the root to the end of the chain.
+    pub joints: Vec<Joint>
+}
+
+impl Chain {
+
+    /// Creates a chain without rotations from the positions of its joints followed by the
+    /// position of the end effector, so `n + 1` points give `n` joints.
+    pub fn from_positions(positions:&[Vector3]) -> Chain {
+        Chain {
+            origin: positions.first().copied().unwrap_or_default(),
+            parent_rotation: Quaternion::new(),
+            joints: positions.windows(2).map(|w| Joint::new(&(w[1] - w[0]))).collect()
+        }
+    }
+
+    /// The world positions of all joints followed by the position of the end effector.
+    pub fn positions(&self) -> Vec<Vector3> {
+        let mut positions = Vec::with_capacity(self.joints.len() + 1);
+        let mut rotation = self.parent_rotation;
+        let mut p = self.origin;
+        positions.push(p);
+        for joint in self.joints.iter() {
+            rotation.mult_mut(&joint.rotation);
+            p += rotation * joint.bone;
+            positions.push(p);
+        }
+        positions
+    }
+
+    /// The world rotations of all joints.
+    pub fn world_rotations(&self) -> Vec<Quaternion> {
+        let mut rotation = self.parent_rotation;
+        self.joints.iter().map(|joint| {
+            rotation.mult_mut(&joint.rotation);
+            rotation
+        }).collect()
+    }
+
+    /// The position at the end of the last bone.
+    pub fn end_effector(&self) -> Vector3 {
+        self.positions()[self.joints.len()]
+    }
+
+    /// The total length of all bones, i.e. the reach of the chain.
+    pub fn length(&self) -> f32 {
+        self.joints.iter().map(|j| j.bone.length()).sum()
+    }
+
+    fn result(&self, target:&Vector3, iterations:usize, settings:&IkSettings) -> IkResult {
+        let distance = self.end_effector().distance(target);
+        IkResult {iterations, distance, reached: distance <= settings.tolerance}
+    }
+
+    /// Rotates the joints one after another, starting at the root, such that their bones point
+    /// to the provided positions (`joints.len() + 1` of them, the first one is ignored).
+    fn align_to(&mut self, targets:&[Vector3], apply_limits:bool) {
+        let mut parent = self.parent_rotation;
+        let mut p = self.origin;
+        for (joint, target) in self.joints.iter_mut().zip(targets[1..].iter()) {
+            let world = parent * joint.rotation;
+            let delta = Quaternion::from_to(&(world * joint.bone), &(*target - p));
+            let mut local = parent.conjugate() * delta * world;
+            local.normalize_mut();
+            if let (Some(limit), true) = (&joint.limit, apply_limits) {
+                local = limit.apply(&local, &joint.bone);
+            }
+            joint.rotation = local;
+            parent.mult_mut(&local);
+            p += parent * joint.bone;
+        }
+    }
+
+    /// Analytic two-bone IK for a chain of exactly two joints. The middle joint bends towards
+    /// `pole`, e.g. a point in front of the knee, and the end effector reaches the target if it
+    /// is within reach, otherwise the chain is stretched towards (or folded away from) it.
+    /// Joint limits are ignored, only `settings.tolerance` is used. A zero-length bone has no
+    /// direction, the other bone then simply points at the target.
+    ///
+    /// # Panics
+    /// Panics if the chain does not have two joints.
+    pub fn solve_two_bone(&mut self, target:&Vector3, pole:&Vector3, settings:&IkSettings) -> IkResult {
+        assert_eq!(self.joints.len(), 2, "two-bone IK needs 2 joints, not {}", self.joints.len());
+        let positions = self.positions();
+        let a = self.origin;
+        let upper = self.joints[0].bone.length();
+        let lower = self.joints[1].bone.length();
+
+        let direction = (*target - a).try_normalize()
+            .or_else(|_| (positions[2] - a).try_normalize())
+            .unwrap_or(Vector3::from(1.0, 0.0, 0.0));
+        let distance = (*target - a).length().clamp((upper - lower).abs(), upper + lower).max(1e-6);
+
+        // bend direction: the pole perpendicular to the line from the root to the target
+        let perpendicular = |v:Vector3| (v - direction * v.dot(&direction)).try_normalize();
+        let bend = perpendicular(*pole - a)
+            .or_else(|_| perpendicular(positions[1] - a))
+            .or_else(|_| perpendicular(Vector3::from(0.0, 0.0, 1.0)))
+            .unwrap_or_else(|_| perpendicular(Vector3::from(1.0, 0.0, 0.0)).unwrap_or_default());
+
+        // law of cosines for the angle at the root, undefined without an upper bone
+        let cos = if upper > 0.0 {
+            ((upper * upper + distance * distance - lower * lower) / (2.0 * upper * distance)).clamp(-1.0, 1.0)
+        } else {
+            1.0
+        };
+        let sin = (1.0 - cos * cos).sqrt();
+        let mid = a + direction * (upper * cos) + bend * (upper * sin);
+        let end = a + direction * distance;
+
+        self.align_to(&[a, mid, end], false);
+        self.result(target, 1, settings)
+    }
+
+    /// Cyclic coordinate descent: each iteration rotates every joint, from the end of the chain
+    /// to the root, such that the end effector points at the target, then applies the joint
+    /// limits.
+    pub fn solve_ccd(&mut self, target:&Vector3, settings:&IkSettings) -> IkResult {
+        for iteration in 0..settings.max_iterations {
+            let result = self.result(target, iteration, settings);
+            if result.reached {
+                return result;
+            }
+            // going from the end to the root, a joint only moves the joints after it, so the
+            // positions and rotations of the pass stay valid up to the current joint, only the
+            // end effector needs to be carried along
+            let positions = self.positions();
+            let rotations = self.world_rotations();
+            let mut end = positions[self.joints.len()];
+            for i in (0..self.joints.len()).rev() {
+                let parent = if i == 0 { self.parent_rotation } else { rotations[i - 1] };
+                let joint = &mut self.joints[i];
+
+                let delta = Quaternion::from_to(&(end - positions[i]), &(*target - positions[i]));
+                let mut local = parent.conjugate() * delta * rotations[i];
+                local.normalize_mut();
+                if let Some(limit) = &joint.limit {
+                    local = limit.apply(&local, &joint.bone);
+                }
+                joint.rotation = local;
+
+                let change = parent * local * rotations[i].conjugate();
+                end = positions[i] + change * (end - positions[i]);
+            }
+        }
+        self.result(target, settings.max_iterations, settings)
+    }
+
+    /// Forward and backward reaching IK: each iteration first moves the joints from the end
+    /// effector at the target back to the root, then forward again from the fixed root, keeping
+    /// the bone lengths. The joint rotations are derived from the new positions and restricted
+    /// by the joint limits. Unreachable targets stretch the chain towards them.
+    pub fn solve_fabrik(&mut self, target:&Vector3, settings:&IkSettings) -> IkResult {
+        let lengths: Vec<f32> = self.joints.iter().map(|j| j.bone.length()).collect();
+        let n = self.joints.len();
+
+        for iteration in 0..settings.max_iterations {
+            let result = self.result(target, iteration, settings);
+            if result.reached {
+                return result;
+            }
+
+            let mut p = self.positions();
+            if self.origin.distance(target) >= self.length() {
+                // out of reach: all bones point at the target
+                let direction = (*target - self.origin).normalize();
+                for i in 0..n {
+                    p[i + 1] = p[i] + direction * lengths[i];
+                }
+            } else {
+                p[n] = *target;
+                for i in (0..n).rev() {
+                    p[i] = p[i + 1] + (p[i] - p[i + 1]).try_normalize().unwrap_or_default() * lengths[i];
+                }
+                p[0] = self.origin;
+                for i in 0..n {
+                    p[i + 1] = p[i] + (p[i + 1] - p[i]).try_normalize().unwrap_or_default() * lengths[i];
+                }
+            }
+            self.align_to(&p, true);
+        }
+        self.result(target, settings.max_iterations, settings)
+    }
+}
+
+#[cfg(test)]
+fn arm() -> Chain {
+    Chain::from_positions(&[Vector3::from_i32(0, 0, 0), Vector3::from_i32(2, 0, 0), Vector3::from_i32(4, 0, 0)])
+}
+
+#[cfg(test)]
+fn assert_bone_lengths(chain:&Chain, lengths:&[f32]) {
+    let p = chain.positions();
+    for (i, l) in lengths.iter().enumerate() {
+        assert!((p[i].distance(&p[i + 1]) - l).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn test_two_bone() {
+    let settings = IkSettings::default();
+    let mut chain = arm();
+    let target = Vector3::from(2., 1., 0.5);
+    let result = chain.solve_two_bone(&target, &Vector3::from_i32(0, 0, 5), &settings);
+    assert!(result.reached, "{:?}", result);
+    assert_eq!(result.iterations, 1);
+    assert_bone_lengths(&chain, &[2., 2.]);
+    // the elbow bends towards the pole
+    assert!(chain.positions()[1].z > 0.5);
+
+    // the pole on the other side flips the elbow
+    chain.solve_two_bone(&target, &Vector3::from_i32(0, 0, -5), &settings);
+    assert!(chain.positions()[1].z < 0.);
+
+    // out of reach
+    let result = chain.solve_two_bone(&Vector3::from_i32(0, 10, 0), &Vector3::from_i32(0, 0, 5), &settings);
+    assert!(!result.reached);
+    assert!((result.distance - 6.).abs() < 1e-4);
+    assert!((chain.end_effector().y - 4.).abs() < 1e-4);
+}
+
+#[test]
+fn test_two_bone_zero_length_bone() {
+    let settings = IkSettings::default();
+    let mut chain = Chain::from_positions(&[Vector3::new(), Vector3::new(), Vector3::from_i32(2, 0, 0)]);
+    let result = chain.solve_two_bone(&Vector3::from_i32(0, 2, 0), &Vector3::from_i32(0, 0, 5), &settings);
+    assert!(result.reached, "{:?}", result);
+    assert!(chain.end_effector().distance(&Vector3::from_i32(0, 2, 0)) < 1e-4);
+
+    let mut chain = Chain::from_positions(&[Vector3::new(), Vector3::from_i32(2, 0, 0), Vector3::from_i32(2, 0, 0)]);
+    let result = chain.solve_two_bone(&Vector3::from_i32(0, 3, 0), &Vector3::from_i32(0, 0, 5), &settings);
+    assert!((result.distance - 1.).abs() < 1e-4, "{:?}", result);
+    assert!(chain.end_effector().distance(&Vector3::from_i32(0, 2, 0)) < 1e-4);
+}
+
+#[test]
+fn test_ccd_and_fabrik_reach() {
+    let positions = [Vector3::from_i32(0, 0, 0), Vector3::from_i32(0, 1, 0), Vector3::from_i32(0, 2, 0),
+                     Vector3::from_i32(0, 3, 0), Vector3::from_i32(0, 4, 0)];
+    let target = Vector3::from(1.5, 2., 1.);
+    let settings = IkSettings {tolerance: 1e-3, max_iterations: 64};
+
+    let mut ccd = Chain::from_positions(&positions);
+    let result = ccd.solve_ccd(&target, &settings);
+    assert!(result.reached, "{:?}", result);
+    assert_bone_lengths(&ccd, &[1., 1., 1., 1.]);
+
+    let mut fabrik = Chain::from_positions(&positions);
+    fabrik.parent_rotation = Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.3);
+    fabrik.joints[0].rotation = fabrik.parent_rotation.conjugate();
+    let result = fabrik.solve_fabrik(&target, &settings);
+    assert!(result.reached, "{:?}", result);
+    assert!(result.iterations < settings.max_iterations);
+    assert_bone_lengths(&fabrik, &[1., 1., 1., 1.]);
+
+    // unreachable targets stretch the chain
+    let far = Vector3::from_i32(10, 0, 0);
+    let result = fabrik.solve_fabrik(&far, &settings);
+    assert!(!result.reached);
+    assert!((result.distance - 6.).abs() < 1e-3);
+    let result = ccd.solve_ccd(&far, &settings);
+    assert_eq!(result.iterations, 64);
+    assert!((result.distance - 6.).abs() < 1e-2);
+}
+
+#[test]
+fn test_iteration_cap() {
+    let mut chain = Chain::from_positions(&[Vector3::from_i32(0, 0, 0), Vector3::from_i32(1, 0, 0), Vector3::from_i32(2, 0, 0)]);
+    let settings = IkSettings {tolerance: 0., max_iterations: 3};
+    let result = chain.solve_fabrik(&Vector3::from(0.5, 1., 0.), &settings);
+    assert_eq!(result.iterations, 3);
+    assert!(!result.reached);
+
+    let result = chain.solve_ccd(&Vector3::from(0.5, 1., 0.), &IkSettings {tolerance: 1., max_iterations: 3});
+    assert_eq!(result.iterations, 0);
+}
+
+#[test]
+fn test_joint_limits() {
+    // an elbow that only bends about z between 0 and 90 degrees
+    let mut chain = arm();
+    chain.joints[1].limit = Some(JointLimit::Hinge {axis: Vector3::new_z_up(), min: 0., max: std::f32::consts::FRAC_PI_2});
+    let settings = IkSettings {tolerance: 1e-3, max_iterations: 64};
+
+    for target in [Vector3::from(1., 1., 0.), Vector3::from(2., -1., 1.)].iter() {
+        chain.solve_fabrik(target, &settings);
+        let q = chain.joints[1].rotation;
+        assert!(q.x.abs() < 1e-5 && q.y.abs() < 1e-5);
+        let angle = 2. * q.z.atan2(q.w);
+        assert!((-1e-4..=std::f32::consts::FRAC_PI_2 + 1e-4).contains(&angle), "{}", angle);
+    }
+
+    // a ball joint at the root keeping the arm within 30 degrees of +x
+    let mut chain = arm();
+    let max_angle = std::f32::consts::PI / 6.;
+    chain.joints[0].limit = Some(JointLimit::Cone {axis: Vector3::from_i32(1, 0, 0), max_angle});
+    chain.solve_ccd(&Vector3::from_i32(0, 3, 0), &settings);
+    let p = chain.positions();
+    assert!((p[1] - p[0]).angle_between(&Vector3::from_i32(1, 0, 0)) <= max_angle + 1e-4);
+    assert_bone_lengths(&chain, &[2., 2.]);
+}
@@ -0,0 +1,508 @@
+//! Interpolation of values along a parameter `t` and keyframe tracks for animation.
+//!
+//! All functions are generic over `Interpolate`, which is implemented for `f32`, `Vector3`,
+//! `Vector4`, `Matrix4`, `Quaternion` and `Transform`. The curves are evaluated for `t` in
+//! `[0, 1]`; each comes with a `_derivative` counterpart returning the derivative with respect
+//! to `t`, e.g. the velocity along a path.
+//!
+//! The curves work on the components of the values. This is fine for keys that are close
+//! to each other, but rotations between keys far apart are not preserved (a `Matrix4`
+//! "shrinks" halfway between them). A `Track` of `Quaternion`s or `Transform`s therefore
+//! interpolates linear keys with `Quaternion::slerp` and re-normalizes the rotation of cubic
+//! keys, so animate rotations with those rather than with `Matrix4`.
+//!
+//! # Example
+//! ```
+//! use js_linalg::Vector3;
+//! use js_linalg::interpolation::{Track, TrackMode};
+//!
+//! let mut track = Track::new(TrackMode::Linear);
+//! track.insert(0., Vector3::from_i32(0, 0, 0))
+//!      .insert(2., Vector3::from_i32(4, 0, 0))
+//!      .insert(3., Vector3::from_i32(4, 2, 0));
+//!
+//! let p = track.sample(1.).unwrap();
+//! assert_eq!(p.x, 2.);
+//! // sampling is clamped to the first and last key
+//! assert_eq!(track.sample(10.).unwrap().y, 2.);
+//! ```
+
+use super::{Vector3, Vector4, Matrix4, Quaternion, Transform};
+
+/// A value that can be interpolated, i.e. that supports weighted sums of two values.
+pub trait Interpolate: Clone {
+    /// Returns `self * a + that * b`.
+    fn weighted_sum(&self, a: f32, that: &Self, b: f32) -> Self;
+
+    /// Interpolates between `self` (`t = 0`) and `that` (`t = 1`) for the linear mode of a
+    /// `Track`. Defaults to `lerp`, rotations override it with `Quaternion::slerp`.
+    fn interpolate(&self, that: &Self, t: f32) -> Self {
+        lerp(self, that, t)
+    }
+
+    /// Turns the weighted sum of keys back into a valid value for the cubic mode of a
+    /// `Track`, e.g. normalizes rotations. Returns the value unchanged by default.
+    fn renormalize(self) -> Self {
+        self
+    }
+}
+
+impl Interpolate for f32 {
+    fn weighted_sum(&self, a: f32, that: &f32, b: f32) -> f32 {
+        self * a + that * b
+    }
+}
+
+macro_rules! impl_interpolate_componentwise {
+    ($($t:ty),*) => {
+        $(
+            impl Interpolate for $t {
+                fn weighted_sum(&self, a: f32, that: &$t, b: f32) -> $t {
+                    let mut result = self.clone();
+                    for (r, v) in result.as_mut_array().iter_mut().zip(that.as_array().iter()) {
+                        *r = *r * a + v * b;
+                    }
+                    result
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_componentwise!(Vector3, Vector4, Matrix4);
+
+impl Interpolate for Quaternion {
+    fn weighted_sum(&self, a: f32, that: &Quaternion, b: f32) -> Quaternion {
+        let mut result = *self;
+        for (r, v) in result.as_mut_array().iter_mut().zip(that.as_array().iter()) {
+            *r = *r * a + v * b;
+        }
+        result
+    }
+
+    fn interpolate(&self, that: &Quaternion, t: f32) -> Quaternion {
+        self.slerp(that, t)
+    }
+
+    fn renormalize(self) -> Quaternion {
+        self.try_normalize().unwrap_or(self)
+    }
+}
+
+impl Interpolate for Transform {
+    fn weighted_sum(&self, a: f32, that: &Transform, b: f32) -> Transform {
+        Transform {
+            translation: self.translation.weighted_sum(a, &that.translation, b),
+            rotation: self.rotation.weighted_sum(a, &that.rotation, b),
+            scale: self.scale.weighted_sum(a, &that.scale, b),
+            shear: self.shear.weighted_sum(a, &that.shear, b),
+        }
+    }
+
+    fn interpolate(&self, that: &Transform, t: f32) -> Transform {
+        Transform::lerp(self, that, t)
+    }
+
+    fn renormalize(mut self) -> Transform {
+        self.rotation = self.rotation.renormalize();
+        self
+    }
+}
+
+/// Returns `p0 * w[0] + p1 * w[1] + p2 * w[2] + p3 * w[3]`.
+fn sum4<T: Interpolate>(p0: &T, p1: &T, p2: &T, p3: &T, w: [f32; 4]) -> T {
+    p0.weighted_sum(w[0], p1, w[1]).weighted_sum(1., &p2.weighted_sum(w[2], p3, w[3]), 1.)
+}
+
+/// Linear interpolation: `t = 0` returns `a`, `t = 1` returns `b`.
+pub fn lerp<T: Interpolate>(a: &T, b: &T, t: f32) -> T {
+    a.weighted_sum(1. - t, b, t)
+}
+
+/// The derivative of `lerp` with respect to `t`, which is `b - a` everywhere.
+pub fn lerp_derivative<T: Interpolate>(a: &T, b: &T) -> T {
+    b.weighted_sum(1., a, -1.)
+}
+
+/// Interpolation between `a` and `b` along the smoothstep curve `3t² - 2t³`, which starts and
+/// ends with zero velocity. `t` is clamped to `[0, 1]`.
+///
+/// # Example
+/// ```
+/// use js_linalg::interpolation::smoothstep;
+/// assert_eq!(smoothstep(&0f32, &1f32, 0.5), 0.5);
+/// assert!(smoothstep(&0f32, &1f32, 0.1) < 0.1);
+/// assert_eq!(smoothstep(&0f32, &1f32, 2.), 1.);
+/// ```
+pub fn smoothstep<T: Interpolate>(a: &T, b: &T, t: f32) -> T {
+    let t = t.clamp(0., 1.);
+    lerp(a, b, t * t * (3. - 2. * t))
+}
+
+/// The derivative of `smoothstep` with respect to `t`. Zero outside of `[0, 1]`.
+pub fn smoothstep_derivative<T: Interpolate>(a: &T, b: &T, t: f32) -> T {
+    let s = if (0. ..=1.).contains(&t) { 6. * t * (1. - t) } else { 0. };
+    b.weighted_sum(s, a, -s)
+}
+
+/// Cubic Hermite interpolation from `p0` with tangent `m0` to `p1` with tangent `m1`. The
+/// tangents are derivatives with respect to `t`, so tangents given per second have to be
+/// multiplied with the duration of the segment.
+pub fn hermite<T: Interpolate>(p0: &T, m0: &T, p1: &T, m1: &T, t: f32) -> T {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    sum4(p0, m0, p1, m1, [
+        2. * t3 - 3. * t2 + 1.,
+        t3 - 2. * t2 + t,
+        -2. * t3 + 3. * t2,
+        t3 - t2
+    ])
+}
+
+/// The derivative of `hermite` with respect to `t`.
+pub fn hermite_derivative<T: Interpolate>(p0: &T, m0: &T, p1: &T, m1: &T, t: f32) -> T {
+    let t2 = t * t;
+    sum4(p0, m0, p1, m1, [
+        6. * t2 - 6. * t,
+        3. * t2 - 4. * t + 1.,
+        -6. * t2 + 6. * t,
+        3. * t2 - 2. * t
+    ])
+}
+
+/// Uniform Catmull-Rom interpolation between `p1` (`t = 0`) and `p2` (`t = 1`). The
+/// neighbours `p0` and `p3` define the tangents `(p2 - p0) / 2` and `(p3 - p1) / 2`, so a
+/// chain of segments passes through all points with a continuous velocity.
+///
+/// # Example
+/// ```
+/// use js_linalg::Vector3;
+/// use js_linalg::interpolation::catmull_rom;
+/// let p = [Vector3::from_i32(0, 0, 0), Vector3::from_i32(1, 0, 0),
+///          Vector3::from_i32(2, 0, 0), Vector3::from_i32(3, 0, 0)];
+/// assert_eq!(catmull_rom(&p[0], &p[1], &p[2], &p[3], 0.5).x, 1.5);
+/// ```
+pub fn catmull_rom<T: Interpolate>(p0: &T, p1: &T, p2: &T, p3: &T, t: f32) -> T {
+    sum4(p0, p1, p2, p3, catmull_rom_weights(t, 0))
+}
+
+/// The derivative of `catmull_rom` with respect to `t`.
+pub fn catmull_rom_derivative<T: Interpolate>(p0: &T, p1: &T, p2: &T, p3: &T, t: f32) -> T {
+    sum4(p0, p1, p2, p3, catmull_rom_weights(t, 1))
+}
+
+/// The weights of the four points in `catmull_rom` for the `derivative`-th derivative (0 to 2)
+/// at `t`, also used by the segments of a `Spline`.
+pub(crate) fn catmull_rom_weights(t: f32, derivative: usize) -> [f32; 4] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    match derivative {
+        0 => [0.5 * (-t3 + 2. * t2 - t), 0.5 * (3. * t3 - 5. * t2 + 2.), 0.5 * (-3. * t3 + 4. * t2 + t), 0.5 * (t3 - t2)],
+        1 => [0.5 * (-3. * t2 + 4. * t - 1.), 0.5 * (9. * t2 - 10. * t), 0.5 * (-9. * t2 + 8. * t + 1.), 0.5 * (3. * t2 - 2. * t)],
+        _ => [0.5 * (-6. * t + 4.), 0.5 * (18. * t - 10.), 0.5 * (-18. * t + 8.), 0.5 * (6. * t - 2.)]
+    }
+}
+
+/// Cubic Bézier curve starting at `p0` and ending at `p3`, with the control points `p1`
+/// and `p2` pulling the curve towards them.
+pub fn bezier<T: Interpolate>(p0: &T, p1: &T, p2: &T, p3: &T, t: f32) -> T {
+    sum4(p0, p1, p2, p3, bezier_weights(t, 0))
+}
+
+/// The derivative of `bezier` with respect to `t`.
+pub fn bezier_derivative<T: Interpolate>(p0: &T, p1: &T, p2: &T, p3: &T, t: f32) -> T {
+    sum4(p0, p1, p2, p3, bezier_weights(t, 1))
+}
+
+/// The weights of the four points in `bezier` for the `derivative`-th derivative (0 to 2) at
+/// `t`, also used by the segments of a `Spline`.
+pub(crate) fn bezier_weights(t: f32, derivative: usize) -> [f32; 4] {
+    let u = 1. - t;
+    match derivative {
+        0 => [u * u * u, 3. * u * u * t, 3. * u * t * t, t * t * t],
+        1 => [-3. * u * u, 3. * u * u - 6. * u * t, 6. * u * t - 3. * t * t, 3. * t * t],
+        _ => [6. * u, -12. * u + 6. * t, 6. * u - 12. * t, 6. * t]
+    }
+}
+
+/// How a `Track` interpolates between two keyframes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TrackMode {
+    /// Holds the value of the previous keyframe until the next one is reached.
+    Step,
+
+    /// Linear interpolation between neighbouring keyframes, see `Interpolate::interpolate`.
+    Linear,
+
+    /// Cubic Hermite interpolation with Catmull-Rom tangents computed from the neighbouring
+    /// keyframes, taking non-uniform key times into account. The curve passes through all
+    /// keyframes with a continuous velocity. Rotation keys should lie in the same hemisphere,
+    /// i.e. have a positive dot product with their neighbours.
+    Cubic
+}
+
+/// A value at a point in time.
+#[derive(Debug, Copy, Clone)]
+pub struct Keyframe<T> {
+    pub time: f32,
+    pub value: T
+}
+
+/// A list of keyframes sorted by time, sampled at arbitrary points in time, e.g. the
+/// position of an object during an animation clip.
+#[derive(Debug, Clone)]
+pub struct Track<T> {
+    mode: TrackMode,
+    keys: Vec<Keyframe<T>>
+}
+
+impl<T: Interpolate> Track<T> {
+
+    /// Creates an empty track interpolating with the provided mode.
+    pub fn new(mode: TrackMode) -> Track<T> {
+        Track {mode, keys: Vec::new()}
+    }
+
+    /// Creates a track from keyframes in arbitrary order.
+    pub fn from_keyframes(mode: TrackMode, mut keys: Vec<Keyframe<T>>) -> Track<T> {
+        keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Track {mode, keys}
+    }
+
+    /// Inserts a keyframe at the provided time, replacing the value of an existing keyframe
+    /// at exactly that time.
+    pub fn insert(&mut self, time: f32, value: T) -> &mut Track<T> {
+        let i = self.keys.partition_point(|k| k.time < time);
+        if i < self.keys.len() && self.keys[i].time == time {
+            self.keys[i].value = value;
+        } else {
+            self.keys.insert(i, Keyframe {time, value});
+        }
+        self
+    }
+
+    /// The interpolation mode of this track.
+    pub fn mode(&self) -> TrackMode {
+        self.mode
+    }
+
+    /// Changes the interpolation mode of this track.
+    pub fn set_mode(&mut self, mode: TrackMode) -> &mut Track<T> {
+        self.mode = mode;
+        self
+    }
+
+    /// The keyframes sorted by time.
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keys
+    }
+
+    /// The number of keyframes.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the track has no keyframes.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The time of the first keyframe, `0` for an empty track.
+    pub fn start_time(&self) -> f32 {
+        self.keys.first().map_or(0., |k| k.time)
+    }
+
+    /// The time of the last keyframe, `0` for an empty track.
+    pub fn end_time(&self) -> f32 {
+        self.keys.last().map_or(0., |k| k.time)
+    }
+
+    /// The time between the first and the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.end_time() - self.start_time()
+    }
+
+    /// Samples the track at the provided time. Times before the first or after the last
+    /// keyframe return the value of that keyframe. Returns `None` for an empty track.
+    pub fn sample(&self, time: f32) -> Option<T> {
+        let first = self.keys.first()?;
+        let last = &self.keys[self.keys.len() - 1];
+        if time.is_nan() || time <= first.time {
+            return Some(first.value.clone());
+        }
+        if time >= last.time {
+            return Some(last.value.clone());
+        }
+
+        // the first keyframe after `time`, never 0 due to the checks above
+        let i = self.keys.partition_point(|k| k.time <= time);
+        let (k1, k2) = (&self.keys[i - 1], &self.keys[i]);
+        let dt = k2.time - k1.time;
+        let t = (time - k1.time) / dt;
+
+        Some(match self.mode {
+            TrackMode::Step => k1.value.clone(),
+            TrackMode::Linear => k1.value.interpolate(&k2.value, t),
+            TrackMode::Cubic => {
+                let m1 = self.tangent(i - 1, dt);
+                let m2 = self.tangent(i, dt);
+                hermite(&k1.value, &m1, &k2.value, &m2, t).renormalize()
+            }
+        })
+    }
+
+    /// Samples the track like `sample`, but repeats it endlessly, e.g. for a walk cycle.
+    /// The first and the last keyframe should hold the same value for a seamless loop.
+    pub fn sample_looped(&self, time: f32) -> Option<T> {
+        let duration = self.duration();
+        if duration <= 0. {
+            return self.sample(self.start_time());
+        }
+        self.sample(self.start_time() + (time - self.start_time()).rem_euclid(duration))
+    }
+
+    /// The Catmull-Rom tangent at keyframe `i` for a segment of duration `dt`. The tangent is
+    /// computed per unit time from the neighbours and scaled to the segment, one-sided
+    /// differences are used at the ends of the track.
+    fn tangent(&self, i: usize, dt: f32) -> T {
+        let prev = &self.keys[i.saturating_sub(1)];
+        let next = &self.keys[(i + 1).min(self.keys.len() - 1)];
+        let s = dt / (next.time - prev.time);
+        next.value.weighted_sum(s, &prev.value, -s)
+    }
+}
+
+#[cfg(test)]
+fn assert_near(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+}
+
+#[test]
+fn test_curve_end_points() {
+    let p0 = Vector3::from_i32(0, 0, 0);
+    let p1 = Vector3::from_i32(1, 2, 0);
+    let p2 = Vector3::from_i32(3, 2, 1);
+    let p3 = Vector3::from_i32(4, 0, 1);
+
+    assert_near(bezier(&p0, &p1, &p2, &p3, 0.).y, 0.);
+    assert_near(bezier(&p0, &p1, &p2, &p3, 1.).x, 4.);
+    assert_near(catmull_rom(&p0, &p1, &p2, &p3, 0.).y, 2.);
+    assert_near(catmull_rom(&p0, &p1, &p2, &p3, 1.).x, 3.);
+
+    // tangents of the bezier curve point to the inner control points
+    assert_near(bezier_derivative(&p0, &p1, &p2, &p3, 0.).y, 6.);
+    assert_near(bezier_derivative(&p0, &p1, &p2, &p3, 1.).y, -6.);
+
+    // catmull-rom is a hermite curve with central difference tangents
+    let m1 = lerp_derivative(&p0, &p2).weighted_sum(0.5, &p0, 0.);
+    let m2 = lerp_derivative(&p1, &p3).weighted_sum(0.5, &p0, 0.);
+    let a = catmull_rom(&p0, &p1, &p2, &p3, 0.3);
+    let b = hermite(&p1, &m1, &p2, &m2, 0.3);
+    assert_near(a.x, b.x);
+    assert_near(a.y, b.y);
+    assert_near(a.z, b.z);
+}
+
+#[test]
+fn test_derivatives_match_finite_differences() {
+    let p = [Vector4::from_i32(0, 1, 0, 1), Vector4::from_i32(2, -1, 3, 1),
+             Vector4::from_i32(1, 4, -2, 0), Vector4::from_i32(5, 2, 1, 3)];
+    let h = 1e-2;
+    for &t in &[0.2f32, 0.5, 0.8] {
+        let checks = [
+            (bezier_derivative(&p[0], &p[1], &p[2], &p[3], t),
+             bezier(&p[0], &p[1], &p[2], &p[3], t + h).weighted_sum(1., &bezier(&p[0], &p[1], &p[2], &p[3], t - h), -1.)),
+            (catmull_rom_derivative(&p[0], &p[1], &p[2], &p[3], t),
+             catmull_rom(&p[0], &p[1], &p[2], &p[3], t + h).weighted_sum(1., &catmull_rom(&p[0], &p[1], &p[2], &p[3], t - h), -1.)),
+            (hermite_derivative(&p[0], &p[1], &p[2], &p[3], t),
+             hermite(&p[0], &p[1], &p[2], &p[3], t + h).weighted_sum(1., &hermite(&p[0], &p[1], &p[2], &p[3], t - h), -1.)),
+            (smoothstep_derivative(&p[0], &p[1], t),
+             smoothstep(&p[0], &p[1], t + h).weighted_sum(1., &smoothstep(&p[0], &p[1], t - h), -1.)),
+        ];
+        for (d, diff) in checks.iter() {
+            for (a, b) in d.as_array().iter().zip(diff.as_array().iter()) {
+                assert!((a - b / (2. * h)).abs() < 1e-2, "{} != {}", a, b / (2. * h));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_track_modes() {
+    let mut track = Track::new(TrackMode::Step);
+    track.insert(1., 10f32).insert(0., 0.).insert(3., 20.).insert(1., 5.);
+    assert_eq!(track.len(), 3);
+    assert_eq!(track.keyframes()[1].value, 5.);
+    assert_eq!(track.duration(), 3.);
+
+    assert_eq!(track.sample(-1.), Some(0.));
+    assert_eq!(track.sample(0.5), Some(0.));
+    assert_eq!(track.sample(2.9), Some(5.));
+    assert_eq!(track.sample(4.), Some(20.));
+
+    track.set_mode(TrackMode::Linear);
+    assert_near(track.sample(2.).unwrap(), 12.5);
+
+    // the cubic track passes through all keys
+    track.set_mode(TrackMode::Cubic);
+    assert_near(track.sample(1.).unwrap(), 5.);
+    assert_near(track.sample(3.).unwrap(), 20.);
+
+    // keys on a straight line at uniform speed are interpolated linearly
+    let keys = vec![Keyframe {time: 2., value: 4f32}, Keyframe {time: 0., value: 0.}, Keyframe {time: 1., value: 2.}];
+    let track = Track::from_keyframes(TrackMode::Cubic, keys);
+    assert_near(track.sample(0.25).unwrap(), 0.5);
+    assert_near(track.sample(1.5).unwrap(), 3.);
+
+    assert!(Track::<f32>::new(TrackMode::Linear).sample(0.).is_none());
+}
+
+#[test]
+fn test_track_looped() {
+    let mut track = Track::new(TrackMode::Linear);
+    track.insert(1., Vector3::from_i32(0, 0, 0)).insert(3., Vector3::from_i32(2, 0, 0));
+    assert_near(track.sample_looped(2.).unwrap().x, 1.);
+    assert_near(track.sample_looped(6.).unwrap().x, 1.);
+    assert_near(track.sample_looped(-0.5).unwrap().x, 0.5);
+}
+
+#[test]
+fn test_track_matrix4() {
+    let mut a = Matrix4::new();
+    a.translation_mut(0., 0., 0.);
+    let mut b = Matrix4::new();
+    b.translation_mut(4., 0., 0.);
+    let mut track = Track::new(TrackMode::Linear);
+    track.insert(0., a).insert(1., b);
+    let m = track.sample(0.25).unwrap();
+    assert_near(m.m_3_0, 1.);
+    assert_near(m.m_0_0, 1.);
+}
+
+#[test]
+fn test_track_rotations_keep_unit_scale() {
+    let axis = Vector3::new_z_up();
+    let angle = 170f32.to_radians();
+    let a = Quaternion::new();
+    let b = Quaternion::from_axis_angle(&axis, angle);
+    let ta = Transform::from(&Vector3::new(), &a, &Vector3::from(1., 1., 1.));
+    let tb = Transform::from(&Vector3::from_i32(2, 0, 0), &b, &Vector3::from(1., 1., 1.));
+
+    for &mode in &[TrackMode::Linear, TrackMode::Cubic] {
+        let mut rotations = Track::new(mode);
+        rotations.insert(0., a).insert(1., b);
+        let q = rotations.sample(0.5).unwrap();
+        assert_near(q.length(), 1.);
+
+        let mut transforms = Track::new(mode);
+        transforms.insert(0., ta).insert(1., tb);
+        let m = transforms.sample(0.5).unwrap().to_matrix4();
+        let x = Vector3::from(m.m_0_0, m.m_0_1, m.m_0_2);
+        assert_near(x.length(), 1.);
+        assert_near(m.m_3_0, 1.);
+
+        // halfway there on the shorter path
+        let expected = Quaternion::from_axis_angle(&axis, angle * 0.5) * Vector3::from_i32(1, 0, 0);
+        assert!(x.distance(&expected) < 1e-4);
+    }
+}
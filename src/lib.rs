@@ -1,12 +1,30 @@
 //! `js_linalg` is probably the smallest linear algebra library on the planet. It comes without dependencies,
 //! is very fast, uses no number abstraction traits (all `f32`) and tailored for usage with OpenGL/WebGL. 
-//! It implements four structs; `Vector3`, `Vector4`, `Matrix3` and `Matrix4`. This is all you need for a happy and fulfilled life.
+//! It implements the structs `Vector2`, `Vector3`, `Vector4`, `Matrix2`, `Matrix3`, `Matrix4` and `Quaternion`. This is all you need for a happy and fulfilled life.
 //! 
 //! Please find more info in the README.md.
 
+/// Two dimensional vector of `f32`'s, e.g. for texture coordinates or UI layout.
+/// The two dimensions are accessible through the fields `x` and `y`.
+///
+/// The memory layout is guaranteed to be two consecutive `f32`'s in the order `x`, `y`
+/// without padding, see `Vector2::as_array` and `Vector2::slice_as_f32`.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32
+}
+
+mod vector2;
+
 /// Three dimensional vector of `f32`'s. 
 /// The three dimensions are accessible through the fields `x`, `y` and `z`.
+///
+/// The memory layout is guaranteed to be three consecutive `f32`'s in the order `x`, `y`, `z`
+/// without padding, see `Vector3::as_array` and `Vector3::slice_as_f32`.
 #[derive(Debug, Copy, Clone)]
+#[repr(C)]
 pub struct Vector3 {
     pub x: f32,
     pub y: f32,
@@ -17,7 +35,11 @@ mod vector3;
 
 /// Four dimensional vector of `f32`'s. The four dimensions are
 /// accessible through the fields `x`, `y`, `z` and `w`.
+///
+/// The memory layout is guaranteed to be four consecutive `f32`'s in the order `x`, `y`, `z`, `w`
+/// without padding, see `Vector4::as_array` and `Vector4::slice_as_f32`.
 #[derive(Debug, Copy, Clone)]
+#[repr(C)]
 pub struct Vector4 {
     /// The first dimension of `Vector4`.
     pub x: f32,
@@ -34,8 +56,28 @@ pub struct Vector4 {
 
 pub mod vector4;
 
-/// A 3x3 matrix of `f32`'s.
+/// A 2x2 matrix of `f32`'s. The field `m_c_r` holds column `c` and row `r`.
+///
+/// The memory layout is guaranteed to be four consecutive `f32`'s in column-major order, as
+/// expected by `glUniformMatrix2fv`, see `Matrix2::as_array` and `Matrix2::slice_as_f32`.
+#[derive(Debug, Clone)]
+#[repr(C)]
+pub struct Matrix2 {
+    pub m_0_0 : f32,
+    pub m_0_1 : f32,
+
+    pub m_1_0 : f32,
+    pub m_1_1 : f32,
+}
+
+mod matrix2;
+
+/// A 3x3 matrix of `f32`'s. The field `m_c_r` holds column `c` and row `r`.
+///
+/// The memory layout is guaranteed to be nine consecutive `f32`'s in column-major order, as
+/// expected by `glUniformMatrix3fv`, see `Matrix3::as_array`.
 #[derive(Debug, Clone)]
+#[repr(C)]
 pub struct Matrix3 {
     pub m_0_0 : f32,
     pub m_0_1 : f32,
@@ -53,8 +95,12 @@ pub struct Matrix3 {
 mod matrix3;
 
 
-/// A 4x4 matrix of `f32`'s.
+/// A 4x4 matrix of `f32`'s. The field `m_c_r` holds column `c` and row `r`.
+///
+/// The memory layout is guaranteed to be sixteen consecutive `f32`'s in column-major order, as
+/// expected by `glUniformMatrix4fv`, see `Matrix4::as_array`.
 #[derive(Debug, Clone)]
+#[repr(C)]
 pub struct Matrix4 {
     pub m_0_0 : f32,
     pub m_0_1 : f32,
@@ -77,4 +123,207 @@ pub struct Matrix4 {
     pub m_3_3 : f32
 }
 
-mod matrix4;
\ No newline at end of file
+mod matrix4;
+
+/// A quaternion of `f32`'s, usually used as unit quaternion to represent rotations.
+/// The vector part is accessible through `x`, `y` and `z`, the scalar part through `w`.
+///
+/// The memory layout is guaranteed to be four consecutive `f32`'s in the order `x`, `y`, `z`, `w`
+/// without padding, see `Quaternion::as_array` and `Quaternion::slice_as_f32`.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32
+}
+
+mod quaternion;
+
+/// A unit dual quaternion `real + ε dual` representing a rigid transformation (rotation
+/// followed by translation) without scaling, e.g. for dual quaternion skinning.
+/// The rotation is `real`, the translation `t` is encoded as `dual = 0.5 * t * real`.
+///
+/// The memory layout is guaranteed to be eight consecutive `f32`'s, `real` first, which
+/// matches a `mat2x4` (or two `vec4`) uniform in GLSL.
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct DualQuaternion {
+    pub real: Quaternion,
+    pub dual: Quaternion
+}
+
+mod dual_quaternion;
+
+/// The order of the three rotations of Euler angles, see `Matrix3::from_euler` and
+/// `Matrix3::to_euler`. The rotations are about the fixed (world) axes and applied in the order
+/// of the name, e.g. `XYZ` rotates about `x` first and about `z` last, so the matrix is
+/// `Rz * Ry * Rx`. This is the same as rotating about the rotating (local) axes in reverse
+/// order, i.e. `XYZ` is intrinsic `z-y'-x''` (yaw, pitch, roll).
+///
+/// The first six orders are Tait-Bryan angles using three different axes, the last six are
+/// proper Euler angles where the first and the last axis are the same.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+    XYX,
+    XZX,
+    YXY,
+    YZY,
+    ZXZ,
+    ZYZ
+}
+
+mod euler;
+
+/// An affine transform split into its parts, see `Matrix4::decompose` and `Matrix4::compose_mut`.
+/// The parts are applied to a point in the order scale, shear, rotation, translation, i.e. the
+/// composed matrix is `T * R * H * S`.
+#[derive(Debug, Copy, Clone)]
+pub struct Transform {
+    /// The translation, i.e. the fourth column of the composed matrix.
+    pub translation: Vector3,
+
+    /// The rotation as unit quaternion.
+    pub rotation: Quaternion,
+
+    /// The scale along the local `x`, `y` and `z` axes. May be negative for mirroring.
+    pub scale: Vector3,
+
+    /// The shear factors `xy` (in `x`), `xz` (in `y`) and `yz` (in `z`): the shear matrix `H`
+    /// maps `(x, y, z)` to `(x + xy * y + xz * z, y + yz * z, z)`. All zero for plain TRS transforms.
+    pub shear: Vector3
+}
+
+mod transform;
+/// A half-line starting at `origin` and pointing into `direction`, typically
+/// used for picking. See `Ray::from_screen`.
+#[derive(Debug, Copy, Clone)]
+pub struct Ray {
+    /// The point where the ray starts.
+    pub origin: Vector3,
+
+    /// The direction of the ray, usually of unit length.
+    pub direction: Vector3
+}
+
+/// The result of a ray cast, see `Ray::intersect_sphere` and friends.
+#[derive(Debug, Copy, Clone)]
+pub struct RayHit {
+    /// The parameter `t` of the hit point, see `Ray::point_at`. This is the distance from the
+    /// origin if the direction of the ray is of unit length.
+    pub distance: f32,
+
+    /// The hit point in the same space as the ray.
+    pub point: Vector3,
+
+    /// The unit normal of the surface at the hit point, pointing out of the hit shape.
+    pub normal: Vector3
+}
+
+/// The result of a ray cast against a triangle, see `Ray::intersect_triangle`.
+#[derive(Debug, Copy, Clone)]
+pub struct TriangleHit {
+    pub hit: RayHit,
+
+    /// The barycentric coordinates `(u, v, w)` of the hit point, i.e. it is
+    /// `u * a + v * b + w * c`, e.g. to interpolate texture coordinates.
+    pub barycentric: Vector3
+}
+
+mod ray;
+
+/// The range normalized device coordinates use for depth. OpenGL/WebGL use `-1..1`
+/// by default, WebGPU, Vulkan and Direct3D use `0..1`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DepthRange {
+    /// Depth in `-1..1`, the OpenGL/WebGL default.
+    NegativeOneToOne,
+
+    /// Depth in `0..1` as used by WebGPU/Vulkan, or OpenGL with `glClipControl`.
+    ZeroToOne
+}
+
+/// A viewport rectangle in window coordinates as passed to `glViewport`, i.e.
+/// with the origin in the lower-left corner.
+#[derive(Debug, Copy, Clone)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+
+    /// The depth range of the normalized device coordinates the projection matrix produces.
+    pub depth_range: DepthRange
+}
+
+mod viewport;
+
+/// A view frustum given by six normalized planes with normals pointing inwards, used for
+/// culling bounding volumes before issuing draw calls. See `Frustum::from_matrix4`.
+#[derive(Debug, Copy, Clone)]
+pub struct Frustum {
+    /// The planes in the order left, right, bottom, top, near, far.
+    pub planes: [geometry::Plane; 6]
+}
+
+mod frustum;
+
+/// The error returned by the `try_` variants of operations that would otherwise panic or
+/// silently produce NaN/inf components, e.g. `Matrix4::try_inverse` or `Vector3::try_normalize`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The matrix has a determinant of (almost) zero relative to the length of its columns, or a
+    /// non-finite one, and cannot be inverted.
+    SingularMatrix,
+
+    /// The vector or quaternion has a length of (almost) zero, or a non-finite length, and
+    /// cannot be normalized, inverted or projected onto.
+    ZeroLength,
+
+    /// A row or column index is outside of the matrix dimension.
+    IndexOutOfRange {
+        /// The provided index.
+        index: u32,
+        /// The number of rows or columns of the matrix.
+        len: u32
+    },
+
+    /// The up vector of a look-at is parallel to the viewing direction, so no camera
+    /// orientation can be derived from it.
+    ParallelUpVector,
+
+    /// The number of control points does not fit the kind of curve, see `spline::SplineKind`.
+    InvalidPointCount {
+        /// The provided number of points.
+        count: u32
+    }
+}
+
+mod error;
+
+pub mod geometry;
+
+pub mod bvh;
+
+pub mod matrix_stack;
+
+pub mod hierarchy;
+
+pub mod camera;
+
+pub mod packing;
+
+pub mod interpolation;
+
+pub mod spline;
+
+pub mod skeleton;
+
+pub mod ik;
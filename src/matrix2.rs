@@ -0,0 +1,358 @@
+use super::{Matrix2, Vector2, Error};
+
+impl Matrix2 {
+
+    /// Creates a new `Matrix2` struct as identity matrix.
+    pub fn new() -> Matrix2 {
+        Matrix2 {
+            m_0_0: 1.0,
+            m_1_0: 0.0,
+
+            m_0_1: 0.0,
+            m_1_1: 1.0,
+        }
+    }
+
+    /// Creates a new `Matrix2` by copying the components of the provided one.
+    pub fn from_matrix2(m:&Matrix2) -> Matrix2 {
+        Matrix2 {
+            m_0_0: m.m_0_0,
+            m_1_0: m.m_1_0,
+
+            m_0_1: m.m_0_1,
+            m_1_1: m.m_1_1,
+        }
+    }
+
+    /// Sets the matrix to identity.
+    pub fn identity_mut(&mut self) -> &mut Matrix2 {
+        self.m_0_0 = 1.0;
+        self.m_1_0 = 0.0;
+        self.m_0_1 = 0.0;
+        self.m_1_1 = 1.0;
+
+        self
+    }
+
+    /// "Copies" the provided matrix components onto this matrix.
+    pub fn set_matrix2(&mut self, m:&Matrix2) -> &mut Matrix2 {
+        self.m_0_0 = m.m_0_0;
+        self.m_0_1 = m.m_0_1;
+        self.m_1_0 = m.m_1_0;
+        self.m_1_1 = m.m_1_1;
+
+        self
+    }
+
+    /// Sets the matrix to a counter-clockwise rotation of `alpha` radians.
+    pub fn rotation_mut(&mut self, alpha:f32) -> &mut Matrix2 {
+        let (s, c) = f32::sin_cos(alpha);
+
+        self.m_0_0 = c;
+        self.m_1_0 = -s;
+        self.m_0_1 = s;
+        self.m_1_1 = c;
+
+        self
+    }
+
+    /// Sets this matrix to a scaling matrix.
+    pub fn scale_mut(&mut self, x:f32, y:f32) -> &mut Matrix2 {
+        self.m_0_0 = x;
+        self.m_1_0 = 0.0;
+        self.m_0_1 = 0.0;
+        self.m_1_1 = y;
+
+        self
+    }
+
+    /// Computes the determinant of this matrix.
+    pub fn determinant(&self) -> f32 {
+        self.m_0_0 * self.m_1_1 - self.m_1_0 * self.m_0_1
+    }
+
+    /// In-place inversion of this matrix. A singular matrix ends up with NaN/inf components.
+    pub fn inverse_mut(&mut self) -> &mut Matrix2 {
+        let det = self.determinant();
+        let a = self.m_0_0;
+
+        self.m_0_0 = self.m_1_1 / det;
+        self.m_1_1 = a / det;
+        self.m_1_0 = -self.m_1_0 / det;
+        self.m_0_1 = -self.m_0_1 / det;
+
+        self
+    }
+
+    /// Computes the inverse of this matrix and returns it as a new `Matrix2`.
+    pub fn inverse(&self) -> Matrix2 {
+        let mut m = Matrix2::from_matrix2(self);
+        m.inverse_mut();
+        m
+    }
+
+    /// Computes the inverse of this matrix and returns it as a new `Matrix2`, or
+    /// `Error::SingularMatrix` if the matrix is singular, see `try_inverse_mut`.
+    pub fn try_inverse(&self) -> Result<Matrix2, Error> {
+        let mut m = Matrix2::from_matrix2(self);
+        m.try_inverse_mut()?;
+        Ok(m)
+    }
+
+    /// In-place inversion like `inverse_mut`, but leaves this matrix untouched and returns
+    /// `Error::SingularMatrix` if the matrix is singular or not finite. The determinant is
+    /// compared against the product of the column lengths, so a uniformly scaled matrix is
+    /// accepted no matter how small the scale.
+    pub fn try_inverse_mut(&mut self) -> Result<&mut Matrix2, Error> {
+        let det = self.determinant();
+        let bound = Vector2::from(self.m_0_0, self.m_0_1).length()
+            * Vector2::from(self.m_1_0, self.m_1_1).length();
+        if !(det.abs() > f32::EPSILON * bound && det.is_finite()) {
+            return Err(Error::SingularMatrix);
+        }
+        Ok(self.inverse_mut())
+    }
+
+    /// In-place transposition of this matrix.
+    pub fn transpose_mut(&mut self) -> &mut Matrix2 {
+        std::mem::swap(&mut self.m_0_1, &mut self.m_1_0);
+        self
+    }
+
+    /// In-place multiplication `self = self * that`.
+    pub fn mult_mut(&mut self, that:&Matrix2) -> &mut Matrix2 {
+        let m00 = self.m_0_0 * that.m_0_0 + self.m_1_0 * that.m_0_1;
+        let m01 = self.m_0_1 * that.m_0_0 + self.m_1_1 * that.m_0_1;
+        let m10 = self.m_0_0 * that.m_1_0 + self.m_1_0 * that.m_1_1;
+        let m11 = self.m_0_1 * that.m_1_0 + self.m_1_1 * that.m_1_1;
+
+        self.m_0_0 = m00;
+        self.m_0_1 = m01;
+        self.m_1_0 = m10;
+        self.m_1_1 = m11;
+
+        self
+    }
+
+    /// Multiplies this matrix with the provided vector and writes the result back to the vector.
+    pub fn mult_to_vector2(&self, vec:&mut Vector2) {
+        let nx = vec.x * self.m_0_0 + vec.y * self.m_1_0;
+        let ny = vec.x * self.m_0_1 + vec.y * self.m_1_1;
+
+        vec.x = nx;
+        vec.y = ny;
+    }
+
+    /// Returns the components as array reference without copying. This is possible since the
+    /// memory layout of `Matrix2` is guaranteed to be `[f32; 4]`.
+    pub fn as_array(&self) -> &[f32; 4] {
+        // SAFETY: `Matrix2` is `#[repr(C)]` and consists of 4 `f32`'s without padding, so it
+        // has the size and alignment of `[f32; 4]`; the result borrows `self`.
+        unsafe { &*(self as *const Matrix2 as *const [f32; 4]) }
+    }
+
+    /// Returns the components as mutable array reference without copying.
+    pub fn as_mut_array(&mut self) -> &mut [f32; 4] {
+        // SAFETY: same layout argument as in `as_array`, the exclusive borrow of `self` is
+        // handed over to the returned array.
+        unsafe { &mut *(self as *mut Matrix2 as *mut [f32; 4]) }
+    }
+
+    /// Returns the components as slice without copying.
+    pub fn as_slice(&self) -> &[f32] {
+        self.as_array()
+    }
+
+    /// Returns a pointer to the first component (`m_0_0`), e.g. for `glUniform*` calls.
+    /// The pointer is valid for 4 `f32`'s as long as this `Matrix2` lives.
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Matrix2 as *const f32
+    }
+
+    /// Reinterprets a slice of `Matrix2`'s as one flat slice of `f32`'s without copying, e.g. for
+    /// uploading an array of matrix uniforms. The result has `4 * values.len()` elements,
+    /// starting with `m_0_0` of the first `Matrix2` and ending with `m_1_1` of the last one.
+    pub fn slice_as_f32(values: &[Matrix2]) -> &[f32] {
+        // SAFETY: every element is 4 consecutive `f32`'s without padding (`#[repr(C)]`) and
+        // slice elements are contiguous, so the memory is exactly `4 * len` initialized `f32`'s
+        // borrowed for the lifetime of `values`.
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const f32, values.len() * 4) }
+    }
+}
+
+impl Default for Matrix2 {
+    /// Same as `Matrix2::new()`.
+    fn default() -> Matrix2 {
+        Matrix2::new()
+    }
+}
+
+impl std::ops::Mul<Matrix2> for Matrix2 {
+    type Output = Matrix2;
+
+    /// Overrides '*' operator to multiply a `Matrix2` with another `Matrix2`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Matrix2;
+    /// let mut m = Matrix2::new();
+    /// m.rotation_mut(0.5);
+    /// let c = m.clone() * m.inverse();
+    /// assert!((c.m_0_0 - 1.).abs() < 1e-6);
+    /// ```
+    fn mul(self, rhs: Matrix2) -> Matrix2 {
+        let mut a = self;
+        a.mult_mut(&rhs);
+        a
+    }
+}
+
+impl std::ops::Mul<&Matrix2> for Matrix2 {
+    type Output = Matrix2;
+
+    fn mul(self, rhs: &Matrix2) -> Matrix2 {
+        let mut a = self;
+        a.mult_mut(rhs);
+        a
+    }
+}
+
+impl std::ops::Mul<&Matrix2> for &Matrix2 {
+    type Output = Matrix2;
+
+    fn mul(self, rhs: &Matrix2) -> Matrix2 {
+        let mut a = Matrix2::from_matrix2(self);
+        a.mult_mut(rhs);
+        a
+    }
+}
+
+impl std::ops::Mul<Matrix2> for &Matrix2 {
+    type Output = Matrix2;
+
+    fn mul(self, rhs: Matrix2) -> Matrix2 {
+        let mut a = Matrix2::from_matrix2(self);
+        a.mult_mut(&rhs);
+        a
+    }
+}
+
+impl std::ops::MulAssign<&Matrix2> for Matrix2 {
+
+    fn mul_assign(&mut self, m: &Matrix2) {
+        self.mult_mut(m);
+    }
+}
+
+impl std::ops::MulAssign<Matrix2> for Matrix2 {
+
+    fn mul_assign(&mut self, m: Matrix2) {
+        self.mult_mut(&m);
+    }
+}
+
+impl std::ops::Mul<Vector2> for Matrix2 {
+    type Output = Vector2;
+
+    /// Overrides '*' operator to multiply a `Matrix2` with a `Vector2`.
+    fn mul(self, rhs: Vector2) -> Vector2 {
+        let mut b = rhs;
+        self.mult_to_vector2(&mut b);
+        b
+    }
+}
+
+impl std::ops::Mul<Vector2> for &Matrix2 {
+    type Output = Vector2;
+
+    /// Overrides '*' operator to multiply a `Matrix2` with a `Vector2`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix2, Vector2};
+    /// let mut m = Matrix2::new();
+    /// m.rotation_mut(std::f32::consts::FRAC_PI_2);
+    /// let v = &m * Vector2::from_i32(1, 0);
+    /// assert!((v.y - 1.).abs() < 1e-6);
+    /// ```
+    fn mul(self, rhs: Vector2) -> Vector2 {
+        let mut b = rhs;
+        self.mult_to_vector2(&mut b);
+        b
+    }
+}
+
+impl std::ops::Mul<&Vector2> for Matrix2 {
+    type Output = Vector2;
+
+    /// Overrides '*' operator to multiply a `Matrix2` with a `Vector2`.
+    fn mul(self, rhs: &Vector2) -> Vector2 {
+        let mut b = *rhs;
+        self.mult_to_vector2(&mut b);
+        b
+    }
+}
+
+impl std::ops::Mul<&Vector2> for &Matrix2 {
+    type Output = Vector2;
+
+    /// Overrides '*' operator to multiply a `Matrix2` with a `Vector2`.
+    fn mul(self, rhs: &Vector2) -> Vector2 {
+        let mut b = *rhs;
+        self.mult_to_vector2(&mut b);
+        b
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn test_mul_matrix2_completeness() {
+    let a = Matrix2::new();
+    let b = Matrix2::new();
+    let _c = &a * &b;
+    let _c = a.clone() * b.clone();
+    let _c = a.clone() * &b;
+    let _c = &a * b;
+    let v = Vector2::new();
+    let _v = &a * v;
+    let _v = &a * &v;
+    let _v = a.clone() * &v;
+    let _v = a * v;
+}
+
+#[test]
+fn test_inverse_matrix2() {
+    let mut m = Matrix2::new();
+    m.rotation_mut(0.3);
+    let mut s = Matrix2::new();
+    s.scale_mut(2., -3.);
+    m.mult_mut(&s);
+
+    let i = &m * m.inverse();
+    assert!((i.m_0_0 - 1.).abs() < 1e-6);
+    assert!(i.m_1_0.abs() < 1e-6);
+    assert!(i.m_0_1.abs() < 1e-6);
+    assert!((i.m_1_1 - 1.).abs() < 1e-6);
+    assert!((m.determinant() + 6.).abs() < 1e-5);
+
+    let mut singular = Matrix2::new();
+    singular.scale_mut(0., 1.);
+    assert_eq!(singular.try_inverse().unwrap_err(), Error::SingularMatrix);
+    assert!(m.try_inverse_mut().is_ok());
+
+    let mut small = Matrix2::new();
+    small.scale_mut(0.0001, 0.0001);
+    let i = &small * small.try_inverse().unwrap();
+    assert!((i.m_0_0 - 1.).abs() < 1e-6);
+    assert!((i.m_1_1 - 1.).abs() < 1e-6);
+}
+
+#[test]
+fn test_matrix2_memory_layout() {
+    assert_eq!(std::mem::size_of::<Matrix2>(), 4 * std::mem::size_of::<f32>());
+
+    let mut m = Matrix2::new();
+    m.m_1_0 = 3.;
+    assert_eq!(m.as_array(), &[1., 0., 3., 1.]);
+    assert_eq!(Matrix2::slice_as_f32(&[m.clone(), m]), &[1., 0., 3., 1., 1., 0., 3., 1.]);
+}
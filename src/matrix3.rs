@@ -1,4 +1,5 @@
-use super::{Matrix3, Matrix4};
+use super::{Matrix3, Matrix4, Vector2, Vector3, EulerOrder, Error};
+use super::euler::axis_rotation_mut;
 
 impl Matrix3 {
 
@@ -19,6 +20,70 @@ impl Matrix3 {
         }
     }
 
+    /// Creates a new `Matrix3` by copying the components of the provided one.
+    pub fn from_matrix3(m:&Matrix3) -> Matrix3 {
+        Matrix3 {
+            m_0_0: m.m_0_0,
+            m_1_0: m.m_1_0,
+            m_2_0: m.m_2_0,
+
+            m_0_1: m.m_0_1,
+            m_1_1: m.m_1_1,
+            m_2_1: m.m_2_1,
+
+            m_0_2: m.m_0_2,
+            m_1_2: m.m_1_2,
+            m_2_2: m.m_2_2,
+        }
+    }
+
+    /// Creates a new `Matrix3` from the upper-left 3x3 block of the provided `Matrix4`, i.e.
+    /// its rotation and scale without the translation.
+    pub fn from_matrix4(m:&Matrix4) -> Matrix3 {
+        Matrix3 {
+            m_0_0: m.m_0_0,
+            m_1_0: m.m_1_0,
+            m_2_0: m.m_2_0,
+
+            m_0_1: m.m_0_1,
+            m_1_1: m.m_1_1,
+            m_2_1: m.m_2_1,
+
+            m_0_2: m.m_0_2,
+            m_1_2: m.m_1_2,
+            m_2_2: m.m_2_2,
+        }
+    }
+
+    /// Embeds this matrix into the upper-left 3x3 block of a new `Matrix4` without translation.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Matrix3;
+    /// let mut m = Matrix3::new();
+    /// m.scale_mut(2., 3., 4.);
+    /// let m4 = m.to_matrix4();
+    /// assert_eq!(m4.m_2_2, 4.);
+    /// assert_eq!(m4.m_3_3, 1.);
+    /// ```
+    pub fn to_matrix4(&self) -> Matrix4 {
+        let mut m = Matrix4::new();
+
+        m.m_0_0 = self.m_0_0;
+        m.m_1_0 = self.m_1_0;
+        m.m_2_0 = self.m_2_0;
+
+        m.m_0_1 = self.m_0_1;
+        m.m_1_1 = self.m_1_1;
+        m.m_2_1 = self.m_2_1;
+
+        m.m_0_2 = self.m_0_2;
+        m.m_1_2 = self.m_1_2;
+        m.m_2_2 = self.m_2_2;
+
+        m
+    }
+
     pub fn calc_mut_normal_matrix(&mut self, view: &Matrix4) -> &mut Matrix3 {
         self.m_0_0 = view.m_0_0;
         self.m_1_0 = view.m_1_0;
@@ -44,7 +109,7 @@ impl Matrix3 {
         m        
     }
 
-    pub fn set_matrix3(&mut self, m:&Matrix3) {
+    pub fn set_matrix3(&mut self, m:&Matrix3) -> &mut Matrix3 {
         self.m_0_0 = m.m_0_0;
         self.m_0_1 = m.m_0_1;
         self.m_0_2 = m.m_0_2;
@@ -54,9 +119,20 @@ impl Matrix3 {
         self.m_2_0 = m.m_2_0;
         self.m_2_1 = m.m_2_1;
         self.m_2_2 = m.m_2_2;
+
+        self
+    }
+
+    /// Computes the determinant of this matrix.
+    pub fn determinant(&self) -> f32 {
+        self.m_0_0 * (self.m_1_1 * self.m_2_2 - self.m_2_1 * self.m_1_2)
+            - self.m_1_0 * (self.m_0_1 * self.m_2_2 - self.m_2_1 * self.m_0_2)
+            + self.m_2_0 * (self.m_0_1 * self.m_1_2 - self.m_1_1 * self.m_0_2)
     }
 
-    pub fn inverse_mut(&mut self) {
+    /// In-place inversion of this matrix. A singular matrix ends up with NaN/inf components,
+    /// use `try_inverse` if the matrix may be singular.
+    pub fn inverse_mut(&mut self) -> &mut Matrix3 {
         let a = self.m_0_0;
         let b = self.m_1_0;
         let c = self.m_2_0;
@@ -78,9 +154,49 @@ impl Matrix3 {
         self.m_0_2 = (d * h - e * g) / det;    // C
         self.m_1_2 = -(a * h - b * g) / det;    // F
         self.m_2_2 = (a * e - b * d) / det;    // I
+
+        self
+    }
+
+    /// Computes the inverse of this matrix and returns it as a new `Matrix3`. See `inverse_mut`.
+    pub fn inverse(&self) -> Matrix3 {
+        let mut m = Matrix3::from_matrix3(self);
+        m.inverse_mut();
+        m
+    }
+
+    /// Computes the inverse of this matrix and returns it as a new `Matrix3`, or
+    /// `Error::SingularMatrix` if the matrix is singular, see `try_inverse_mut`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Error, Matrix3};
+    /// let mut a = Matrix3::new();
+    /// a.scale_mut(1., 0., 1.);
+    /// assert_eq!(a.try_inverse().unwrap_err(), Error::SingularMatrix);
+    /// ```
+    pub fn try_inverse(&self) -> Result<Matrix3, Error> {
+        let mut m = Matrix3::from_matrix3(self);
+        m.try_inverse_mut()?;
+        Ok(m)
     }
 
-    pub fn transpose_mut(&mut self) {
+    /// In-place inversion like `inverse_mut`, but leaves this matrix untouched and returns
+    /// `Error::SingularMatrix` if the matrix is singular or not finite. The determinant is
+    /// compared against the product of the column lengths, so a uniformly scaled matrix is
+    /// accepted no matter how small the scale.
+    pub fn try_inverse_mut(&mut self) -> Result<&mut Matrix3, Error> {
+        let det = self.determinant();
+        let bound = Vector3::from(self.m_0_0, self.m_0_1, self.m_0_2).length()
+            * Vector3::from(self.m_1_0, self.m_1_1, self.m_1_2).length()
+            * Vector3::from(self.m_2_0, self.m_2_1, self.m_2_2).length();
+        if !(det.abs() > f32::EPSILON * bound && det.is_finite()) {
+            return Err(Error::SingularMatrix);
+        }
+        Ok(self.inverse_mut())
+    }
+
+    pub fn transpose_mut(&mut self) -> &mut Matrix3 {
         let mut tmp;
 
         tmp        = self.m_0_1;
@@ -94,5 +210,618 @@ impl Matrix3 {
         tmp        = self.m_1_2;
         self.m_1_2 = self.m_2_1;
         self.m_2_1 = tmp;
+
+        self
+    }
+
+    /// Sets the matrix to a rotation of `alpha` radians around the provided (unit) axis.
+    pub fn rotation_mut(&mut self, alpha: f32, u: &Vector3) -> &mut Matrix3 {
+        let (s, c) = f32::sin_cos(alpha);
+        let t = 1.0 - c;
+
+        self.m_0_0 = t * u.x * u.x + c;
+        self.m_1_0 = t * u.x * u.y - u.z * s;
+        self.m_2_0 = t * u.x * u.z + u.y * s;
+        self.m_0_1 = t * u.y * u.x + u.z * s;
+        self.m_1_1 = t * u.y * u.y + c;
+        self.m_2_1 = t * u.y * u.z - u.x * s;
+        self.m_0_2 = t * u.z * u.x - u.y * s;
+        self.m_1_2 = t * u.z * u.y + u.x * s;
+        self.m_2_2 = t * u.z * u.z + c;
+
+        self
+    }
+
+    /// Sets the matrix to a rotation of `alpha` radians around the x axis.
+    pub fn rotation_x_mut(&mut self, alpha: f32) -> &mut Matrix3 {
+        let (s, c) = f32::sin_cos(alpha);
+
+        self.identity_mut();
+
+        self.m_1_1 = c;
+        self.m_2_1 = -s;
+        self.m_1_2 = s;
+        self.m_2_2 = c;
+
+        self
+    }
+
+    /// Sets the matrix to a rotation of `alpha` radians around the y axis.
+    pub fn rotation_y_mut(&mut self, alpha: f32) -> &mut Matrix3 {
+        let (s, c) = f32::sin_cos(alpha);
+
+        self.identity_mut();
+
+        self.m_0_0 = c;
+        self.m_2_0 = s;
+        self.m_0_2 = -s;
+        self.m_2_2 = c;
+
+        self
+    }
+
+    /// Sets the matrix to a rotation of `alpha` radians around the z axis.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix3, Vector3};
+    /// let mut m = Matrix3::new();
+    /// m.rotation_z_mut(std::f32::consts::FRAC_PI_2);
+    /// let v = &m * Vector3::from_i32(1, 0, 0);
+    /// assert!((v.y - 1.).abs() < 1e-6);
+    /// ```
+    pub fn rotation_z_mut(&mut self, alpha: f32) -> &mut Matrix3 {
+        let (s, c) = f32::sin_cos(alpha);
+
+        self.identity_mut();
+
+        self.m_0_0 = c;
+        self.m_1_0 = -s;
+        self.m_0_1 = s;
+        self.m_1_1 = c;
+
+        self
+    }
+
+    /// Creates a new rotation `Matrix3` from Euler angles in radians. `angles.x`, `angles.y`
+    /// and `angles.z` are the angles of the first, second and third rotation of `order`, which
+    /// for `EulerOrder::ZYX` means that `angles.x` rotates about `z`. See `EulerOrder`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{EulerOrder, Matrix3, Vector3};
+    /// let m = Matrix3::from_euler(EulerOrder::ZYX, &Vector3::from(0.5, 0., 0.));
+    /// let mut r = Matrix3::new();
+    /// r.rotation_z_mut(0.5);
+    /// assert_eq!(m.as_array(), r.as_array());
+    /// ```
+    pub fn from_euler(order: EulerOrder, angles: &Vector3) -> Matrix3 {
+        let mut m = Matrix3::new();
+        m.rotation_euler_mut(order, angles);
+        m
+    }
+
+    /// Sets this matrix to the rotation described by Euler angles, see `from_euler`.
+    pub fn rotation_euler_mut(&mut self, order: EulerOrder, angles: &Vector3) -> &mut Matrix3 {
+        let (i, j, k) = order.axes();
+
+        axis_rotation_mut(self, k, angles.z);
+        self.mult_mut(axis_rotation_mut(&mut Matrix3::new(), j, angles.y));
+        self.mult_mut(axis_rotation_mut(&mut Matrix3::new(), i, angles.x));
+
+        self
+    }
+
+    /// Extracts Euler angles in radians from this rotation matrix, the inverse of `from_euler`.
+    /// The middle angle is in `-PI/2..PI/2` for Tait-Bryan orders and in `0..PI` for proper
+    /// Euler orders, the others in `-PI..PI`.
+    ///
+    /// In gimbal lock, i.e. if the middle rotation aligns the first and the last axis, only
+    /// the sum (or difference) of the outer angles is defined. The third angle is then set to
+    /// zero and the whole rotation is put into the first one.
+    pub fn to_euler(&self, order: EulerOrder) -> Vector3 {
+        // anything below this is treated as gimbal lock, f32 rotation matrices are not more accurate
+        const LOCK: f32 = 1e-6;
+
+        let (i, j, _) = order.axes();
+        let k = 3 - i - j;
+        let r = |row: usize, col: usize| self.as_array()[col * 3 + row];
+
+        // +1 for cyclic axis orders like x, y, z
+        let s = if (j + 3 - i) % 3 == 1 { 1.0 } else { -1.0 };
+
+        if order.is_proper() {
+            let sb = (r(j, i) * r(j, i) + r(k, i) * r(k, i)).sqrt();
+            let b = f32::atan2(sb, r(i, i));
+
+            if sb > LOCK {
+                let a = f32::atan2(r(i, j), s * r(i, k));
+                let c = f32::atan2(r(j, i), -s * r(k, i));
+                Vector3::from(a, b, c)
+            } else {
+                let a = f32::atan2(s * r(i, i).signum() * r(k, j), r(j, j));
+                Vector3::from(a, b, 0.0)
+            }
+        } else {
+            let cb = (r(i, i) * r(i, i) + r(j, i) * r(j, i)).sqrt();
+            let b = f32::atan2(-s * r(k, i), cb);
+
+            if cb > LOCK {
+                let a = f32::atan2(s * r(k, j), r(k, k));
+                let c = f32::atan2(s * r(j, i), r(i, i));
+                Vector3::from(a, b, c)
+            } else {
+                let a = f32::atan2(-s * r(j, k), r(j, j));
+                Vector3::from(a, b, 0.0)
+            }
+        }
+    }
+
+    /// Sets this matrix to a scaling matrix.
+    pub fn scale_mut(&mut self, x: f32, y: f32, z: f32) -> &mut Matrix3 {
+        self.identity_mut();
+
+        self.m_0_0 = x;
+        self.m_1_1 = y;
+        self.m_2_2 = z;
+
+        self
+    }
+
+    /// Multiplies this matrix with the provided vector and writes the result back to the vector.
+    pub fn mult_to_vector3(&self, vec: &mut Vector3) {
+        let nx = vec.x * self.m_0_0 + vec.y * self.m_1_0 + vec.z * self.m_2_0;
+        let ny = vec.x * self.m_0_1 + vec.y * self.m_1_1 + vec.z * self.m_2_1;
+        let nz = vec.x * self.m_0_2 + vec.y * self.m_1_2 + vec.z * self.m_2_2;
+
+        vec.x = nx;
+        vec.y = ny;
+        vec.z = nz;
+    }
+
+    /// Sets the matrix to identity.
+    pub fn identity_mut(&mut self) -> &mut Matrix3 {
+        self.m_0_0 = 1.0;
+        self.m_1_0 = 0.0;
+        self.m_2_0 = 0.0;
+
+        self.m_0_1 = 0.0;
+        self.m_1_1 = 1.0;
+        self.m_2_1 = 0.0;
+
+        self.m_0_2 = 0.0;
+        self.m_1_2 = 0.0;
+        self.m_2_2 = 1.0;
+
+        self
+    }
+
+    /// In-place multiplication `self = self * that`.
+    pub fn mult_mut(&mut self, that: &Matrix3) -> &mut Matrix3 {
+        let m00 = self.m_0_0 * that.m_0_0 + self.m_1_0 * that.m_0_1 + self.m_2_0 * that.m_0_2;
+        let m01 = self.m_0_1 * that.m_0_0 + self.m_1_1 * that.m_0_1 + self.m_2_1 * that.m_0_2;
+        let m02 = self.m_0_2 * that.m_0_0 + self.m_1_2 * that.m_0_1 + self.m_2_2 * that.m_0_2;
+
+        let m10 = self.m_0_0 * that.m_1_0 + self.m_1_0 * that.m_1_1 + self.m_2_0 * that.m_1_2;
+        let m11 = self.m_0_1 * that.m_1_0 + self.m_1_1 * that.m_1_1 + self.m_2_1 * that.m_1_2;
+        let m12 = self.m_0_2 * that.m_1_0 + self.m_1_2 * that.m_1_1 + self.m_2_2 * that.m_1_2;
+
+        let m20 = self.m_0_0 * that.m_2_0 + self.m_1_0 * that.m_2_1 + self.m_2_0 * that.m_2_2;
+        let m21 = self.m_0_1 * that.m_2_0 + self.m_1_1 * that.m_2_1 + self.m_2_1 * that.m_2_2;
+        let m22 = self.m_0_2 * that.m_2_0 + self.m_1_2 * that.m_2_1 + self.m_2_2 * that.m_2_2;
+
+        self.m_0_0 = m00;
+        self.m_0_1 = m01;
+        self.m_0_2 = m02;
+
+        self.m_1_0 = m10;
+        self.m_1_1 = m11;
+        self.m_1_2 = m12;
+
+        self.m_2_0 = m20;
+        self.m_2_1 = m21;
+        self.m_2_2 = m22;
+
+        self
+    }
+
+    /// Sets this matrix to a 2D translation matrix, for use with `Vector2` in homogeneous
+    /// coordinates (see `mult_to_vector2`).
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix3, Vector2};
+    /// let mut m = Matrix3::new();
+    /// m.translation_2d_mut(10., 20.);
+    /// let v = &m * Vector2::from_i32(1, 2);
+    /// assert_eq!(v.x, 11.);
+    /// assert_eq!(v.y, 22.);
+    /// ```
+    pub fn translation_2d_mut(&mut self, x: f32, y: f32) -> &mut Matrix3 {
+        self.identity_mut();
+
+        self.m_2_0 = x;
+        self.m_2_1 = y;
+
+        self
+    }
+
+    /// Sets this matrix to a 2D counter-clockwise rotation of `alpha` radians around the origin.
+    pub fn rotation_2d_mut(&mut self, alpha: f32) -> &mut Matrix3 {
+        let (s, c) = f32::sin_cos(alpha);
+
+        self.identity_mut();
+
+        self.m_0_0 = c;
+        self.m_1_0 = -s;
+        self.m_0_1 = s;
+        self.m_1_1 = c;
+
+        self
+    }
+
+    /// Sets this matrix to a 2D scaling matrix.
+    pub fn scale_2d_mut(&mut self, x: f32, y: f32) -> &mut Matrix3 {
+        self.identity_mut();
+
+        self.m_0_0 = x;
+        self.m_1_1 = y;
+
+        self
+    }
+
+    /// Sets this matrix to a 2D shear matrix, i.e. `x' = x + x_by_y * y` and `y' = y + y_by_x * x`.
+    pub fn shear_2d_mut(&mut self, x_by_y: f32, y_by_x: f32) -> &mut Matrix3 {
+        self.identity_mut();
+
+        self.m_1_0 = x_by_y;
+        self.m_0_1 = y_by_x;
+
+        self
+    }
+
+    /// Multiplies this matrix with the provided 2D vector extended by the third dimension `w`
+    /// and writes the result back to the vector. Use `w = 1` for points and `w = 0` for
+    /// directions, which ignores the translation.
+    pub fn mult_to_vector2(&self, vec: &mut Vector2, w: f32) {
+        let nx = vec.x * self.m_0_0 + vec.y * self.m_1_0 + w * self.m_2_0;
+        let ny = vec.x * self.m_0_1 + vec.y * self.m_1_1 + w * self.m_2_1;
+
+        vec.x = nx;
+        vec.y = ny;
+    }
+
+    /// Returns the components as array reference without copying. This is possible since the
+    /// memory layout of `Matrix3` is guaranteed to be `[f32; 9]`.
+    pub fn as_array(&self) -> &[f32; 9] {
+        // SAFETY: `Matrix3` is `#[repr(C)]` and consists of 9 `f32`'s without padding, so it
+        // has the size and alignment of `[f32; 9]`; the result borrows `self`.
+        unsafe { &*(self as *const Matrix3 as *const [f32; 9]) }
+    }
+
+    /// Returns the components as mutable array reference without copying.
+    pub fn as_mut_array(&mut self) -> &mut [f32; 9] {
+        // SAFETY: same layout argument as in `as_array`, the exclusive borrow of `self` is
+        // handed over to the returned array.
+        unsafe { &mut *(self as *mut Matrix3 as *mut [f32; 9]) }
+    }
+
+    /// Returns the components as slice without copying.
+    pub fn as_slice(&self) -> &[f32] {
+        self.as_array()
+    }
+
+    /// Returns a pointer to the first component (`m_0_0`), e.g. for `glUniform*` calls.
+    /// The pointer is valid for 9 `f32`'s as long as this `Matrix3` lives.
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Matrix3 as *const f32
+    }
+
+    /// Reinterprets a slice of `Matrix3`'s as one flat slice of `f32`'s without copying, e.g. for
+    /// uploading an array of matrix uniforms. The result has `9 * values.len()` elements,
+    /// starting with `m_0_0` of the first `Matrix3` and ending with `m_2_2` of the last one.
+    pub fn slice_as_f32(values: &[Matrix3]) -> &[f32] {
+        // SAFETY: every element is 9 consecutive `f32`'s without padding (`#[repr(C)]`) and
+        // slice elements are contiguous, so the memory is exactly `9 * len` initialized `f32`'s
+        // borrowed for the lifetime of `values`.
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const f32, values.len() * 9) }
+    }
+}
+
+impl Default for Matrix3 {
+    /// Same as `Matrix3::new()`.
+    fn default() -> Matrix3 {
+        Matrix3::new()
+    }
+}
+
+impl std::ops::Mul<Vector2> for Matrix3 {
+    type Output = Vector2;
+
+    /// Overrides '*' operator to transform a `Vector2` by a 2D affine `Matrix3`, where the
+    /// third dimension of the vector is hard-coded to 1.
+    fn mul(self, rhs: Vector2) -> Vector2 {
+        let mut b = rhs;
+        self.mult_to_vector2(&mut b, 1.0);
+        b
+    }
+}
+
+impl std::ops::Mul<Vector2> for &Matrix3 {
+    type Output = Vector2;
+
+    /// Overrides '*' operator to transform a `Vector2` by a 2D affine `Matrix3`, where the
+    /// third dimension of the vector is hard-coded to 1.
+    fn mul(self, rhs: Vector2) -> Vector2 {
+        let mut b = rhs;
+        self.mult_to_vector2(&mut b, 1.0);
+        b
+    }
+}
+
+impl std::ops::Mul<&Vector2> for Matrix3 {
+    type Output = Vector2;
+
+    /// Overrides '*' operator to transform a `Vector2` by a 2D affine `Matrix3`, where the
+    /// third dimension of the vector is hard-coded to 1.
+    fn mul(self, rhs: &Vector2) -> Vector2 {
+        let mut b = *rhs;
+        self.mult_to_vector2(&mut b, 1.0);
+        b
+    }
+}
+
+impl std::ops::Mul<&Vector2> for &Matrix3 {
+    type Output = Vector2;
+
+    /// Overrides '*' operator to transform a `Vector2` by a 2D affine `Matrix3`, where the
+    /// third dimension of the vector is hard-coded to 1.
+    fn mul(self, rhs: &Vector2) -> Vector2 {
+        let mut b = *rhs;
+        self.mult_to_vector2(&mut b, 1.0);
+        b
+    }
+}
+
+impl std::ops::Mul<Matrix3> for Matrix3 {
+    type Output = Matrix3;
+
+    /// Overrides '*' operator to multiply a `Matrix3` with another `Matrix3`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Matrix3;
+    /// let mut m = Matrix3::new();
+    /// m.rotation_x_mut(0.5);
+    /// let c = m.clone() * m.inverse();
+    /// assert!((c.m_1_1 - 1.).abs() < 1e-6);
+    /// ```
+    fn mul(self, rhs: Matrix3) -> Matrix3 {
+        let mut a = self;
+        a.mult_mut(&rhs);
+        a
+    }
+}
+
+impl std::ops::Mul<&Matrix3> for Matrix3 {
+    type Output = Matrix3;
+
+    fn mul(self, rhs: &Matrix3) -> Matrix3 {
+        let mut a = self;
+        a.mult_mut(rhs);
+        a
     }
-}
\ No newline at end of file
+}
+
+impl std::ops::Mul<&Matrix3> for &Matrix3 {
+    type Output = Matrix3;
+
+    fn mul(self, rhs: &Matrix3) -> Matrix3 {
+        let mut a = Matrix3::from_matrix3(self);
+        a.mult_mut(rhs);
+        a
+    }
+}
+
+impl std::ops::Mul<Matrix3> for &Matrix3 {
+    type Output = Matrix3;
+
+    fn mul(self, rhs: Matrix3) -> Matrix3 {
+        let mut a = Matrix3::from_matrix3(self);
+        a.mult_mut(&rhs);
+        a
+    }
+}
+
+impl std::ops::MulAssign<&Matrix3> for Matrix3 {
+
+    fn mul_assign(&mut self, m: &Matrix3) {
+        self.mult_mut(m);
+    }
+}
+
+impl std::ops::MulAssign<Matrix3> for Matrix3 {
+
+    fn mul_assign(&mut self, m: Matrix3) {
+        self.mult_mut(&m);
+    }
+}
+
+impl std::ops::Mul<Vector3> for Matrix3 {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to multiply a `Matrix3` with a `Vector3`.
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        let mut b = rhs;
+        self.mult_to_vector3(&mut b);
+        b
+    }
+}
+
+impl std::ops::Mul<&Vector3> for Matrix3 {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to multiply a `Matrix3` with a `Vector3`.
+    fn mul(self, rhs: &Vector3) -> Vector3 {
+        let mut b = *rhs;
+        self.mult_to_vector3(&mut b);
+        b
+    }
+}
+
+impl std::ops::Mul<Vector3> for &Matrix3 {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to multiply a `Matrix3` with a `Vector3`.
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        let mut b = rhs;
+        self.mult_to_vector3(&mut b);
+        b
+    }
+}
+
+impl std::ops::Mul<&Vector3> for &Matrix3 {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to multiply a `Matrix3` with a `Vector3`.
+    fn mul(self, rhs: &Vector3) -> Vector3 {
+        let mut b = *rhs;
+        self.mult_to_vector3(&mut b);
+        b
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn test_mul_matrix3_completeness() {
+    let a = Matrix3::new();
+    let b = Matrix3::new();
+    let v = Vector3::new();
+    let _c = &a * &b;
+    let _c = a.clone() * b.clone();
+    let _c = a.clone() * &b;
+    let _c = &a * b;
+    let _v = &a * v;
+    let _v = &a * &v;
+    let _v = a.clone() * &v;
+    let _v = a.clone() * v;
+
+    let w = Vector2::new();
+    let _w = &a * w;
+    let _w = &a * &w;
+    let _w = a.clone() * &w;
+    let _w = a * w;
+}
+
+#[test]
+fn test_matrix3_rotations() {
+    use std::f32::consts::FRAC_PI_2;
+
+    let axes = [Vector3::from_i32(1, 0, 0), Vector3::from_i32(0, 1, 0), Vector3::from_i32(0, 0, 1)];
+    let mut simple = [Matrix3::new(), Matrix3::new(), Matrix3::new()];
+    simple[0].rotation_x_mut(0.7);
+    simple[1].rotation_y_mut(0.7);
+    simple[2].rotation_z_mut(0.7);
+
+    for (axis, s) in axes.iter().zip(simple.iter()) {
+        let mut general = Matrix3::new();
+        general.rotation_mut(0.7, axis);
+        for (a, b) in general.as_array().iter().zip(s.as_array().iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+        assert!((s.determinant() - 1.).abs() < 1e-6);
+    }
+
+    // agrees with the Matrix4 rotation
+    let axis = Vector3::from(1., 2., 3.).normalize();
+    let mut m4 = Matrix4::new();
+    m4.rotation_mut(1.1, &axis);
+    let mut m3 = Matrix3::new();
+    m3.rotation_mut(1.1, &axis);
+    for (a, b) in Matrix3::from_matrix4(&m4).as_array().iter().zip(m3.as_array().iter()) {
+        assert!((a - b).abs() < 1e-6);
+    }
+
+    let v = &simple[0] * Vector3::from_i32(0, 1, 0);
+    assert!((v.y - 0.7f32.cos()).abs() < 1e-6);
+    assert!((v.z - 0.7f32.sin()).abs() < 1e-6);
+
+    let mut r = Matrix3::new();
+    r.rotation_y_mut(FRAC_PI_2);
+    let v = r * Vector3::from_i32(0, 0, 1);
+    assert!((v.x - 1.).abs() < 1e-6);
+}
+
+#[test]
+fn test_inverse_matrix3() {
+    let mut m = Matrix3::new();
+    m.rotation_x_mut(0.3);
+    m.mult_mut(Matrix3::new().scale_mut(2., -3., 0.5));
+    assert!((m.determinant() + 3.).abs() < 1e-5);
+
+    let i = &m * m.try_inverse().unwrap();
+    for (a, b) in i.as_array().iter().zip(Matrix3::new().as_array().iter()) {
+        assert!((a - b).abs() < 1e-6);
+    }
+
+    let mut singular = Matrix3::new();
+    singular.m_0_0 = 0.;
+    assert_eq!(singular.try_inverse().unwrap_err(), Error::SingularMatrix);
+    assert!(singular.try_inverse_mut().is_err());
+    assert_eq!(singular.m_1_1, 1.);
+
+    let mut small = Matrix3::new();
+    small.rotation_x_mut(0.3);
+    small.mult_mut(Matrix3::new().scale_mut(0.001, 0.001, 0.001));
+    let i = &small * small.try_inverse().unwrap();
+    for (a, b) in i.as_array().iter().zip(Matrix3::new().as_array().iter()) {
+        assert!((a - b).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_matrix3_embed_into_matrix4() {
+    let mut m = Matrix3::new();
+    m.rotation_z_mut(0.4);
+    let mut m4 = m.to_matrix4();
+    m4.m_3_0 = 5.;
+    let back = Matrix3::from_matrix4(&m4);
+    assert_eq!(back.as_array(), m.as_array());
+    assert_eq!(m4.m_3_3, 1.);
+    assert_eq!(m4.m_0_3, 0.);
+}
+
+#[test]
+fn test_matrix3_2d_affine() {
+    use std::f32::consts::FRAC_PI_2;
+
+    // scale, then rotate, then translate
+    let mut m = Matrix3::new();
+    m.translation_2d_mut(10., 0.);
+    m.mult_mut(Matrix3::new().rotation_2d_mut(FRAC_PI_2));
+    m.mult_mut(Matrix3::new().scale_2d_mut(2., 2.));
+
+    let v = &m * Vector2::from_i32(1, 0);
+    assert!((v.x - 10.).abs() < 1e-5);
+    assert!((v.y - 2.).abs() < 1e-5);
+
+    let mut d = Vector2::from_i32(1, 0);
+    m.mult_to_vector2(&mut d, 0.0);
+    assert!(d.x.abs() < 1e-5);
+
+    let mut shear = Matrix3::new();
+    shear.shear_2d_mut(0.5, 0.);
+    let v = shear * Vector2::from_i32(1, 2);
+    assert_eq!(v.x, 2.);
+    assert_eq!(v.y, 2.);
+}
+
+#[test]
+fn test_matrix3_memory_layout() {
+    assert_eq!(std::mem::size_of::<Matrix3>(), 9 * std::mem::size_of::<f32>());
+
+    let mut m = Matrix3::new();
+    m.m_1_0 = 2.;
+    m.m_2_1 = 3.;
+    assert_eq!(m.as_array(), &[1., 0., 0., 2., 1., 0., 0., 3., 1.]);
+}
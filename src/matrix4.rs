@@ -1,4 +1,4 @@
-use super::{Matrix4, Vector3, Vector4};
+use super::{Matrix3, Matrix4, Vector3, Vector4, Quaternion, DepthRange, Transform, EulerOrder, Error};
 
 #[allow(dead_code)]
 impl Matrix4 {
@@ -77,6 +77,51 @@ impl Matrix4 {
         self.m_0_3 = 0.0;
     }
 
+    /// Sets the matrix to the rotation described by the provided (unit) quaternion.
+    pub fn rotation_quaternion_mut(&mut self, q: &Quaternion) -> &mut Matrix4 {
+        let xx = q.x * q.x;
+        let yy = q.y * q.y;
+        let zz = q.z * q.z;
+        let xy = q.x * q.y;
+        let xz = q.x * q.z;
+        let yz = q.y * q.z;
+        let wx = q.w * q.x;
+        let wy = q.w * q.y;
+        let wz = q.w * q.z;
+
+        self.identity_mut();
+
+        self.m_0_0 = 1.0 - 2.0 * (yy + zz);
+        self.m_1_0 = 2.0 * (xy - wz);
+        self.m_2_0 = 2.0 * (xz + wy);
+        self.m_0_1 = 2.0 * (xy + wz);
+        self.m_1_1 = 1.0 - 2.0 * (xx + zz);
+        self.m_2_1 = 2.0 * (yz - wx);
+        self.m_0_2 = 2.0 * (xz - wy);
+        self.m_1_2 = 2.0 * (yz + wx);
+        self.m_2_2 = 1.0 - 2.0 * (xx + yy);
+
+        self
+    }
+
+    /// Creates a new rotation `Matrix4` from Euler angles in radians without translation.
+    /// See `Matrix3::from_euler`.
+    pub fn from_euler(order: EulerOrder, angles: &Vector3) -> Matrix4 {
+        Matrix3::from_euler(order, angles).to_matrix4()
+    }
+
+    /// Sets the matrix to the rotation described by Euler angles. See `Matrix3::from_euler`.
+    pub fn rotation_euler_mut(&mut self, order: EulerOrder, angles: &Vector3) -> &mut Matrix4 {
+        self.set_matrix4(&Matrix4::from_euler(order, angles));
+        self
+    }
+
+    /// Extracts Euler angles in radians from the rotation in the upper-left 3x3 block of this
+    /// matrix, which must not contain scale or shear. See `Matrix3::to_euler`.
+    pub fn to_euler(&self, order: EulerOrder) -> Vector3 {
+        Matrix3::from_matrix4(self).to_euler(order)
+    }
+
     /// Sets the matrix to identity.
     pub fn identity_mut(&mut self) -> &mut Matrix4 {
         self.m_0_0 = 1.0;
@@ -144,6 +189,22 @@ impl Matrix4 {
     /// assert_eq!(a.m_3_2, 7.);
     /// ```
     pub fn set_row(&mut self, row: u32, x: f32, y: f32, z: f32, a: f32) {
+        if self.try_set_row(row, x, y, z, a).is_err() {
+            panic!("Matrix4x4 has rows 0 to 3, not {}", row);
+        }
+    }
+
+    /// Sets the components of the specified row like `set_row`, but returns
+    /// `Error::IndexOutOfRange` instead of panicking if `row` is larger than `3`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Error, Matrix4};
+    /// let mut a = Matrix4::new();
+    /// assert!(a.try_set_row(3, 0., 0., 1., 1.).is_ok());
+    /// assert_eq!(a.try_set_row(4, 0., 0., 0., 1.).unwrap_err(), Error::IndexOutOfRange {index: 4, len: 4});
+    /// ```
+    pub fn try_set_row(&mut self, row: u32, x: f32, y: f32, z: f32, a: f32) -> Result<&mut Matrix4, Error> {
         match row {
             0 => {
                 self.m_0_0 = x;
@@ -169,8 +230,64 @@ impl Matrix4 {
                 self.m_2_3 = z;
                 self.m_3_3 = a;
             },
-            _ => panic!("Matrix4x4 has rows 0 to 3, not {}", row),
+            _ => return Err(Error::IndexOutOfRange {index: row, len: 4}),
         };
+        Ok(self)
+    }
+
+    /// Sets the components of the specified column, i.e. `m_<column>_0` to `m_<column>_3`.
+    /// The translation of an affine matrix lives in column `3`.
+    ///
+    /// # Panics
+    /// Panics if `column` is larger than `3`, see `try_set_column`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Matrix4;
+    /// let mut a = Matrix4::new();
+    /// a.set_column(3, 4., 5., 6., 1.);
+    /// assert_eq!(a.m_3_0, 4.);
+    /// assert_eq!(a.m_3_1, 5.);
+    /// assert_eq!(a.m_3_2, 6.);
+    /// ```
+    pub fn set_column(&mut self, column: u32, x: f32, y: f32, z: f32, a: f32) -> &mut Matrix4 {
+        match self.try_set_column(column, x, y, z, a) {
+            Ok(m) => m,
+            Err(_) => panic!("Matrix4x4 has columns 0 to 3, not {}", column),
+        }
+    }
+
+    /// Sets the components of the specified column like `set_column`, but returns
+    /// `Error::IndexOutOfRange` instead of panicking if `column` is larger than `3`.
+    pub fn try_set_column(&mut self, column: u32, x: f32, y: f32, z: f32, a: f32) -> Result<&mut Matrix4, Error> {
+        match column {
+            0 => {
+                self.m_0_0 = x;
+                self.m_0_1 = y;
+                self.m_0_2 = z;
+                self.m_0_3 = a;
+            },
+            1 => {
+                self.m_1_0 = x;
+                self.m_1_1 = y;
+                self.m_1_2 = z;
+                self.m_1_3 = a;
+            },
+            2 => {
+                self.m_2_0 = x;
+                self.m_2_1 = y;
+                self.m_2_2 = z;
+                self.m_2_3 = a;
+            },
+            3 => {
+                self.m_3_0 = x;
+                self.m_3_1 = y;
+                self.m_3_2 = z;
+                self.m_3_3 = a;
+            },
+            _ => return Err(Error::IndexOutOfRange {index: column, len: 4}),
+        };
+        Ok(self)
     }
 
     /// Set the matrix to a projection matrix.
@@ -185,6 +302,153 @@ impl Matrix4 {
         self.frustum_mut(-half_scaled_aspect_ratio, half_scaled_aspect_ratio, -half_height, half_height, near_clipping_plane, far_clipping_plane);
     }
 
+    /// Sets the matrix to a frustum projection with the far clipping plane at infinity.
+    pub fn frustum_infinite_mut(&mut self, left: f32, right: f32, bottom: f32, top: f32, near: f32) -> &mut Matrix4 {
+        self.identity_mut();
+
+        self.m_0_0 = (2.0 * near) / (right - left);
+        self.m_2_0 = (right + left) / (right - left);
+
+        self.m_1_1 = (2.0 * near) / (top - bottom);
+        self.m_2_1 = (top + bottom) / (top - bottom);
+
+        // limit of frustum_mut for far -> infinity
+        self.m_2_2 = -1.0;
+        self.m_3_2 = -2.0 * near;
+
+        self.m_2_3 = -1.0;
+        self.m_3_3 = 0.0;
+
+        self
+    }
+
+    /// Set the matrix to a projection matrix with the far clipping plane at infinity. The
+    /// arguments are the same as for `projection_mut`, `view_angle` is in degrees.
+    pub fn projection_infinite_mut(&mut self, view_angle: f32, width: f32, height: f32, near_clipping_plane: f32) -> &mut Matrix4 {
+        use std::f32::consts::PI;
+        let radians: f32 = view_angle * PI / 180.0;
+        let half_height = f32::tan(radians / 2.0) * near_clipping_plane;
+        let half_scaled_aspect_ratio = half_height * (width / height);
+        self.frustum_infinite_mut(-half_scaled_aspect_ratio, half_scaled_aspect_ratio, -half_height, half_height, near_clipping_plane)
+    }
+
+    /// Set the matrix to a reversed-Z projection with the far clipping plane at infinity and
+    /// depth in `0..1`: the near clipping plane maps to depth 1, infinity to depth 0. Combined
+    /// with a floating point depth buffer and a `GREATER` depth test this gives an almost
+    /// uniform depth precision across huge view distances.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix4, Vector4};
+    /// let mut p = Matrix4::new();
+    /// p.projection_reversed_z_mut(45., 800., 600., 0.1);
+    /// let near = &p * Vector4 {x: 0., y: 0., z: -0.1, w: 1.};
+    /// assert!((near.z / near.w - 1.).abs() < 1e-6);
+    /// ```
+    pub fn projection_reversed_z_mut(&mut self, view_angle: f32, width: f32, height: f32, near_clipping_plane: f32) -> &mut Matrix4 {
+        self.projection_infinite_mut(view_angle, width, height, near_clipping_plane)
+            .depth_range_mut(DepthRange::ZeroToOne, true)
+    }
+
+    /// Sets the matrix to an orthographic projection (OpenGL depth range `-1..1`), e.g. for
+    /// UI rendering or shadow maps of directional lights.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix4, Vector3};
+    /// let mut p = Matrix4::new();
+    /// p.orthographic_mut(0., 800., 0., 600., -1., 1.);
+    /// let v = &p * Vector3::from_i32(800, 600, 0);
+    /// assert_eq!(v.x, 1.);
+    /// assert_eq!(v.y, 1.);
+    /// ```
+    pub fn orthographic_mut(&mut self, left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> &mut Matrix4 {
+        self.identity_mut();
+
+        self.m_0_0 = 2.0 / (right - left);
+        self.m_3_0 = -(right + left) / (right - left);
+
+        self.m_1_1 = 2.0 / (top - bottom);
+        self.m_3_1 = -(top + bottom) / (top - bottom);
+
+        self.m_2_2 = -2.0 / (far - near);
+        self.m_3_2 = -(far + near) / (far - near);
+
+        self
+    }
+
+    /// Converts a projection matrix that produces OpenGL depth (`-1..1`), i.e. any matrix built
+    /// with `frustum_mut`, `projection_mut`, `orthographic_mut` or their infinite variants, to
+    /// the provided depth range. With `reversed` set, the near clipping plane maps to the
+    /// upper end of the range and the far clipping plane to the lower end.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{DepthRange, Matrix4, Vector4};
+    /// let mut p = Matrix4::new();
+    /// p.projection_mut(45., 800., 600., 0.1, 100.);
+    /// p.depth_range_mut(DepthRange::ZeroToOne, false);
+    /// let near = &p * Vector4 {x: 0., y: 0., z: -0.1, w: 1.};
+    /// assert!((near.z / near.w).abs() < 1e-6);
+    /// ```
+    pub fn depth_range_mut(&mut self, range: DepthRange, reversed: bool) -> &mut Matrix4 {
+        // the depth row gets replaced by a combination of the depth row and the w row:
+        // 0..1 is z' = 0.5 * z + 0.5 * w, reversing 0..1 is z'' = w - z', reversing -1..1 is z' = -z
+        let (z, w) = match (range, reversed) {
+            (DepthRange::NegativeOneToOne, false) => return self,
+            (DepthRange::NegativeOneToOne, true)  => (-1.0, 0.0),
+            (DepthRange::ZeroToOne, false)        => (0.5, 0.5),
+            (DepthRange::ZeroToOne, true)         => (-0.5, 0.5),
+        };
+
+        self.m_0_2 = z * self.m_0_2 + w * self.m_0_3;
+        self.m_1_2 = z * self.m_1_2 + w * self.m_1_3;
+        self.m_2_2 = z * self.m_2_2 + w * self.m_2_3;
+        self.m_3_2 = z * self.m_3_2 + w * self.m_3_3;
+
+        self
+    }
+
+    /// In-place inversion of a projection matrix in closed form. Works for every matrix built
+    /// with `frustum_mut`, `projection_mut`, `orthographic_mut`, their infinite and reversed-Z
+    /// variants and any `depth_range_mut` conversion of them, but not for general matrices
+    /// (use `inverse_mut` for those).
+    pub fn inverse_projection_mut(&mut self) -> &mut Matrix4 {
+        // block upper triangular [A C; 0 D] with diagonal A, inverse is [A^-1  -A^-1 C D^-1; 0 D^-1]
+        let a = self.m_0_0;
+        let b = self.m_1_1;
+        let c = self.m_2_0;
+        let d = self.m_2_1;
+        let tx = self.m_3_0;
+        let ty = self.m_3_1;
+
+        let e = self.m_2_2;
+        let f = self.m_3_2;
+        let g = self.m_2_3;
+        let h = self.m_3_3;
+        let det = e * h - f * g;
+
+        let p = h / det;
+        let q = -f / det;
+        let r = -g / det;
+        let s = e / det;
+
+        self.m_0_0 = 1.0 / a;
+        self.m_2_0 = -(c * p + tx * r) / a;
+        self.m_3_0 = -(c * q + tx * s) / a;
+
+        self.m_1_1 = 1.0 / b;
+        self.m_2_1 = -(d * p + ty * r) / b;
+        self.m_3_1 = -(d * q + ty * s) / b;
+
+        self.m_2_2 = p;
+        self.m_3_2 = q;
+        self.m_2_3 = r;
+        self.m_3_3 = s;
+
+        self
+    }
+
     /// Sets this matrix to a scaling matrix.
     pub fn scale_mut(&mut self, x: f32, y: f32, z: f32) {
         self.m_0_0 = x;
@@ -289,6 +553,34 @@ impl Matrix4 {
         matrix
     }
 
+    /// Computes a view matrix like `look_at`, but returns `Error::ZeroLength` if `eye` and
+    /// `center` coincide or `up` is zero and `Error::ParallelUpVector` if `up` is parallel to the viewing direction,
+    /// both cases where `look_at` produces NaN components.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Error, Matrix4, Vector3};
+    /// let eye = Vector3::from_i32(0, 0, 5);
+    /// let up  = Vector3::new_z_up();
+    /// assert_eq!(Matrix4::try_look_at(&eye, &Vector3::new(), &up).unwrap_err(), Error::ParallelUpVector);
+    /// assert!(Matrix4::try_look_at(&eye, &Vector3::new(), &Vector3::from_i32(0, 1, 0)).is_ok());
+    /// ```
+    pub fn try_look_at(eye: &Vector3, center: &Vector3, up: &Vector3) -> Result<Matrix4, Error> {
+        let mut matrix = Matrix4::new();
+        matrix.try_look_at_mut(eye, center, up)?;
+        Ok(matrix)
+    }
+
+    /// In-place variant of `try_look_at`. This matrix is left untouched on error.
+    pub fn try_look_at_mut(&mut self, eye: &Vector3, center: &Vector3, up: &Vector3) -> Result<&mut Matrix4, Error> {
+        let w = (*eye - *center).try_normalize()?;
+        // squared sine of the angle between up and viewing direction, i.e. less than ~0.02 degrees
+        if up.try_normalize()?.cross(&w).length_squared() < f32::EPSILON {
+            return Err(Error::ParallelUpVector);
+        }
+        Ok(self.look_at_mut(eye, center, up))
+    }
+
     /// Computes a classical view matrix by providing a vantage point (`eye`), point where to look at (`center`)
     /// and an up vector (`up`).
     /// 
@@ -311,7 +603,7 @@ impl Matrix4 {
         w.set_vector3(eye).sub_mut_vector3(center).normalize_mut();
 
         // compute cross product
-        u.set_vector3(&up).cross_mut(&w).normalize_mut();
+        u.set_vector3(up).cross_mut(&w).normalize_mut();
         // side = (0,0,1) x w
 
         // up = side x look
@@ -399,6 +691,395 @@ impl Matrix4 {
         vec.y = ny;
         vec.z = nz;
     }
+
+    /// Computes the determinant of this matrix.
+    pub fn determinant(&self) -> f32 {
+        let b00 = self.m_0_0 * self.m_1_1 - self.m_0_1 * self.m_1_0;
+        let b01 = self.m_0_0 * self.m_1_2 - self.m_0_2 * self.m_1_0;
+        let b02 = self.m_0_0 * self.m_1_3 - self.m_0_3 * self.m_1_0;
+        let b03 = self.m_0_1 * self.m_1_2 - self.m_0_2 * self.m_1_1;
+        let b04 = self.m_0_1 * self.m_1_3 - self.m_0_3 * self.m_1_1;
+        let b05 = self.m_0_2 * self.m_1_3 - self.m_0_3 * self.m_1_2;
+        let b06 = self.m_2_0 * self.m_3_1 - self.m_2_1 * self.m_3_0;
+        let b07 = self.m_2_0 * self.m_3_2 - self.m_2_2 * self.m_3_0;
+        let b08 = self.m_2_0 * self.m_3_3 - self.m_2_3 * self.m_3_0;
+        let b09 = self.m_2_1 * self.m_3_2 - self.m_2_2 * self.m_3_1;
+        let b10 = self.m_2_1 * self.m_3_3 - self.m_2_3 * self.m_3_1;
+        let b11 = self.m_2_2 * self.m_3_3 - self.m_2_3 * self.m_3_2;
+
+        b00 * b11 - b01 * b10 + b02 * b09 + b03 * b08 - b04 * b07 + b05 * b06
+    }
+
+    /// In-place inversion of this matrix via cofactor expansion. Just like `Matrix3::inverse_mut`,
+    /// this does not check the determinant; a singular matrix ends up with NaN/inf components.
+    /// Use `try_inverse` if the input may be singular.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Matrix4;
+    /// let mut a = Matrix4::new();
+    /// a.translation_mut(1., 2., 3.).inverse_mut();
+    /// assert_eq!(a.m_3_0, -1.);
+    /// assert_eq!(a.m_3_1, -2.);
+    /// assert_eq!(a.m_3_2, -3.);
+    /// ```
+    pub fn inverse_mut(&mut self) -> &mut Matrix4 {
+        let a00 = self.m_0_0;
+        let a01 = self.m_0_1;
+        let a02 = self.m_0_2;
+        let a03 = self.m_0_3;
+        let a10 = self.m_1_0;
+        let a11 = self.m_1_1;
+        let a12 = self.m_1_2;
+        let a13 = self.m_1_3;
+        let a20 = self.m_2_0;
+        let a21 = self.m_2_1;
+        let a22 = self.m_2_2;
+        let a23 = self.m_2_3;
+        let a30 = self.m_3_0;
+        let a31 = self.m_3_1;
+        let a32 = self.m_3_2;
+        let a33 = self.m_3_3;
+
+        // 2x2 sub-determinants of the first two and the last two columns
+        let b00 = a00 * a11 - a01 * a10;
+        let b01 = a00 * a12 - a02 * a10;
+        let b02 = a00 * a13 - a03 * a10;
+        let b03 = a01 * a12 - a02 * a11;
+        let b04 = a01 * a13 - a03 * a11;
+        let b05 = a02 * a13 - a03 * a12;
+        let b06 = a20 * a31 - a21 * a30;
+        let b07 = a20 * a32 - a22 * a30;
+        let b08 = a20 * a33 - a23 * a30;
+        let b09 = a21 * a32 - a22 * a31;
+        let b10 = a21 * a33 - a23 * a31;
+        let b11 = a22 * a33 - a23 * a32;
+
+        let det = b00 * b11 - b01 * b10 + b02 * b09 + b03 * b08 - b04 * b07 + b05 * b06;
+        let inv_det = 1.0 / det;
+
+        self.m_0_0 = (a11 * b11 - a12 * b10 + a13 * b09) * inv_det;
+        self.m_0_1 = (a02 * b10 - a01 * b11 - a03 * b09) * inv_det;
+        self.m_0_2 = (a31 * b05 - a32 * b04 + a33 * b03) * inv_det;
+        self.m_0_3 = (a22 * b04 - a21 * b05 - a23 * b03) * inv_det;
+        self.m_1_0 = (a12 * b08 - a10 * b11 - a13 * b07) * inv_det;
+        self.m_1_1 = (a00 * b11 - a02 * b08 + a03 * b07) * inv_det;
+        self.m_1_2 = (a32 * b02 - a30 * b05 - a33 * b01) * inv_det;
+        self.m_1_3 = (a20 * b05 - a22 * b02 + a23 * b01) * inv_det;
+        self.m_2_0 = (a10 * b10 - a11 * b08 + a13 * b06) * inv_det;
+        self.m_2_1 = (a01 * b08 - a00 * b10 - a03 * b06) * inv_det;
+        self.m_2_2 = (a30 * b04 - a31 * b02 + a33 * b00) * inv_det;
+        self.m_2_3 = (a21 * b02 - a20 * b04 - a23 * b00) * inv_det;
+        self.m_3_0 = (a11 * b07 - a10 * b09 - a12 * b06) * inv_det;
+        self.m_3_1 = (a00 * b09 - a01 * b07 + a02 * b06) * inv_det;
+        self.m_3_2 = (a31 * b01 - a30 * b03 - a32 * b00) * inv_det;
+        self.m_3_3 = (a20 * b03 - a21 * b01 + a22 * b00) * inv_det;
+
+        self
+    }
+
+    /// Computes the inverse of this matrix and returns it as a new `Matrix4`. See `inverse_mut`.
+    pub fn inverse(&self) -> Matrix4 {
+        let mut m = Matrix4::from_matrix4(self);
+        m.inverse_mut();
+        m
+    }
+
+    /// Computes the inverse of this matrix and returns it as a new `Matrix4`, or
+    /// `Error::SingularMatrix` if the matrix is singular, see `try_inverse_mut`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Error, Matrix4};
+    /// let mut a = Matrix4::new();
+    /// a.scale_mut(1., 0., 1.);
+    /// assert_eq!(a.try_inverse().unwrap_err(), Error::SingularMatrix);
+    /// ```
+    pub fn try_inverse(&self) -> Result<Matrix4, Error> {
+        let mut m = Matrix4::from_matrix4(self);
+        m.try_inverse_mut()?;
+        Ok(m)
+    }
+
+    /// In-place inversion like `inverse_mut`, but leaves this matrix untouched and returns
+    /// `Error::SingularMatrix` if the matrix is singular or not finite. The determinant is
+    /// compared against the product of the column lengths, so a uniformly scaled matrix is
+    /// accepted no matter how small the scale.
+    pub fn try_inverse_mut(&mut self) -> Result<&mut Matrix4, Error> {
+        let det = self.determinant();
+        let bound = Vector4::from(self.m_0_0, self.m_0_1, self.m_0_2, self.m_0_3).length()
+            * Vector4::from(self.m_1_0, self.m_1_1, self.m_1_2, self.m_1_3).length()
+            * Vector4::from(self.m_2_0, self.m_2_1, self.m_2_2, self.m_2_3).length()
+            * Vector4::from(self.m_3_0, self.m_3_1, self.m_3_2, self.m_3_3).length();
+        if !(det.abs() > f32::EPSILON * bound && det.is_finite()) {
+            return Err(Error::SingularMatrix);
+        }
+        Ok(self.inverse_mut())
+    }
+
+    /// In-place inversion of an affine matrix, i.e. a matrix whose bottom row is `(0, 0, 0, 1)`.
+    /// Only the upper-left 3x3 block is inverted, the translation is then mapped back through it.
+    /// This is considerably cheaper than `inverse_mut` but gives wrong results for projections.
+    pub fn inverse_affine_mut(&mut self) -> &mut Matrix4 {
+        let a = self.m_0_0;
+        let b = self.m_1_0;
+        let c = self.m_2_0;
+        let d = self.m_0_1;
+        let e = self.m_1_1;
+        let f = self.m_2_1;
+        let g = self.m_0_2;
+        let h = self.m_1_2;
+        let i = self.m_2_2;
+
+        let det = a * (e * i - f * h) - b * (i * d - f * g) + c * (d * h - e * g);
+
+        self.m_0_0 = (e * i - f * h) / det;
+        self.m_1_0 = -(b * i - c * h) / det;
+        self.m_2_0 = (b * f - c * e) / det;
+        self.m_0_1 = -(d * i - f * g) / det;
+        self.m_1_1 = (a * i - c * g) / det;
+        self.m_2_1 = -(a * f - c * d) / det;
+        self.m_0_2 = (d * h - e * g) / det;
+        self.m_1_2 = -(a * h - b * g) / det;
+        self.m_2_2 = (a * e - b * d) / det;
+
+        self.inverse_translation_mut();
+
+        self
+    }
+
+    /// In-place inversion of a rigid matrix, i.e. a matrix composed only of a rotation and a
+    /// translation. The rotation part is simply transposed, which makes this the cheapest way
+    /// to invert a view or model matrix without scaling.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix4, Vector3};
+    /// let mut view = Matrix4::look_at(
+    ///     &Vector3::from_i32(4, 3, 5),
+    ///     &Vector3::from_i32(0, 0, 0),
+    ///     &Vector3::new_z_up());
+    /// view.inverse_rigid_mut();
+    /// // the camera sits at the origin of camera space
+    /// let eye = &view * Vector3::new();
+    /// assert!((eye.x - 4.).abs() < 1e-5);
+    /// ```
+    pub fn inverse_rigid_mut(&mut self) -> &mut Matrix4 {
+        let mut tmp;
+
+        tmp        = self.m_0_1;
+        self.m_0_1 = self.m_1_0;
+        self.m_1_0 = tmp;
+
+        tmp        = self.m_0_2;
+        self.m_0_2 = self.m_2_0;
+        self.m_2_0 = tmp;
+
+        tmp        = self.m_1_2;
+        self.m_1_2 = self.m_2_1;
+        self.m_2_1 = tmp;
+
+        self.inverse_translation_mut();
+
+        self
+    }
+
+    /// Replaces the translation `t` with `-A * t`, where `A` is the (already inverted)
+    /// upper-left 3x3 block. Shared tail of the affine and rigid inversion.
+    fn inverse_translation_mut(&mut self) {
+        let tx = self.m_3_0;
+        let ty = self.m_3_1;
+        let tz = self.m_3_2;
+
+        self.m_3_0 = -(self.m_0_0 * tx + self.m_1_0 * ty + self.m_2_0 * tz);
+        self.m_3_1 = -(self.m_0_1 * tx + self.m_1_1 * ty + self.m_2_1 * tz);
+        self.m_3_2 = -(self.m_0_2 * tx + self.m_1_2 * ty + self.m_2_2 * tz);
+
+        self.m_0_3 = 0.0;
+        self.m_1_3 = 0.0;
+        self.m_2_3 = 0.0;
+        self.m_3_3 = 1.0;
+    }
+
+    /// Creates a new `Matrix4` composed of the parts of the provided `Transform`.
+    /// See `compose_mut`.
+    pub fn from_transform(t:&Transform) -> Matrix4 {
+        t.to_matrix4()
+    }
+
+    /// Sets this matrix to `T * R * H * S`, composed of the translation, rotation, shear and
+    /// scale of the provided `Transform`. This is the inverse of `decompose`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix4, Quaternion, Transform, Vector3};
+    /// let t = Transform::from(
+    ///     &Vector3::from_i32(1, 2, 3),
+    ///     &Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.5),
+    ///     &Vector3::from_i32(2, 2, 2));
+    /// let mut m = Matrix4::new();
+    /// m.compose_mut(&t);
+    /// assert_eq!(m.m_3_2, 3.);
+    /// ```
+    pub fn compose_mut(&mut self, t:&Transform) -> &mut Matrix4 {
+        self.rotation_quaternion_mut(&t.rotation);
+
+        let (sx, sy, sz) = (t.scale.x, t.scale.y, t.scale.z);
+        let (xy, xz, yz) = (t.shear.x, t.shear.y, t.shear.z);
+
+        // columns of R * H * S, where H is upper unitriangular
+        let c0 = Vector3::from(self.m_0_0, self.m_0_1, self.m_0_2);
+        let c1 = Vector3::from(self.m_1_0, self.m_1_1, self.m_1_2);
+        let c2 = Vector3::from(self.m_2_0, self.m_2_1, self.m_2_2);
+
+        self.m_0_0 = sx * c0.x;
+        self.m_0_1 = sx * c0.y;
+        self.m_0_2 = sx * c0.z;
+
+        self.m_1_0 = sy * (xy * c0.x + c1.x);
+        self.m_1_1 = sy * (xy * c0.y + c1.y);
+        self.m_1_2 = sy * (xy * c0.z + c1.z);
+
+        self.m_2_0 = sz * (xz * c0.x + yz * c1.x + c2.x);
+        self.m_2_1 = sz * (xz * c0.y + yz * c1.y + c2.y);
+        self.m_2_2 = sz * (xz * c0.z + yz * c1.z + c2.z);
+
+        self.m_3_0 = t.translation.x;
+        self.m_3_1 = t.translation.y;
+        self.m_3_2 = t.translation.z;
+
+        self
+    }
+
+    /// Splits this affine matrix into translation, rotation, scale and shear, such that
+    /// `compose_mut` restores it. The projective bottom row is ignored. Returns `None` if the
+    /// matrix has non-finite components or if the upper-left 3x3 block is singular, i.e. if a
+    /// scale would be (close to) zero relative to the others.
+    ///
+    /// The columns are orthogonalized in the order `x`, `y`, `z` (Gram-Schmidt), the removed
+    /// parts become the shear. Mirroring cannot be told apart from a rotation by 180 degrees plus
+    /// mirroring on another axis, so a negative determinant is always reported as negative
+    /// `scale.x`; `scale.y` and `scale.z` are never negative.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix4, Vector3};
+    /// let mut m = Matrix4::new();
+    /// m.translation_mut(1., 2., 3.);
+    /// m.mult_mut(Matrix4::new().rotation_quaternion_mut(&js_linalg::Quaternion::from_euler(0.1, 0.2, 0.3)));
+    /// let mut s = Matrix4::new();
+    /// s.scale_mut(-2., 1., 4.);
+    /// m.mult_mut(&s);
+    ///
+    /// let t = m.decompose().unwrap();
+    /// assert!((t.scale.x + 2.).abs() < 1e-5);
+    /// assert!((t.scale.z - 4.).abs() < 1e-5);
+    /// assert_eq!(t.translation.y, 2.);
+    /// ```
+    pub fn decompose(&self) -> Option<Transform> {
+        let mut c0 = Vector3::from(self.m_0_0, self.m_0_1, self.m_0_2);
+        let mut c1 = Vector3::from(self.m_1_0, self.m_1_1, self.m_1_2);
+        let mut c2 = Vector3::from(self.m_2_0, self.m_2_1, self.m_2_2);
+
+        if !self.as_array().iter().all(|v| v.is_finite()) {
+            return None;
+        }
+
+        // degenerate axes are detected relative to the largest one, so tiny uniform scales pass
+        let tolerance = f32::EPSILON * c0.length().max(c1.length()).max(c2.length());
+
+        let mut sx = c0.length();
+        if sx <= tolerance {
+            return None;
+        }
+        c0 *= 1.0 / sx;
+
+        let mut xy = c0.dot(&c1);
+        c1 -= c0 * xy;
+        let sy = c1.length();
+        if sy <= tolerance {
+            return None;
+        }
+        c1 *= 1.0 / sy;
+        xy /= sy;
+
+        let mut xz = c0.dot(&c2);
+        c2 -= c0 * xz;
+        let mut yz = c1.dot(&c2);
+        c2 -= c1 * yz;
+        let sz = c2.length();
+        if sz <= tolerance {
+            return None;
+        }
+        c2 *= 1.0 / sz;
+        xz /= sz;
+        yz /= sz;
+
+        // a left-handed basis is mirrored, move the mirroring into the x scale
+        if c0.dot(&c1.cross(&c2)) < 0.0 {
+            sx = -sx;
+            c0 *= -1.0;
+            xy = -xy;
+            xz = -xz;
+        }
+
+        let mut rotation = Quaternion::from_matrix3(&Matrix3 {
+            m_0_0: c0.x, m_0_1: c0.y, m_0_2: c0.z,
+            m_1_0: c1.x, m_1_1: c1.y, m_1_2: c1.z,
+            m_2_0: c2.x, m_2_1: c2.y, m_2_2: c2.z,
+        });
+        rotation.normalize_mut();
+
+        Some(Transform {
+            translation: Vector3::from(self.m_3_0, self.m_3_1, self.m_3_2),
+            rotation,
+            scale: Vector3::from(sx, sy, sz),
+            shear: Vector3::from(xy, xz, yz),
+        })
+    }
+
+    /// Returns the components as array reference without copying. This is possible since the
+    /// memory layout of `Matrix4` is guaranteed to be `[f32; 16]`.
+    pub fn as_array(&self) -> &[f32; 16] {
+        // SAFETY: `Matrix4` is `#[repr(C)]` and consists of 16 `f32`'s without padding, so it
+        // has the size and alignment of `[f32; 16]`; the result borrows `self`.
+        unsafe { &*(self as *const Matrix4 as *const [f32; 16]) }
+    }
+
+    /// Returns the components as mutable array reference without copying.
+    pub fn as_mut_array(&mut self) -> &mut [f32; 16] {
+        // SAFETY: same layout argument as in `as_array`, the exclusive borrow of `self` is
+        // handed over to the returned array.
+        unsafe { &mut *(self as *mut Matrix4 as *mut [f32; 16]) }
+    }
+
+    /// Returns the components as slice without copying.
+    pub fn as_slice(&self) -> &[f32] {
+        self.as_array()
+    }
+
+    /// Returns a pointer to the first component (`m_0_0`), e.g. for `glUniform*` calls.
+    /// The pointer is valid for 16 `f32`'s as long as this `Matrix4` lives.
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Matrix4 as *const f32
+    }
+
+    /// Reinterprets a slice of `Matrix4`'s as one flat slice of `f32`'s without copying, e.g. for
+    /// uploading an array of matrix uniforms. The result has `16 * values.len()` elements,
+    /// starting with `m_0_0` of the first `Matrix4` and ending with `m_3_3` of the last one.
+    pub fn slice_as_f32(values: &[Matrix4]) -> &[f32] {
+        // SAFETY: every element is 16 consecutive `f32`'s without padding (`#[repr(C)]`) and
+        // slice elements are contiguous, so the memory is exactly `16 * len` initialized `f32`'s
+        // borrowed for the lifetime of `values`.
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const f32, values.len() * 16) }
+    }
+}
+
+impl Default for Matrix4 {
+    /// Same as `Matrix4::new()`.
+    fn default() -> Matrix4 {
+        Matrix4::new()
+    }
 }
 
 impl std::ops::Add<Matrix4> for Matrix4 {
@@ -559,6 +1240,161 @@ fn test_new_matrix4() {
     assert!(m.m_2_2 == 1.0);
 }
 
+#[test]
+fn test_matrix4_memory_layout() {
+    assert_eq!(std::mem::size_of::<Matrix4>(), 16 * std::mem::size_of::<f32>());
+
+    let mut m = Matrix4::new();
+    m.translation_mut(5., 6., 7.);
+    assert_eq!(m.as_array()[12..15], [5., 6., 7.]);
+    // SAFETY: the pointer is valid for the 16 components of `m`
+    assert_eq!(unsafe { *m.as_ptr().add(12) }, 5.);
+    assert_eq!(Matrix4::slice_as_f32(&[m.clone(), m]).len(), 32);
+}
+
+#[cfg(test)]
+fn assert_matrix4_near(a: &Matrix4, b: &Matrix4) {
+    let a = [a.m_0_0, a.m_0_1, a.m_0_2, a.m_0_3, a.m_1_0, a.m_1_1, a.m_1_2, a.m_1_3,
+             a.m_2_0, a.m_2_1, a.m_2_2, a.m_2_3, a.m_3_0, a.m_3_1, a.m_3_2, a.m_3_3];
+    let b = [b.m_0_0, b.m_0_1, b.m_0_2, b.m_0_3, b.m_1_0, b.m_1_1, b.m_1_2, b.m_1_3,
+             b.m_2_0, b.m_2_1, b.m_2_2, b.m_2_3, b.m_3_0, b.m_3_1, b.m_3_2, b.m_3_3];
+    for i in 0..16 {
+        assert!((a[i] - b[i]).abs() < 1e-4, "component {}: {} != {}", i, a[i], b[i]);
+    }
+}
+
+#[test]
+fn test_inverse_matrix4() {
+    let mut m = Matrix4::new();
+    m.projection_mut(45.0, 800.0, 600.0, 0.1, 100.0);
+    m.mult_mut(&Matrix4::look_at(&Vector3::from_i32(4, 3, 5), &Vector3::new(), &Vector3::new_z_up()));
+
+    let inv = m.try_inverse().unwrap();
+    let mut in_place = m.clone();
+    in_place.try_inverse_mut().unwrap();
+    assert_matrix4_near(&in_place, &inv);
+    assert_matrix4_near(&(&m * &inv), &Matrix4::new());
+    assert_matrix4_near(&(&inv * &m), &Matrix4::new());
+}
+
+#[test]
+fn test_inverse_matrix4_singular() {
+    let mut m = Matrix4::new();
+    m.set_row(1, 1., 2., 3., 4.);
+    m.set_row(2, 2., 4., 6., 8.);
+    assert_eq!(m.try_inverse().unwrap_err(), Error::SingularMatrix);
+
+    // the singular matrix is left untouched
+    let before = m.clone();
+    assert!(m.try_inverse_mut().is_err());
+    assert_matrix4_near(&m, &before);
+}
+
+#[test]
+fn test_inverse_matrix4_small_scale() {
+    // regular matrices with a tiny determinant must still be invertible
+    let mut m = Matrix4::new();
+    m.rotation_mut(0.4, &Vector3::from(0., 1., 0.));
+    let mut s = Matrix4::new();
+    s.scale_mut(0.001, 0.001, 0.001);
+    m.mult_mut(&s);
+    m.m_3_0 = 0.002;
+    assert!(m.determinant().abs() < f32::EPSILON);
+    assert_matrix4_near(&(&m * m.try_inverse().unwrap()), &Matrix4::new());
+
+    let mut tiny = Matrix4::new();
+    tiny.scale_mut(0.001, 0.001, 0.001);
+    tiny.m_3_3 = 0.001;
+    assert_matrix4_near(&(&tiny * tiny.try_inverse().unwrap()), &Matrix4::new());
+
+    let t = m.decompose().unwrap();
+    assert!((t.scale.x - 0.001).abs() < 1e-8);
+    assert!((t.scale.z - 0.001).abs() < 1e-8);
+
+    let mut broken = Matrix4::new();
+    broken.m_1_2 = f32::NAN;
+    assert_eq!(broken.try_inverse().unwrap_err(), Error::SingularMatrix);
+    assert!(broken.decompose().is_none());
+    broken.m_1_2 = 0.;
+    broken.m_3_1 = f32::INFINITY;
+    assert!(broken.decompose().is_none());
+}
+
+#[test]
+fn test_inverse_projection_matrix4() {
+    let mut projections = Vec::new();
+
+    let mut m = Matrix4::new();
+    m.frustum_mut(-0.3, 0.5, -0.2, 0.4, 0.1, 100.);
+    projections.push(m.clone());
+    m.depth_range_mut(DepthRange::ZeroToOne, true);
+    projections.push(m.clone());
+    m.projection_infinite_mut(60., 800., 600., 0.1);
+    projections.push(m.clone());
+    m.projection_reversed_z_mut(60., 800., 600., 0.1);
+    projections.push(m.clone());
+    m.orthographic_mut(-3., 5., -2., 4., -1., 10.);
+    projections.push(m.clone());
+    m.depth_range_mut(DepthRange::ZeroToOne, false);
+    projections.push(m.clone());
+
+    for p in projections {
+        let mut inv = p.clone();
+        inv.inverse_projection_mut();
+        assert_matrix4_near(&(&p * &inv), &Matrix4::new());
+    }
+}
+
+#[test]
+fn test_depth_range_matrix4() {
+    let depth = |m: &Matrix4, z: f32| {
+        let v = m * Vector4 {x: 0., y: 0., z, w: 1.};
+        v.z / v.w
+    };
+
+    let mut m = Matrix4::new();
+    m.projection_mut(45., 800., 600., 0.5, 20.);
+    assert!((depth(&m, -0.5) + 1.).abs() < 1e-5);
+    assert!((depth(&m, -20.) - 1.).abs() < 1e-5);
+
+    m.depth_range_mut(DepthRange::ZeroToOne, false);
+    assert!(depth(&m, -0.5).abs() < 1e-5);
+    assert!((depth(&m, -20.) - 1.).abs() < 1e-5);
+
+    m.projection_mut(45., 800., 600., 0.5, 20.);
+    m.depth_range_mut(DepthRange::ZeroToOne, true);
+    assert!((depth(&m, -0.5) - 1.).abs() < 1e-5);
+    assert!(depth(&m, -20.).abs() < 1e-5);
+
+    m.orthographic_mut(-1., 1., -1., 1., 0.5, 20.);
+    m.depth_range_mut(DepthRange::NegativeOneToOne, true);
+    assert!((depth(&m, -0.5) - 1.).abs() < 1e-5);
+    assert!((depth(&m, -20.) + 1.).abs() < 1e-5);
+
+    m.projection_reversed_z_mut(45., 800., 600., 0.5);
+    assert!(depth(&m, -1e7) < 1e-6);
+}
+
+#[test]
+fn test_inverse_affine_and_rigid_matrix4() {
+    let mut rot = Matrix4::new();
+    rot.rotation_mut(0.7, Vector3::from(1., 2., 3.).normalize_mut());
+    let mut trans = Matrix4::new();
+    trans.translation_mut(3., -2., 5.);
+    let mut scale = Matrix4::new();
+    scale.scale_mut(2., 3., 0.5);
+
+    let rigid = &trans * &rot;
+    let mut fast = rigid.clone();
+    fast.inverse_rigid_mut();
+    assert_matrix4_near(&fast, &rigid.inverse());
+
+    let affine = &rigid * &scale;
+    let mut fast = affine.clone();
+    fast.inverse_affine_mut();
+    assert_matrix4_near(&fast, &affine.inverse());
+}
+
 impl std::ops::Sub<Matrix4> for Matrix4 {
     type Output = Matrix4;
 
@@ -885,7 +1721,7 @@ impl std::ops::Mul<&Matrix4> for &Matrix4 {
     /// let c = &m * &u;
     /// ```
     fn mul(self, rhs: &Matrix4) -> Matrix4 {
-        let mut a = Matrix4::from_matrix4(&self);
+        let mut a = Matrix4::from_matrix4(self);
         a.mult_mut(rhs);
         a
     }
@@ -905,7 +1741,7 @@ impl std::ops::Mul<Matrix4> for &Matrix4 {
     /// let c = &m * &u;
     /// ```
     fn mul(self, rhs: Matrix4) -> Matrix4 {
-        let mut a = Matrix4::from_matrix4(&self);
+        let mut a = Matrix4::from_matrix4(self);
         a.mult_mut(&rhs);
         a
     }
@@ -1017,3 +1853,20 @@ impl std::ops::Mul<Vector4> for &Matrix4 {
     }
 }
 
+
+#[test]
+fn test_try_look_at_matrix4() {
+    let eye = Vector3::from_i32(4, 3, 5);
+    let m = Matrix4::try_look_at(&eye, &Vector3::new(), &Vector3::new_z_up()).unwrap();
+    assert_matrix4_near(&m, &Matrix4::look_at(&eye, &Vector3::new(), &Vector3::new_z_up()));
+
+    let mut m = Matrix4::new();
+    assert_eq!(m.try_look_at_mut(&eye, &eye, &Vector3::new_z_up()).unwrap_err(), Error::ZeroLength);
+    let down = Vector3::from_i32(-4, -3, -5);
+    assert_eq!(m.try_look_at_mut(&eye, &Vector3::new(), &down).unwrap_err(), Error::ParallelUpVector);
+    assert_matrix4_near(&m, &Matrix4::new());
+
+    assert!(m.try_set_column(4, 0., 0., 0., 1.).is_err());
+    m.set_column(3, 1., 2., 3., 1.);
+    assert_eq!(m.m_3_1, 2.);
+}
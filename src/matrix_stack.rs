@@ -0,0 +1,315 @@
+//! Stacks of model-view matrices for drawing hierarchical scenes the classic OpenGL way:
+//! `push`, transform, draw the children, `pop`.
+//!
+//! # Example
+//! ```
+//! use js_linalg::{Matrix4, Vector3};
+//! use js_linalg::matrix_stack::MatrixStack;
+//! let view = Matrix4::look_at(&Vector3::from_i32(0, -5, 0), &Vector3::new(), &Vector3::new_z_up());
+//! let mut stack = MatrixStack::from_matrix4(&view);
+//!
+//! stack.push().translate(1., 0., 0.);
+//! // draw the body with stack.top() and stack.normal_matrix()
+//! stack.push().rotate(0.5, &Vector3::new_z_up()).scale(0.5, 0.5, 0.5);
+//! // draw the arm
+//! stack.pop();
+//! stack.pop();
+//! assert_eq!(stack.depth(), 1);
+//! ```
+
+use super::{Matrix3, Matrix4, Quaternion, Vector3};
+
+/// A growable stack of `Matrix4`'s. It always holds at least one matrix, the top of the stack,
+/// which all transformations are applied to.
+#[derive(Debug, Clone)]
+pub struct MatrixStack {
+    matrices: Vec<Matrix4>,
+}
+
+/// A stack of at most `N` `Matrix4`'s that never allocates, e.g. for embedded targets or per
+/// frame use without touching the heap. It always holds at least one matrix.
+#[derive(Debug, Clone)]
+pub struct FixedMatrixStack<const N: usize> {
+    matrices: [Matrix4; N],
+    len: usize,
+}
+
+fn translate(m:&mut Matrix4, x:f32, y:f32, z:f32) {
+    m.mult_mut(Matrix4::new().translation_mut(x, y, z));
+}
+
+fn rotate(m:&mut Matrix4, alpha:f32, axis:&Vector3) {
+    let mut r = Matrix4::new();
+    r.rotation_mut(alpha, axis);
+    m.mult_mut(&r);
+}
+
+fn scale(m:&mut Matrix4, x:f32, y:f32, z:f32) {
+    let mut s = Matrix4::new();
+    s.scale_mut(x, y, z);
+    m.mult_mut(&s);
+}
+
+impl MatrixStack {
+
+    /// Creates a new stack holding the identity matrix.
+    pub fn new() -> MatrixStack {
+        MatrixStack {matrices: vec![Matrix4::new()]}
+    }
+
+    /// Creates a new stack holding a copy of the provided matrix, usually the view matrix.
+    pub fn from_matrix4(m:&Matrix4) -> MatrixStack {
+        MatrixStack {matrices: vec![Matrix4::from_matrix4(m)]}
+    }
+
+    /// The number of matrices on the stack, at least 1.
+    pub fn depth(&self) -> usize {
+        self.matrices.len()
+    }
+
+    /// The current matrix.
+    pub fn top(&self) -> &Matrix4 {
+        self.matrices.last().unwrap()
+    }
+
+    /// The current matrix for modifications.
+    pub fn top_mut(&mut self) -> &mut Matrix4 {
+        self.matrices.last_mut().unwrap()
+    }
+
+    /// Pushes a copy of the current matrix, which becomes the new top.
+    pub fn push(&mut self) -> &mut MatrixStack {
+        let top = Matrix4::from_matrix4(self.top());
+        self.matrices.push(top);
+        self
+    }
+
+    /// Removes the current matrix, the previously pushed one becomes the top again. Panics if
+    /// only one matrix is left.
+    pub fn pop(&mut self) -> &mut MatrixStack {
+        assert!(self.matrices.len() > 1, "cannot pop the last matrix of a MatrixStack");
+        self.matrices.pop();
+        self
+    }
+
+    /// Replaces the current matrix by the identity matrix.
+    pub fn load_identity(&mut self) -> &mut MatrixStack {
+        self.top_mut().identity_mut();
+        self
+    }
+
+    /// Replaces the current matrix by a copy of the provided one.
+    pub fn load(&mut self, m:&Matrix4) -> &mut MatrixStack {
+        self.top_mut().set_matrix4(m);
+        self
+    }
+
+    /// Multiplies the provided matrix onto the current one from the right, `top = top * m`.
+    pub fn mult(&mut self, m:&Matrix4) -> &mut MatrixStack {
+        self.top_mut().mult_mut(m);
+        self
+    }
+
+    /// Multiplies a translation onto the current matrix from the right.
+    pub fn translate(&mut self, x:f32, y:f32, z:f32) -> &mut MatrixStack {
+        translate(self.top_mut(), x, y, z);
+        self
+    }
+
+    /// Multiplies a rotation of `alpha` radians around the provided (unit) axis onto the current
+    /// matrix from the right.
+    pub fn rotate(&mut self, alpha:f32, axis:&Vector3) -> &mut MatrixStack {
+        rotate(self.top_mut(), alpha, axis);
+        self
+    }
+
+    /// Multiplies the rotation of the provided (unit) quaternion onto the current matrix from the right.
+    pub fn rotate_quaternion(&mut self, q:&Quaternion) -> &mut MatrixStack {
+        self.top_mut().mult_mut(&q.to_matrix4());
+        self
+    }
+
+    /// Multiplies a scaling onto the current matrix from the right.
+    pub fn scale(&mut self, x:f32, y:f32, z:f32) -> &mut MatrixStack {
+        scale(self.top_mut(), x, y, z);
+        self
+    }
+
+    /// Computes the normal matrix of the current matrix, see `Matrix3::normal_matrix`.
+    pub fn normal_matrix(&self) -> Matrix3 {
+        Matrix3::normal_matrix(self.top())
+    }
+}
+
+impl Default for MatrixStack {
+    /// Same as `MatrixStack::new()`.
+    fn default() -> MatrixStack {
+        MatrixStack::new()
+    }
+}
+
+impl<const N: usize> FixedMatrixStack<N> {
+
+    /// Creates a new stack holding the identity matrix. Panics if `N` is zero.
+    pub fn new() -> FixedMatrixStack<N> {
+        assert!(N > 0, "a FixedMatrixStack needs room for at least one matrix");
+        FixedMatrixStack {matrices: [(); N].map(|_| Matrix4::new()), len: 1}
+    }
+
+    /// Creates a new stack holding a copy of the provided matrix, usually the view matrix.
+    pub fn from_matrix4(m:&Matrix4) -> FixedMatrixStack<N> {
+        let mut stack = FixedMatrixStack::new();
+        stack.load(m);
+        stack
+    }
+
+    /// The number of matrices on the stack, at least 1.
+    pub fn depth(&self) -> usize {
+        self.len
+    }
+
+    /// The maximum number of matrices on the stack, `N`.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The current matrix.
+    pub fn top(&self) -> &Matrix4 {
+        &self.matrices[self.len - 1]
+    }
+
+    /// The current matrix for modifications.
+    pub fn top_mut(&mut self) -> &mut Matrix4 {
+        &mut self.matrices[self.len - 1]
+    }
+
+    /// Pushes a copy of the current matrix, which becomes the new top. Panics if the stack is full.
+    pub fn push(&mut self) -> &mut FixedMatrixStack<N> {
+        assert!(self.len < N, "FixedMatrixStack overflow, capacity is {}", N);
+        let (below, above) = self.matrices.split_at_mut(self.len);
+        above[0].set_matrix4(&below[self.len - 1]);
+        self.len += 1;
+        self
+    }
+
+    /// Removes the current matrix, the previously pushed one becomes the top again. Panics if
+    /// only one matrix is left.
+    pub fn pop(&mut self) -> &mut FixedMatrixStack<N> {
+        assert!(self.len > 1, "cannot pop the last matrix of a FixedMatrixStack");
+        self.len -= 1;
+        self
+    }
+
+    /// Replaces the current matrix by the identity matrix.
+    pub fn load_identity(&mut self) -> &mut FixedMatrixStack<N> {
+        self.top_mut().identity_mut();
+        self
+    }
+
+    /// Replaces the current matrix by a copy of the provided one.
+    pub fn load(&mut self, m:&Matrix4) -> &mut FixedMatrixStack<N> {
+        self.top_mut().set_matrix4(m);
+        self
+    }
+
+    /// Multiplies the provided matrix onto the current one from the right, `top = top * m`.
+    pub fn mult(&mut self, m:&Matrix4) -> &mut FixedMatrixStack<N> {
+        self.top_mut().mult_mut(m);
+        self
+    }
+
+    /// Multiplies a translation onto the current matrix from the right.
+    pub fn translate(&mut self, x:f32, y:f32, z:f32) -> &mut FixedMatrixStack<N> {
+        translate(self.top_mut(), x, y, z);
+        self
+    }
+
+    /// Multiplies a rotation of `alpha` radians around the provided (unit) axis onto the current
+    /// matrix from the right.
+    pub fn rotate(&mut self, alpha:f32, axis:&Vector3) -> &mut FixedMatrixStack<N> {
+        rotate(self.top_mut(), alpha, axis);
+        self
+    }
+
+    /// Multiplies the rotation of the provided (unit) quaternion onto the current matrix from the right.
+    pub fn rotate_quaternion(&mut self, q:&Quaternion) -> &mut FixedMatrixStack<N> {
+        self.top_mut().mult_mut(&q.to_matrix4());
+        self
+    }
+
+    /// Multiplies a scaling onto the current matrix from the right.
+    pub fn scale(&mut self, x:f32, y:f32, z:f32) -> &mut FixedMatrixStack<N> {
+        scale(self.top_mut(), x, y, z);
+        self
+    }
+
+    /// Computes the normal matrix of the current matrix, see `Matrix3::normal_matrix`.
+    pub fn normal_matrix(&self) -> Matrix3 {
+        Matrix3::normal_matrix(self.top())
+    }
+}
+
+impl<const N: usize> Default for FixedMatrixStack<N> {
+    /// Same as `FixedMatrixStack::new()`.
+    fn default() -> FixedMatrixStack<N> {
+        FixedMatrixStack::new()
+    }
+}
+
+#[test]
+fn test_matrix_stack_push_pop() {
+    let mut stack = MatrixStack::new();
+    stack.translate(1., 2., 3.);
+    stack.push().scale(2., 2., 2.).rotate(std::f32::consts::FRAC_PI_2, &Vector3::new_z_up());
+
+    // scale and rotate apply first, then the translation of the parent
+    let v = stack.top() * Vector3::from_i32(1, 0, 0);
+    assert!((v.x - 1.).abs() < 1e-5);
+    assert!((v.y - 4.).abs() < 1e-5);
+    assert_eq!(stack.depth(), 2);
+
+    stack.pop();
+    let v = stack.top() * Vector3::new();
+    assert_eq!(v.x, 1.);
+    assert_eq!(v.z, 3.);
+
+    // the normal matrix is the inverse transpose: stretching x by 3 shrinks the x component of
+    // normals to 1/3, which keeps them perpendicular to the stretched surface
+    let mut n = Vector3::from_i32(1, 0, 0);
+    let normal_matrix = stack.push().scale(3., 1., 1.).normal_matrix();
+    normal_matrix.mult_to_vector3(&mut n);
+    assert!((n.x - 1. / 3.).abs() < 1e-5);
+
+    let mut tilted = Vector3::from_i32(1, 1, 0);
+    normal_matrix.mult_to_vector3(&mut tilted);
+    let tangent = stack.top() * Vector3::from_i32(1, -1, 0) - stack.top() * Vector3::new();
+    assert!(tilted.dot(&tangent).abs() < 1e-5);
+}
+
+#[test]
+#[should_panic]
+fn test_matrix_stack_pop_last() {
+    MatrixStack::new().pop();
+}
+
+#[test]
+fn test_fixed_matrix_stack_matches_matrix_stack() {
+    let q = Quaternion::from_euler(0.1, 0.2, 0.3);
+    let mut a = MatrixStack::new();
+    let mut b = FixedMatrixStack::<4>::new();
+    a.translate(1., 0., 0.).push().rotate_quaternion(&q).push().scale(1., 2., 3.);
+    b.translate(1., 0., 0.).push().rotate_quaternion(&q).push().scale(1., 2., 3.);
+    assert_eq!(a.top().as_array(), b.top().as_array());
+    assert_eq!(b.depth(), 3);
+
+    b.pop().pop();
+    assert_eq!(b.top().m_3_0, 1.);
+    assert_eq!(b.capacity(), 4);
+}
+
+#[test]
+#[should_panic]
+fn test_fixed_matrix_stack_overflow() {
+    let mut stack = FixedMatrixStack::<2>::new();
+    stack.push().push();
+}
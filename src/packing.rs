@@ -0,0 +1,325 @@
+//! Packing of vectors and matrices into byte buffers following the GLSL `std140` and
+//! `std430` layout rules, e.g. for uniform buffer objects and shader storage buffers.
+//!
+//! The central piece is `UniformBlock`, which appends values one by one and takes care of
+//! the alignment and padding rules, so that the resulting bytes match a GLSL block
+//! declaring the same members in the same order.
+//!
+//! # Example
+//! ```
+//! use js_linalg::{Matrix3, Matrix4, Vector3};
+//! use js_linalg::packing::{Layout, UniformBlock};
+//!
+//! // layout(std140) uniform Matrices {
+//! //     mat4 model_view;
+//! //     mat3 normal_matrix;
+//! //     vec3 light_position;
+//! //     float shininess;
+//! // };
+//! let model_view = Matrix4::new();
+//! let mut block = UniformBlock::new(Layout::Std140);
+//! block.push(&model_view)
+//!      .push(&Matrix3::normal_matrix(&model_view))
+//!      .push(&Vector3::from_i32(1, 2, 3))
+//!      .push(&32.0f32);
+//!
+//! // 64 bytes mat4, 48 bytes mat3 (columns padded to 16 bytes), float packed behind the vec3
+//! assert_eq!(block.offsets(), &[0, 64, 112, 124]);
+//! assert_eq!(block.finish().len(), 128);
+//! ```
+
+use super::{Vector2, Vector3, Vector4, Matrix2, Matrix3, Matrix4};
+
+/// The memory layout rules to pack values with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Layout {
+    /// `layout(std140)`, required for uniform blocks. Array elements and structs are
+    /// aligned to 16 bytes.
+    Std140,
+
+    /// `layout(std430)`, available for shader storage blocks. Like `Std140` but arrays of
+    /// scalars are tightly packed.
+    Std430
+}
+
+/// A value that can be written to a byte buffer following the `std140`/`std430` rules.
+pub trait Pack {
+    /// The base alignment of the value in bytes.
+    fn alignment(layout: Layout) -> usize;
+
+    /// The number of bytes written by `write`, not including trailing padding.
+    fn size(layout: Layout) -> usize;
+
+    /// Writes the value into the beginning of `out` in native byte order. `out` must be at
+    /// least `size(layout)` bytes long. Padding bytes inside the value are set to zero.
+    fn write(&self, layout: Layout, out: &mut [u8]);
+}
+
+/// Rounds `offset` up to the next multiple of `alignment`.
+pub fn align_to(offset: usize, alignment: usize) -> usize {
+    match offset % alignment {
+        0 => offset,
+        r => offset + alignment - r,
+    }
+}
+
+/// Returns the distance between two consecutive elements of an array of `T`.
+pub fn array_stride<T: Pack>(layout: Layout) -> usize {
+    array_alignment::<T>(layout).max(align_to(T::size(layout), array_alignment::<T>(layout)))
+}
+
+fn array_alignment<T: Pack>(layout: Layout) -> usize {
+    match layout {
+        Layout::Std140 => align_to(T::alignment(layout), 16),
+        Layout::Std430 => T::alignment(layout),
+    }
+}
+
+fn write_f32s(values: &[f32], out: &mut [u8]) {
+    for (i, v) in values.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&v.to_ne_bytes());
+    }
+}
+
+impl Pack for f32 {
+    fn alignment(_layout: Layout) -> usize { 4 }
+
+    fn size(_layout: Layout) -> usize { 4 }
+
+    fn write(&self, _layout: Layout, out: &mut [u8]) {
+        write_f32s(&[*self], out);
+    }
+}
+
+impl Pack for Vector2 {
+    fn alignment(_layout: Layout) -> usize { 8 }
+
+    fn size(_layout: Layout) -> usize { 8 }
+
+    fn write(&self, _layout: Layout, out: &mut [u8]) {
+        write_f32s(self.as_array(), out);
+    }
+}
+
+impl Pack for Vector3 {
+    fn alignment(_layout: Layout) -> usize { 16 }
+
+    fn size(_layout: Layout) -> usize { 12 }
+
+    fn write(&self, _layout: Layout, out: &mut [u8]) {
+        write_f32s(self.as_array(), out);
+    }
+}
+
+impl Pack for Vector4 {
+    fn alignment(_layout: Layout) -> usize { 16 }
+
+    fn size(_layout: Layout) -> usize { 16 }
+
+    fn write(&self, _layout: Layout, out: &mut [u8]) {
+        write_f32s(self.as_array(), out);
+    }
+}
+
+impl Pack for Matrix2 {
+    // two columns of vec2, laid out like an array of vec2
+    fn alignment(layout: Layout) -> usize {
+        array_alignment::<Vector2>(layout)
+    }
+
+    fn size(layout: Layout) -> usize {
+        2 * array_stride::<Vector2>(layout)
+    }
+
+    fn write(&self, layout: Layout, out: &mut [u8]) {
+        let stride = array_stride::<Vector2>(layout);
+        write_f32s(&[self.m_0_0, self.m_0_1], &mut out[0..8]);
+        write_f32s(&[self.m_1_0, self.m_1_1], &mut out[stride..stride + 8]);
+    }
+}
+
+impl Pack for Matrix3 {
+    fn alignment(_layout: Layout) -> usize { 16 }
+
+    // three columns of vec3, each padded to 16 bytes in both layouts
+    fn size(_layout: Layout) -> usize { 48 }
+
+    fn write(&self, _layout: Layout, out: &mut [u8]) {
+        write_f32s(&[self.m_0_0, self.m_0_1, self.m_0_2, 0.0], &mut out[0..16]);
+        write_f32s(&[self.m_1_0, self.m_1_1, self.m_1_2, 0.0], &mut out[16..32]);
+        write_f32s(&[self.m_2_0, self.m_2_1, self.m_2_2], &mut out[32..44]);
+    }
+}
+
+impl Pack for Matrix4 {
+    fn alignment(_layout: Layout) -> usize { 16 }
+
+    fn size(_layout: Layout) -> usize { 64 }
+
+    fn write(&self, _layout: Layout, out: &mut [u8]) {
+        write_f32s(self.as_array(), out);
+    }
+}
+
+/// Builder for the contents of a whole uniform (or shader storage) block. Values are
+/// appended in declaration order, the builder inserts the padding required by the layout
+/// and remembers the offset of each member.
+#[derive(Debug, Clone)]
+pub struct UniformBlock {
+    layout: Layout,
+    data: Vec<u8>,
+    offsets: Vec<usize>,
+    max_alignment: usize,
+}
+
+impl UniformBlock {
+
+    /// Creates a new, empty block following the provided layout rules.
+    pub fn new(layout: Layout) -> UniformBlock {
+        UniformBlock {layout, data: Vec::new(), offsets: Vec::new(), max_alignment: 4}
+    }
+
+    /// Appends a single value.
+    pub fn push<T: Pack>(&mut self, value: &T) -> &mut UniformBlock {
+        let offset = self.reserve(T::alignment(self.layout), T::size(self.layout));
+        value.write(self.layout, &mut self.data[offset..]);
+        self
+    }
+
+    /// Appends an array of values, e.g. `uniform vec4 lights[4]`. Each element is placed at
+    /// the array stride of the layout, see `array_stride`.
+    pub fn push_array<T: Pack>(&mut self, values: &[T]) -> &mut UniformBlock {
+        let stride = array_stride::<T>(self.layout);
+        let offset = self.reserve(array_alignment::<T>(self.layout), stride * values.len());
+        for (i, v) in values.iter().enumerate() {
+            v.write(self.layout, &mut self.data[offset + i * stride..]);
+        }
+        // the member following an array starts at the next multiple of the array alignment
+        let end = align_to(self.data.len(), array_alignment::<T>(self.layout));
+        self.data.resize(end, 0);
+        self
+    }
+
+    /// Aligns the end of the data and reserves `size` zeroed bytes, returns the offset.
+    fn reserve(&mut self, alignment: usize, size: usize) -> usize {
+        let offset = align_to(self.data.len(), alignment);
+        self.data.resize(offset + size, 0);
+        self.offsets.push(offset);
+        self.max_alignment = self.max_alignment.max(alignment);
+        offset
+    }
+
+    /// The layout rules this block follows.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// The byte offsets of all members pushed so far, in push order.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// The number of bytes written so far, without trailing padding.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if nothing has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The bytes written so far, without trailing padding.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Removes all values but keeps the allocated memory, e.g. to refill the block every frame.
+    pub fn clear(&mut self) -> &mut UniformBlock {
+        self.data.clear();
+        self.offsets.clear();
+        self.max_alignment = 4;
+        self
+    }
+
+    /// Returns the bytes with trailing padding to the block alignment, which is 16 bytes
+    /// for `std140` and the largest member alignment for `std430`.
+    pub fn finish(mut self) -> Vec<u8> {
+        let alignment = match self.layout {
+            Layout::Std140 => 16,
+            Layout::Std430 => self.max_alignment,
+        };
+        let end = align_to(self.data.len(), alignment);
+        self.data.resize(end, 0);
+        self.data
+    }
+}
+
+#[cfg(test)]
+fn read_f32(bytes: &[u8], offset: usize) -> f32 {
+    let mut b = [0u8; 4];
+    b.copy_from_slice(&bytes[offset..offset + 4]);
+    f32::from_ne_bytes(b)
+}
+
+#[test]
+fn test_std140_matrix3_columns_are_padded() {
+    let mut m = Matrix3::new();
+    m.m_1_0 = 5.;
+    m.m_2_2 = 7.;
+    let mut block = UniformBlock::new(Layout::Std140);
+    block.push(&m);
+
+    let bytes = block.as_bytes();
+    assert_eq!(bytes.len(), 48);
+    assert_eq!(read_f32(bytes, 0), 1.);
+    assert_eq!(read_f32(bytes, 12), 0.);
+    assert_eq!(read_f32(bytes, 16), 5.);
+    assert_eq!(read_f32(bytes, 20), 1.);
+    assert_eq!(read_f32(bytes, 40), 7.);
+}
+
+#[test]
+fn test_array_strides() {
+    let values = [1.0f32, 2.0, 3.0];
+
+    let mut block = UniformBlock::new(Layout::Std140);
+    block.push_array(&values).push(&4.0f32);
+    assert_eq!(block.offsets(), &[0, 48]);
+    assert_eq!(read_f32(block.as_bytes(), 16), 2.);
+
+    let mut block = UniformBlock::new(Layout::Std430);
+    block.push_array(&values).push(&4.0f32);
+    assert_eq!(block.offsets(), &[0, 12]);
+    assert_eq!(read_f32(block.as_bytes(), 4), 2.);
+    assert_eq!(block.finish().len(), 16);
+
+    let vectors = [Vector3::from_i32(1, 2, 3), Vector3::from_i32(4, 5, 6)];
+    let mut block = UniformBlock::new(Layout::Std430);
+    block.push_array(&vectors);
+    assert_eq!(read_f32(block.as_bytes(), 16), 4.);
+    assert_eq!(block.len(), 32);
+}
+
+#[test]
+fn test_vector3_followed_by_vector3() {
+    let mut block = UniformBlock::new(Layout::Std140);
+    block.push(&Vector3::new()).push(&Vector3::new()).push(&Vector4::new());
+    assert_eq!(block.offsets(), &[0, 16, 32]);
+}
+
+#[test]
+fn test_matrix2_layouts() {
+    let mut m = Matrix2::new();
+    m.m_1_0 = 3.;
+
+    let mut block = UniformBlock::new(Layout::Std140);
+    block.push(&Vector2::from_i32(1, 2)).push(&m);
+    assert_eq!(block.offsets(), &[0, 16]);
+    assert_eq!(read_f32(block.as_bytes(), 32), 3.);
+
+    let mut block = UniformBlock::new(Layout::Std430);
+    block.push(&Vector2::from_i32(1, 2)).push(&m);
+    assert_eq!(block.offsets(), &[0, 8]);
+    assert_eq!(read_f32(block.as_bytes(), 16), 3.);
+}
@@ -0,0 +1,596 @@
+use super::{Quaternion, Vector3, Matrix3, Matrix4, Error};
+
+impl Quaternion {
+
+    /// Creates a new `Quaternion` set to identity, i.e. no rotation.
+    pub fn new() -> Quaternion {
+        Quaternion {x: 0.0, y: 0.0, z: 0.0, w: 1.0}
+    }
+
+    /// Creates a new `Quaternion` from four `f32`'s. `w` is the scalar part.
+    pub fn from(x:f32, y:f32, z:f32, w:f32) -> Quaternion {
+        Quaternion {x, y, z, w}
+    }
+
+    /// Creates a new `Quaternion` from a copy of the provided one.
+    pub fn from_quaternion(q:&Quaternion) -> Quaternion {
+        Quaternion {x: q.x, y: q.y, z: q.z, w: q.w}
+    }
+
+    /// Creates a rotation of `alpha` radians around the provided axis. The axis
+    /// is expected to be normalized, just like in `Matrix4::rotation_mut`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Quaternion, Vector3};
+    /// let q = Quaternion::from_axis_angle(&Vector3::new_z_up(), std::f32::consts::FRAC_PI_2);
+    /// let v = q * Vector3::from_i32(1, 0, 0);
+    /// assert!((v.y - 1.).abs() < 1e-6);
+    /// ```
+    pub fn from_axis_angle(axis:&Vector3, alpha:f32) -> Quaternion {
+        let s = f32::sin(alpha * 0.5);
+        Quaternion {
+            x: axis.x * s,
+            y: axis.y * s,
+            z: axis.z * s,
+            w: f32::cos(alpha * 0.5),
+        }
+    }
+
+    /// Creates the shortest rotation that turns the direction `from` into the direction `to`.
+    /// The vectors do not need to be of unit length. Opposite directions result in a half turn
+    /// about an arbitrary perpendicular axis, a zero vector results in the identity.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Quaternion, Vector3};
+    /// let q = Quaternion::from_to(&Vector3::from_i32(2, 0, 0), &Vector3::from_i32(0, 0, 3));
+    /// let v = q * Vector3::from_i32(1, 0, 0);
+    /// assert!((v.z - 1.).abs() < 1e-6);
+    /// ```
+    pub fn from_to(from:&Vector3, to:&Vector3) -> Quaternion {
+        let (f, t) = match (from.try_normalize(), to.try_normalize()) {
+            (Ok(f), Ok(t)) => (f, t),
+            _ => return Quaternion::new(),
+        };
+
+        let d = f.dot(&t);
+        if d < -1.0 + 1e-6 {
+            let mut axis = Vector3::from(1.0, 0.0, 0.0).cross(&f);
+            if axis.length_squared() < 1e-6 {
+                axis = Vector3::from(0.0, 1.0, 0.0).cross(&f);
+            }
+            return Quaternion::from_axis_angle(&axis.normalize(), std::f32::consts::PI);
+        }
+
+        // half-way quaternion: (f x t, 1 + f . t) normalized has half the angle of (f x t, f . t)
+        let c = f.cross(&t);
+        let mut q = Quaternion {x: c.x, y: c.y, z: c.z, w: 1.0 + d};
+        q.normalize_mut();
+        q
+    }
+
+    /// Creates a rotation from Euler angles in radians. The rotations are applied
+    /// first around the x-axis, then around the y-axis and last around the z-axis,
+    /// which corresponds to the matrix product `Rz * Ry * Rx`.
+    pub fn from_euler(x:f32, y:f32, z:f32) -> Quaternion {
+        let (sx, cx) = f32::sin_cos(x * 0.5);
+        let (sy, cy) = f32::sin_cos(y * 0.5);
+        let (sz, cz) = f32::sin_cos(z * 0.5);
+
+        Quaternion {
+            x: sx * cy * cz - cx * sy * sz,
+            y: cx * sy * cz + sx * cy * sz,
+            z: cx * cy * sz - sx * sy * cz,
+            w: cx * cy * cz + sx * sy * sz,
+        }
+    }
+
+    /// Creates a rotation from the rotation matrix `m`. The matrix must be a pure
+    /// rotation (orthonormal, no scaling).
+    pub fn from_matrix3(m:&Matrix3) -> Quaternion {
+        Quaternion::from_rotation(
+            m.m_0_0, m.m_1_0, m.m_2_0,
+            m.m_0_1, m.m_1_1, m.m_2_1,
+            m.m_0_2, m.m_1_2, m.m_2_2)
+    }
+
+    /// Creates a rotation from the upper-left 3x3 block of `m`, which must be a pure
+    /// rotation (orthonormal, no scaling). The translation is ignored.
+    pub fn from_matrix4(m:&Matrix4) -> Quaternion {
+        Quaternion::from_rotation(
+            m.m_0_0, m.m_1_0, m.m_2_0,
+            m.m_0_1, m.m_1_1, m.m_2_1,
+            m.m_0_2, m.m_1_2, m.m_2_2)
+    }
+
+    /// Shepperd's method, arguments given row by row. Picks the largest of the four
+    /// possible divisors to stay numerically stable.
+    #[allow(clippy::too_many_arguments)]
+    fn from_rotation(r00:f32, r01:f32, r02:f32, r10:f32, r11:f32, r12:f32, r20:f32, r21:f32, r22:f32) -> Quaternion {
+        let trace = r00 + r11 + r22;
+
+        if trace > 0.0 {
+            let s = f32::sqrt(trace + 1.0) * 2.0;
+            Quaternion {
+                x: (r21 - r12) / s,
+                y: (r02 - r20) / s,
+                z: (r10 - r01) / s,
+                w: 0.25 * s,
+            }
+        } else if r00 > r11 && r00 > r22 {
+            let s = f32::sqrt(1.0 + r00 - r11 - r22) * 2.0;
+            Quaternion {
+                x: 0.25 * s,
+                y: (r01 + r10) / s,
+                z: (r02 + r20) / s,
+                w: (r21 - r12) / s,
+            }
+        } else if r11 > r22 {
+            let s = f32::sqrt(1.0 + r11 - r00 - r22) * 2.0;
+            Quaternion {
+                x: (r01 + r10) / s,
+                y: 0.25 * s,
+                z: (r12 + r21) / s,
+                w: (r02 - r20) / s,
+            }
+        } else {
+            let s = f32::sqrt(1.0 + r22 - r00 - r11) * 2.0;
+            Quaternion {
+                x: (r02 + r20) / s,
+                y: (r12 + r21) / s,
+                z: 0.25 * s,
+                w: (r10 - r01) / s,
+            }
+        }
+    }
+
+    /// Returns the rotation of this (unit) quaternion as new `Matrix3`.
+    pub fn to_matrix3(&self) -> Matrix3 {
+        let m = self.to_matrix4();
+        Matrix3 {
+            m_0_0: m.m_0_0,
+            m_0_1: m.m_0_1,
+            m_0_2: m.m_0_2,
+            m_1_0: m.m_1_0,
+            m_1_1: m.m_1_1,
+            m_1_2: m.m_1_2,
+            m_2_0: m.m_2_0,
+            m_2_1: m.m_2_1,
+            m_2_2: m.m_2_2,
+        }
+    }
+
+    /// Returns the rotation of this (unit) quaternion as new `Matrix4`
+    /// without translation. See `Matrix4::rotation_quaternion_mut`.
+    pub fn to_matrix4(&self) -> Matrix4 {
+        let mut m = Matrix4::new();
+        m.rotation_quaternion_mut(self);
+        m
+    }
+
+    /// Set the individual components of this `Quaternion`.
+    pub fn set(&mut self, xp:f32, yp:f32, zp:f32, wp:f32) -> &mut Quaternion {
+        self.x = xp;
+        self.y = yp;
+        self.z = zp;
+        self.w = wp;
+
+        self
+    }
+
+    /// Sets this quaternion to identity.
+    pub fn identity_mut(&mut self) -> &mut Quaternion {
+        self.set(0.0, 0.0, 0.0, 1.0)
+    }
+
+    /// Computes the dot product of this and the provided quaternion.
+    pub fn dot(&self, q:&Quaternion) -> f32 {
+        self.x * q.x + self.y * q.y + self.z * q.z + self.w * q.w
+    }
+
+    /// Returns the length (norm) of this quaternion.
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// In-place normalization to unit length. Rotations accumulated over many
+    /// multiplications slowly drift away from unit length and should be
+    /// re-normalized from time to time.
+    pub fn normalize_mut(&mut self) -> &mut Quaternion {
+        let d = self.length();
+        self.x /= d;
+        self.y /= d;
+        self.z /= d;
+        self.w /= d;
+
+        self
+    }
+
+    /// Returns a normalized copy of this quaternion.
+    pub fn normalize(&self) -> Quaternion {
+        let mut q = *self;
+        q.normalize_mut();
+        q
+    }
+
+    /// Normalizes this quaternion like `normalize`, but returns `Error::ZeroLength` instead of
+    /// NaN components if the length of this quaternion is (close to) zero.
+    pub fn try_normalize(&self) -> Result<Quaternion, Error> {
+        let mut q = *self;
+        q.try_normalize_mut()?;
+        Ok(q)
+    }
+
+    /// In-place variant of `try_normalize`. This quaternion is left untouched on error.
+    pub fn try_normalize_mut(&mut self) -> Result<&mut Quaternion, Error> {
+        let d = self.length();
+        if !(d >= f32::EPSILON && d.is_finite()) {
+            return Err(Error::ZeroLength);
+        }
+        Ok(self.normalize_mut())
+    }
+
+    /// In-place conjugation, i.e. the vector part is negated. For unit quaternions
+    /// this is the same as the inverse, but cheaper.
+    pub fn conjugate_mut(&mut self) -> &mut Quaternion {
+        self.x = -self.x;
+        self.y = -self.y;
+        self.z = -self.z;
+
+        self
+    }
+
+    /// Returns the conjugate of this quaternion as new `Quaternion`.
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion {x: -self.x, y: -self.y, z: -self.z, w: self.w}
+    }
+
+    /// In-place inversion. Also works for quaternions that are not of unit length.
+    pub fn inverse_mut(&mut self) -> &mut Quaternion {
+        let d = self.dot(self);
+        self.x = -self.x / d;
+        self.y = -self.y / d;
+        self.z = -self.z / d;
+        self.w /= d;
+
+        self
+    }
+
+    /// Returns the inverse of this quaternion as new `Quaternion`.
+    pub fn inverse(&self) -> Quaternion {
+        let mut q = *self;
+        q.inverse_mut();
+        q
+    }
+
+    /// Computes the inverse like `inverse`, but returns `Error::ZeroLength` for a (close to)
+    /// zero quaternion, which has no inverse.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Error, Quaternion};
+    /// let zero = Quaternion {x: 0., y: 0., z: 0., w: 0.};
+    /// assert_eq!(zero.try_inverse().unwrap_err(), Error::ZeroLength);
+    /// ```
+    pub fn try_inverse(&self) -> Result<Quaternion, Error> {
+        let d = self.dot(self);
+        if !(d >= f32::EPSILON && d.is_finite()) {
+            return Err(Error::ZeroLength);
+        }
+        Ok(self.inverse())
+    }
+
+    /// In-place multiplication `self = self * q`. The resulting rotation first
+    /// applies `q` and then `self`, just like with matrices.
+    pub fn mult_mut(&mut self, q:&Quaternion) -> &mut Quaternion {
+        let x = self.w * q.x + self.x * q.w + self.y * q.z - self.z * q.y;
+        let y = self.w * q.y - self.x * q.z + self.y * q.w + self.z * q.x;
+        let z = self.w * q.z + self.x * q.y - self.y * q.x + self.z * q.w;
+        let w = self.w * q.w - self.x * q.x - self.y * q.y - self.z * q.z;
+
+        self.set(x, y, z, w)
+    }
+
+    /// Rotates the provided vector in-place by this (unit) quaternion.
+    pub fn mult_to_vector3(&self, vec:&mut Vector3) {
+        // t = 2 * (q.xyz x v), v' = v + w * t + q.xyz x t
+        let tx = 2.0 * (self.y * vec.z - self.z * vec.y);
+        let ty = 2.0 * (self.z * vec.x - self.x * vec.z);
+        let tz = 2.0 * (self.x * vec.y - self.y * vec.x);
+
+        vec.x += self.w * tx + self.y * tz - self.z * ty;
+        vec.y += self.w * ty + self.z * tx - self.x * tz;
+        vec.z += self.w * tz + self.x * ty - self.y * tx;
+    }
+
+    /// Normalized linear interpolation between this and `q`. Cheaper than `slerp`
+    /// but does not have constant angular velocity. Takes the shortest path.
+    pub fn nlerp(&self, q:&Quaternion, t:f32) -> Quaternion {
+        let sign = if self.dot(q) < 0.0 { -1.0 } else { 1.0 };
+        let mut r = Quaternion {
+            x: self.x + (sign * q.x - self.x) * t,
+            y: self.y + (sign * q.y - self.y) * t,
+            z: self.z + (sign * q.z - self.z) * t,
+            w: self.w + (sign * q.w - self.w) * t,
+        };
+        r.normalize_mut();
+        r
+    }
+
+    /// Spherical linear interpolation between this and `q` with constant angular velocity.
+    /// Takes the shortest path and falls back to `nlerp` for nearly identical rotations.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Quaternion, Vector3};
+    /// let a = Quaternion::new();
+    /// let b = Quaternion::from_axis_angle(&Vector3::new_z_up(), 1.0);
+    /// let c = a.slerp(&b, 0.5);
+    /// let d = Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.5);
+    /// assert!((c.dot(&d) - 1.).abs() < 1e-6);
+    /// ```
+    pub fn slerp(&self, q:&Quaternion, t:f32) -> Quaternion {
+        let mut cos_theta = self.dot(q);
+        let mut end = *q;
+        if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            end.set(-q.x, -q.y, -q.z, -q.w);
+        }
+
+        if cos_theta > 0.9995 {
+            return self.nlerp(&end, t);
+        }
+
+        let theta = f32::acos(cos_theta);
+        let sin_theta = f32::sin(theta);
+        let a = f32::sin((1.0 - t) * theta) / sin_theta;
+        let b = f32::sin(t * theta) / sin_theta;
+
+        Quaternion {
+            x: a * self.x + b * end.x,
+            y: a * self.y + b * end.y,
+            z: a * self.z + b * end.z,
+            w: a * self.w + b * end.w,
+        }
+    }
+
+    /// Returns the components as array reference without copying, in the order `x, y, z, w`.
+    /// This is possible since the memory layout of `Quaternion` is guaranteed to be `[f32; 4]`.
+    pub fn as_array(&self) -> &[f32; 4] {
+        // SAFETY: `Quaternion` is `#[repr(C)]` and consists of 4 `f32`'s without padding, so it
+        // has the size and alignment of `[f32; 4]`; the result borrows `self`.
+        unsafe { &*(self as *const Quaternion as *const [f32; 4]) }
+    }
+
+    /// Returns the components as mutable array reference without copying.
+    pub fn as_mut_array(&mut self) -> &mut [f32; 4] {
+        // SAFETY: same layout argument as in `as_array`, the exclusive borrow of `self` is
+        // handed over to the returned array.
+        unsafe { &mut *(self as *mut Quaternion as *mut [f32; 4]) }
+    }
+
+    /// Returns the components as slice without copying.
+    pub fn as_slice(&self) -> &[f32] {
+        self.as_array()
+    }
+
+    /// Returns a pointer to the first component (`x`), e.g. for `glUniform*` calls.
+    /// The pointer is valid for 4 `f32`'s as long as this `Quaternion` lives.
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Quaternion as *const f32
+    }
+
+    /// Reinterprets a slice of `Quaternion`'s as one flat slice of `f32`'s without copying, e.g.
+    /// for uploading the joint rotations of a pose. The result has `4 * values.len()` elements.
+    pub fn slice_as_f32(values: &[Quaternion]) -> &[f32] {
+        // SAFETY: every element is 4 consecutive `f32`'s without padding (`#[repr(C)]`) and
+        // slice elements are contiguous, so the memory is exactly `4 * len` initialized `f32`'s
+        // borrowed for the lifetime of `values`.
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const f32, values.len() * 4) }
+    }
+}
+
+impl Default for Quaternion {
+    /// Same as `Quaternion::new()`.
+    fn default() -> Quaternion {
+        Quaternion::new()
+    }
+}
+
+impl std::ops::Mul<Quaternion> for Quaternion {
+    type Output = Quaternion;
+
+    /// Overrides '*' operator to concatenate two rotations.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Quaternion;
+    /// let a = Quaternion::new();
+    /// let b = Quaternion::from(0., 0., 1., 0.);
+    /// let c = a * b;
+    /// assert_eq!(c.z, 1.);
+    /// ```
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        let mut q = self;
+        q.mult_mut(&rhs);
+        q
+    }
+}
+
+impl std::ops::Mul<&Quaternion> for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: &Quaternion) -> Quaternion {
+        let mut q = self;
+        q.mult_mut(rhs);
+        q
+    }
+}
+
+impl std::ops::Mul<&Quaternion> for &Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: &Quaternion) -> Quaternion {
+        let mut q = *self;
+        q.mult_mut(rhs);
+        q
+    }
+}
+
+impl std::ops::Mul<Quaternion> for &Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: Quaternion) -> Quaternion {
+        let mut q = *self;
+        q.mult_mut(&rhs);
+        q
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn check_quaternion_mul_completeness() {
+    let a = Quaternion::new();
+    let b = Quaternion::from(0., 1., 0., 0.);
+
+    let _c  = a * b;
+    let _c  = &a * b;
+    let _c  = a * &b;
+    let _c  = &a * &b;
+}
+
+impl std::ops::MulAssign<&Quaternion> for Quaternion {
+
+    fn mul_assign(&mut self, rhs: &Quaternion) {
+        self.mult_mut(rhs);
+    }
+}
+
+impl std::ops::MulAssign<Quaternion> for Quaternion {
+
+    fn mul_assign(&mut self, rhs: Quaternion) {
+        self.mult_mut(&rhs);
+    }
+}
+
+impl std::ops::Mul<Vector3> for Quaternion {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to rotate a `Vector3` by a `Quaternion`.
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        let mut v = rhs;
+        self.mult_to_vector3(&mut v);
+        v
+    }
+}
+
+impl std::ops::Mul<&Vector3> for &Quaternion {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to rotate a `Vector3` by a `Quaternion`.
+    fn mul(self, rhs: &Vector3) -> Vector3 {
+        let mut v = *rhs;
+        self.mult_to_vector3(&mut v);
+        v
+    }
+}
+
+impl std::ops::Mul<&Vector3> for Quaternion {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to rotate a `Vector3` by a `Quaternion`.
+    fn mul(self, rhs: &Vector3) -> Vector3 {
+        let mut v = *rhs;
+        self.mult_to_vector3(&mut v);
+        v
+    }
+}
+
+impl std::ops::Mul<Vector3> for &Quaternion {
+    type Output = Vector3;
+
+    /// Overrides '*' operator to rotate a `Vector3` by a `Quaternion`.
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        let mut v = rhs;
+        self.mult_to_vector3(&mut v);
+        v
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn check_quaternion_mul_vector3_completeness() {
+    let q = Quaternion::new();
+    let v = Vector3::from_i32(1, 2, 3);
+
+    let _v  = q * v;
+    let _v  = &q * v;
+    let _v  = q * &v;
+    let _v  = &q * &v;
+}
+
+#[test]
+fn test_quaternion_matches_rotation_matrix() {
+    let mut axis = Vector3::from(1., -2., 3.);
+    axis.normalize_mut();
+
+    let q = Quaternion::from_axis_angle(&axis, 0.8);
+    let mut m = Matrix4::new();
+    m.rotation_mut(0.8, &axis);
+
+    let v = Vector3::from(0.3, 4., -1.);
+    let a = q * v;
+    let b = &m * v;
+    assert!((a.x - b.x).abs() < 1e-5);
+    assert!((a.y - b.y).abs() < 1e-5);
+    assert!((a.z - b.z).abs() < 1e-5);
+
+    // round trip over the matrix representation
+    let r = Quaternion::from_matrix4(&m);
+    assert!((r.dot(&q).abs() - 1.).abs() < 1e-5);
+    let r = Quaternion::from_matrix3(&q.to_matrix3());
+    assert!((r.dot(&q).abs() - 1.).abs() < 1e-5);
+}
+
+#[test]
+fn test_quaternion_euler_and_inverse() {
+    let qx = Quaternion::from_axis_angle(&Vector3::from_i32(1, 0, 0), 0.3);
+    let qy = Quaternion::from_axis_angle(&Vector3::from_i32(0, 1, 0), -1.1);
+    let qz = Quaternion::from_axis_angle(&Vector3::from_i32(0, 0, 1), 2.0);
+    let q = Quaternion::from_euler(0.3, -1.1, 2.0);
+    assert!((q.dot(&(qz * qy * qx)) - 1.).abs() < 1e-5);
+
+    let i = q * q.inverse();
+    assert!((i.w - 1.).abs() < 1e-6);
+    assert!(i.x.abs() < 1e-6 && i.y.abs() < 1e-6 && i.z.abs() < 1e-6);
+}
+
+#[test]
+fn test_quaternion_slerp_shortest_path() {
+    let a = Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.1);
+    let b = Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.5);
+    let mut neg_b = b;
+    neg_b.set(-b.x, -b.y, -b.z, -b.w);
+
+    let c = a.slerp(&neg_b, 0.5);
+    let d = Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.3);
+    assert!((c.dot(&d) - 1.).abs() < 1e-5);
+    assert!((c.length() - 1.).abs() < 1e-5);
+}
+
+#[test]
+fn test_quaternion_memory_layout() {
+    assert_eq!(std::mem::size_of::<Quaternion>(), 4 * std::mem::size_of::<f32>());
+
+    let mut q = Quaternion::from(1., 2., 3., 4.);
+    assert_eq!(q.as_array(), &[1., 2., 3., 4.]);
+    q.as_mut_array()[3] = 5.;
+    assert_eq!(q.w, 5.);
+    assert_eq!(q.as_slice().len(), 4);
+    // SAFETY: the pointer is valid for the 4 components of `q`
+    assert_eq!(unsafe { *q.as_ptr().add(2) }, 3.);
+
+    let values = [q, Quaternion::from(6., 7., 8., 9.)];
+    assert_eq!(Quaternion::slice_as_f32(&values), &[1., 2., 3., 5., 6., 7., 8., 9.]);
+}
@@ -0,0 +1,482 @@
+use super::{Ray, RayHit, TriangleHit, Viewport, Matrix4, Vector3};
+use super::geometry::{Aabb, Capsule, Obb, Plane, Sphere, Triangle};
+
+impl Ray {
+
+    /// Creates a new `Ray` from an origin and a direction. The direction is
+    /// copied as is, i.e. it is not normalized.
+    pub fn new(origin:&Vector3, direction:&Vector3) -> Ray {
+        Ray {origin: *origin, direction: *direction}
+    }
+
+    /// Creates a new `Ray` starting at `from` and pointing towards `to`. The
+    /// direction is normalized.
+    pub fn from_points(from:&Vector3, to:&Vector3) -> Ray {
+        let mut direction = to - from;
+        direction.normalize_mut();
+        Ray {origin: *from, direction}
+    }
+
+    /// Creates a world-space picking ray through the provided screen coordinates. `x` and `y`
+    /// are in pixels relative to the upper-left corner of the viewport, i.e. as delivered by
+    /// mouse events. `projection` and `view` are the matrices built with `projection_mut` and
+    /// `look_at_mut` (or any of the infinite and reversed-Z variants, as long as the depth range
+    /// of `viewport` matches). The ray starts on the near clipping plane and its direction is normalized.
+    /// Returns `None` if `projection * view` cannot be inverted.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix4, Ray, Vector3, Viewport};
+    /// let mut projection = Matrix4::new();
+    /// projection.projection_mut(45., 800., 600., 0.1, 100.);
+    /// let view = Matrix4::look_at(&Vector3::from_i32(0, -5, 0), &Vector3::new(), &Vector3::new_z_up());
+    ///
+    /// // a click into the center of the viewport looks straight at the center of the scene
+    /// let ray = Ray::from_screen(400., 300., &Viewport::from_size(800., 600.), &projection, &view).unwrap();
+    /// assert!((ray.direction.y - 1.).abs() < 1e-5);
+    /// ```
+    pub fn from_screen(x:f32, y:f32, viewport:&Viewport, projection:&Matrix4, view:&Matrix4) -> Option<Ray> {
+        let inverse = (projection * view).try_inverse().ok()?;
+
+        // screen coordinates grow downwards, window coordinates upwards
+        let window_y = viewport.y + viewport.height - y;
+        let window_x = viewport.x + x;
+
+        // The near clipping plane is at depth 0, or at depth 1 for reversed-Z projections. The
+        // other end may be at infinity, so the direction is taken towards the middle depth instead.
+        let a = viewport.unproject_with_inverse(&Vector3::from(window_x, window_y, 0.0), &inverse);
+        let b = viewport.unproject_with_inverse(&Vector3::from(window_x, window_y, 1.0), &inverse);
+        let middle = viewport.unproject_with_inverse(&Vector3::from(window_x, window_y, 0.5), &inverse)?;
+
+        let near = match (a, b) {
+            (Some(a), Some(b)) => {
+                // cameras look along -z in view space, the near plane is the larger z
+                let mut a_view = a;
+                let mut b_view = b;
+                view.mult_to_vector3(&mut a_view, 1.0);
+                view.mult_to_vector3(&mut b_view, 1.0);
+                if a_view.z >= b_view.z { a } else { b }
+            },
+            (Some(a), None) => a,
+            (None, Some(b)) => b,
+            (None, None)    => return None,
+        };
+
+        Some(Ray::from_points(&near, &middle))
+    }
+
+    /// Returns the point `origin + t * direction`.
+    pub fn point_at(&self, t:f32) -> Vector3 {
+        Vector3 {
+            x: self.origin.x + t * self.direction.x,
+            y: self.origin.y + t * self.direction.y,
+            z: self.origin.z + t * self.direction.z,
+        }
+    }
+
+    /// Casts this ray against the provided plane. Returns `None` if the ray is parallel to the
+    /// plane or points away from it. The normal of the hit is the normal of the plane.
+    pub fn intersect_plane(&self, plane:&Plane) -> Option<RayHit> {
+        let denom = plane.normal.dot(&self.direction);
+        if denom.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let t = -plane.signed_distance(&self.origin) / denom;
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(RayHit {distance: t, point: self.point_at(t), normal: plane.normal})
+    }
+
+    /// Casts this ray against the provided sphere. Rays starting inside the sphere hit it
+    /// from the inside, at the point where they leave it.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Ray, Vector3};
+    /// use js_linalg::geometry::Sphere;
+    /// let ray = Ray::new(&Vector3::from_i32(0, -5, 0), &Vector3::from_i32(0, 1, 0));
+    /// let hit = ray.intersect_sphere(&Sphere::new(&Vector3::new(), 1.)).unwrap();
+    /// assert_eq!(hit.distance, 4.);
+    /// assert_eq!(hit.normal.y, -1.);
+    /// ```
+    pub fn intersect_sphere(&self, sphere:&Sphere) -> Option<RayHit> {
+        let (t0, t1) = self.sphere_roots(&sphere.center, sphere.radius)?;
+        let t = if t0 >= 0.0 { t0 } else if t1 >= 0.0 { t1 } else { return None };
+
+        let point = self.point_at(t);
+        let normal = (point - sphere.center) * (1.0 / sphere.radius);
+        Some(RayHit {distance: t, point, normal})
+    }
+
+    /// Both solutions of `|origin + t * direction - center| = radius` in ascending order.
+    fn sphere_roots(&self, center:&Vector3, radius:f32) -> Option<(f32, f32)> {
+        let oc = self.origin - center;
+        let a = self.direction.length_squared();
+        let b = oc.dot(&self.direction);
+        let c = oc.length_squared() - radius * radius;
+
+        let h = b * b - a * c;
+        if h < 0.0 || a == 0.0 {
+            return None;
+        }
+        let h = h.sqrt();
+        Some(((-b - h) / a, (-b + h) / a))
+    }
+
+    /// Casts this ray against the provided axis-aligned box using the slab method. Rays
+    /// starting inside the box hit it from the inside, at the point where they leave it.
+    /// A ray with zero direction never hits.
+    pub fn intersect_aabb(&self, aabb:&Aabb) -> Option<RayHit> {
+        let origin = [self.origin.x, self.origin.y, self.origin.z];
+        let direction = [self.direction.x, self.direction.y, self.direction.z];
+        if direction == [0.0; 3] {
+            return None;
+        }
+        let min = [aabb.min.x, aabb.min.y, aabb.min.z];
+        let max = [aabb.max.x, aabb.max.y, aabb.max.z];
+
+        let mut t_near = f32::NEG_INFINITY;
+        let mut t_far = f32::INFINITY;
+        let mut axis_near = 0;
+        let mut axis_far = 0;
+
+        for i in 0..3 {
+            if direction[i] == 0.0 {
+                // parallel to the slab, either always or never inside
+                if origin[i] < min[i] || origin[i] > max[i] {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv = 1.0 / direction[i];
+            let mut t0 = (min[i] - origin[i]) * inv;
+            let mut t1 = (max[i] - origin[i]) * inv;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > t_near {
+                t_near = t0;
+                axis_near = i;
+            }
+            if t1 < t_far {
+                t_far = t1;
+                axis_far = i;
+            }
+            if t_near > t_far || t_far < 0.0 {
+                return None;
+            }
+        }
+
+        // entering a slab means moving against the outward normal, leaving means moving along it
+        let (t, axis, sign) = if t_near >= 0.0 {
+            (t_near, axis_near, -direction[axis_near].signum())
+        } else {
+            (t_far, axis_far, direction[axis_far].signum())
+        };
+
+        let mut normal = [0.0; 3];
+        normal[axis] = sign;
+        Some(RayHit {distance: t, point: self.point_at(t), normal: Vector3::from(normal[0], normal[1], normal[2])})
+    }
+
+    /// Casts this ray against the provided oriented box by casting it against the box in the local
+    /// space of the box. Like `intersect_aabb`, rays starting inside hit the box from the inside.
+    pub fn intersect_obb(&self, obb:&Obb) -> Option<RayHit> {
+        let d = &self.direction;
+        let local = Ray {
+            origin: obb.to_local(&self.origin),
+            direction: Vector3::from(d.dot(&obb.axes[0]), d.dot(&obb.axes[1]), d.dot(&obb.axes[2])),
+        };
+        let h = obb.half_extents;
+        let hit = local.intersect_aabb(&Aabb::new(&(h * -1.0), &h))?;
+
+        let n = &hit.normal;
+        Some(RayHit {
+            distance: hit.distance,
+            point: self.point_at(hit.distance),
+            normal: obb.axes[0] * n.x + obb.axes[1] * n.y + obb.axes[2] * n.z,
+        })
+    }
+
+    /// Casts this ray against the provided triangle (Möller-Trumbore). With `cull_backfaces` set,
+    /// triangles seen from the back, i.e. with clockwise corners, are never hit. The normal of the
+    /// hit is the front facing normal of the triangle, see `Triangle::normal`.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Ray, Vector3};
+    /// use js_linalg::geometry::Triangle;
+    /// let triangle = Triangle::new(&Vector3::from_i32(0, 0, 0), &Vector3::from_i32(1, 0, 0), &Vector3::from_i32(0, 1, 0));
+    /// let ray = Ray::new(&Vector3::from(0.25, 0.25, 1.), &Vector3::from_i32(0, 0, -1));
+    /// let hit = ray.intersect_triangle(&triangle, true).unwrap();
+    /// assert_eq!(hit.hit.distance, 1.);
+    /// assert_eq!(hit.barycentric.x, 0.5);
+    ///
+    /// let from_below = Ray::new(&Vector3::from(0.25, 0.25, -1.), &Vector3::from_i32(0, 0, 1));
+    /// assert!(from_below.intersect_triangle(&triangle, true).is_none());
+    /// assert!(from_below.intersect_triangle(&triangle, false).is_some());
+    /// ```
+    pub fn intersect_triangle(&self, triangle:&Triangle, cull_backfaces:bool) -> Option<TriangleHit> {
+        let e1 = triangle.b - triangle.a;
+        let e2 = triangle.c - triangle.a;
+        let n = e1.cross(&e2);
+        let p = self.direction.cross(&e2);
+        let det = e1.dot(&p);
+
+        // det is -dot(direction, n), so compare it relative to both lengths: this only rejects
+        // rays (nearly) parallel to the triangle, no matter how small the triangle is
+        let tolerance = f32::EPSILON * n.length() * self.direction.length();
+        if cull_backfaces {
+            if det <= tolerance {
+                return None;
+            }
+        } else if det.abs() <= tolerance {
+            return None;
+        }
+
+        let inv = 1.0 / det;
+        let s = self.origin - triangle.a;
+        let u = s.dot(&p) * inv;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = s.cross(&e1);
+        let v = self.direction.dot(&q) * inv;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = e2.dot(&q) * inv;
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(TriangleHit {
+            hit: RayHit {distance: t, point: self.point_at(t), normal: n.normalize()},
+            barycentric: Vector3::from(1.0 - u - v, u, v),
+        })
+    }
+
+    /// Casts this ray against the provided capsule. Like `intersect_sphere`, rays starting inside
+    /// hit the capsule from the inside.
+    pub fn intersect_capsule(&self, capsule:&Capsule) -> Option<RayHit> {
+        let pa = capsule.segment.start;
+        let pb = capsule.segment.end;
+        let r = capsule.radius;
+
+        let length = pa.distance(&pb);
+        if length < f32::EPSILON {
+            return self.intersect_sphere(&Sphere::new(&pa, r));
+        }
+        let axis = (pb - pa) * (1.0 / length);
+
+        // the parameter along the axis decides which part of the surface a candidate belongs to
+        let height = |t:f32| axis.dot(&(self.point_at(t) - pa));
+        let mut best:Option<(f32, Vector3)> = None;
+        let mut consider = |t:f32, center:Vector3| {
+            if t >= 0.0 && best.iter().all(|&(b, _)| t < b) {
+                best = Some((t, center));
+            }
+        };
+
+        // the body: the infinite cylinder, restricted to the segment
+        let perp = |v:&Vector3| v - axis * axis.dot(v);
+        let oa = perp(&(self.origin - pa));
+        let d = perp(&self.direction);
+        let a = d.length_squared();
+        if a > 0.0 {
+            let b = oa.dot(&d);
+            let c = oa.length_squared() - r * r;
+            let h = b * b - a * c;
+            if h >= 0.0 {
+                let h = h.sqrt();
+                for t in [(-b - h) / a, (-b + h) / a].iter() {
+                    let y = height(*t);
+                    if (0.0..=length).contains(&y) {
+                        consider(*t, pa + axis * y);
+                    }
+                }
+            }
+        }
+
+        // the caps: each half sphere beyond its end of the segment
+        for (center, beyond_start) in [(pa, true), (pb, false)].iter() {
+            if let Some((t0, t1)) = self.sphere_roots(center, r) {
+                for t in [t0, t1].iter() {
+                    let y = height(*t);
+                    if (*beyond_start && y <= 0.0) || (!*beyond_start && y >= length) {
+                        consider(*t, *center);
+                    }
+                }
+            }
+        }
+
+        let (t, center) = best?;
+        let point = self.point_at(t);
+        Some(RayHit {distance: t, point, normal: (point - center) * (1.0 / r)})
+    }
+
+    /// Transforms this ray in-place by the provided matrix, e.g. to bring a world-space
+    /// ray into the local space of a model via the inverse model matrix. The direction
+    /// is not re-normalized so that distances along the ray stay comparable.
+    pub fn transform_mut(&mut self, m:&Matrix4) -> &mut Ray {
+        m.mult_to_vector3(&mut self.origin, 1.0);
+        m.mult_to_vector3(&mut self.direction, 0.0);
+        self
+    }
+}
+
+#[test]
+fn test_ray_from_screen_hits_projected_point() {
+    let mut projection = Matrix4::new();
+    projection.projection_mut(60., 640., 480., 0.5, 50.);
+    let eye = Vector3::from_i32(4, 3, 5);
+    let view = Matrix4::look_at(&eye, &Vector3::new(), &Vector3::new_z_up());
+    let viewport = Viewport::from_size(640., 480.);
+
+    let world = Vector3::from(0.5, -1., 0.25);
+    let window = viewport.project(&world, &projection, &view).unwrap();
+    let ray = Ray::from_screen(window.x, 480. - window.y, &viewport, &projection, &view).unwrap();
+
+    // the ray runs from the near plane through the original point
+    let expected = Ray::from_points(&eye, &world);
+    assert!((ray.direction.x - expected.direction.x).abs() < 1e-3);
+    assert!((ray.direction.y - expected.direction.y).abs() < 1e-3);
+    assert!((ray.direction.z - expected.direction.z).abs() < 1e-3);
+}
+
+#[test]
+fn test_ray_from_screen_reversed_z() {
+    use super::DepthRange;
+    let mut projection = Matrix4::new();
+    projection.projection_reversed_z_mut(45., 800., 600., 0.1);
+    let view = Matrix4::look_at(&Vector3::from_i32(0, -5, 0), &Vector3::new(), &Vector3::new_z_up());
+    let mut viewport = Viewport::from_size(800., 600.);
+    viewport.depth_range = DepthRange::ZeroToOne;
+
+    let ray = Ray::from_screen(400., 300., &viewport, &projection, &view).unwrap();
+    assert!((ray.direction.y - 1.).abs() < 1e-5);
+    assert!((ray.origin.y + 4.9).abs() < 1e-3);
+}
+
+#[cfg(test)]
+fn assert_hit(hit:Option<RayHit>, distance:f32, normal:&Vector3) {
+    let hit = hit.unwrap();
+    assert!((hit.distance - distance).abs() < 1e-4, "{:?}", hit);
+    assert!(hit.normal.distance(normal) < 1e-4, "{:?}", hit);
+}
+
+#[test]
+fn test_ray_plane_and_sphere() {
+    let ray = Ray::new(&Vector3::from_i32(0, 0, 5), &Vector3::from_i32(0, 0, -1));
+    let ground = Plane::from_point_normal(&Vector3::new(), &Vector3::new_z_up());
+    assert_hit(ray.intersect_plane(&ground), 5., &Vector3::new_z_up());
+    assert!(Ray::new(&Vector3::new(), &Vector3::from_i32(1, 0, 0)).intersect_plane(&ground).is_none());
+    assert!(Ray::new(&Vector3::from_i32(0, 0, 5), &Vector3::new_z_up()).intersect_plane(&ground).is_none());
+
+    let sphere = Sphere::new(&Vector3::from_i32(0, 0, 1), 1.);
+    assert_hit(ray.intersect_sphere(&sphere), 3., &Vector3::new_z_up());
+
+    // from inside, the exit point
+    let inside = Ray::new(&Vector3::from_i32(0, 0, 1), &Vector3::from_i32(1, 0, 0));
+    assert_hit(inside.intersect_sphere(&sphere), 1., &Vector3::from_i32(1, 0, 0));
+
+    let miss = Ray::new(&Vector3::from_i32(3, 0, 5), &Vector3::from_i32(0, 0, -1));
+    assert!(miss.intersect_sphere(&sphere).is_none());
+}
+
+#[test]
+fn test_ray_aabb_and_obb() {
+    let aabb = Aabb::new(&Vector3::from_i32(-1, -1, -1), &Vector3::from_i32(1, 1, 1));
+    let ray = Ray::new(&Vector3::from_i32(-5, 0, 0), &Vector3::from_i32(1, 0, 0));
+    assert_hit(ray.intersect_aabb(&aabb), 4., &Vector3::from_i32(-1, 0, 0));
+
+    let diagonal = Ray::from_points(&Vector3::from(3., 3., 0.5), &Vector3::from(0., 0., 0.5));
+    let hit = diagonal.intersect_aabb(&aabb).unwrap();
+    assert!((hit.point.x - 1.).abs() < 1e-5);
+
+    let inside = Ray::new(&Vector3::new(), &Vector3::from_i32(0, -1, 0));
+    assert_hit(inside.intersect_aabb(&aabb), 1., &Vector3::from_i32(0, -1, 0));
+
+    let parallel_miss = Ray::new(&Vector3::from_i32(-5, 2, 0), &Vector3::from_i32(1, 0, 0));
+    assert!(parallel_miss.intersect_aabb(&aabb).is_none());
+    let behind = Ray::new(&Vector3::from_i32(5, 0, 0), &Vector3::from_i32(1, 0, 0));
+    assert!(behind.intersect_aabb(&aabb).is_none());
+    let zero = Ray::new(&Vector3::new(), &Vector3::new());
+    assert!(zero.intersect_aabb(&aabb).is_none());
+
+    // a box rotated by 45 degrees around z shows an edge towards -x at distance sqrt(2)
+    let mut obb = Obb::from_aabb(&aabb);
+    let mut r = Matrix4::new();
+    r.rotation_mut(std::f32::consts::FRAC_PI_4, &Vector3::new_z_up());
+    obb.transform_mut(&r);
+    let hit = ray.intersect_obb(&obb).unwrap();
+    assert!((hit.distance - (5. - 2f32.sqrt())).abs() < 1e-4);
+    assert!((hit.normal.length() - 1.).abs() < 1e-5);
+    assert!(hit.normal.x < 0.);
+}
+
+#[test]
+fn test_ray_triangle_barycentric() {
+    let t = Triangle::new(&Vector3::from_i32(0, 0, 0), &Vector3::from_i32(4, 0, 0), &Vector3::from_i32(0, 4, 0));
+    let ray = Ray::new(&Vector3::from(1., 2., 3.), &Vector3::from_i32(0, 0, -1));
+    let hit = ray.intersect_triangle(&t, false).unwrap();
+    assert_hit(Some(hit.hit), 3., &Vector3::new_z_up());
+
+    let b = hit.barycentric;
+    let p = t.a * b.x + t.b * b.y + t.c * b.z;
+    assert!(p.distance(&Vector3::from(1., 2., 0.)) < 1e-5);
+
+    let outside = Ray::new(&Vector3::from(3., 3., 3.), &Vector3::from_i32(0, 0, -1));
+    assert!(outside.intersect_triangle(&t, false).is_none());
+
+    // a tiny triangle must still be hit, a ray in its plane and a degenerate triangle must not
+    let small = Triangle::new(&Vector3::new(), &Vector3::from(1e-4, 0., 0.), &Vector3::from(0., 1e-4, 0.));
+    let hit = Ray::new(&Vector3::from(2e-5, 2e-5, 1.), &Vector3::from_i32(0, 0, -1)).intersect_triangle(&small, true).unwrap();
+    assert_hit(Some(hit.hit), 1., &Vector3::new_z_up());
+    let in_plane = Ray::new(&Vector3::from(-1., 1., 0.), &Vector3::from_i32(1, 0, 0));
+    assert!(in_plane.intersect_triangle(&t, false).is_none());
+    let line = Triangle::new(&Vector3::new(), &Vector3::from_i32(1, 1, 0), &Vector3::from_i32(2, 2, 0));
+    assert!(ray.intersect_triangle(&line, false).is_none());
+}
+
+#[test]
+fn test_ray_capsule() {
+    let capsule = Capsule::new(&Vector3::from_i32(0, 0, -2), &Vector3::from_i32(0, 0, 2), 1.);
+
+    // the body from the side
+    let side = Ray::new(&Vector3::from_i32(-5, 0, 1), &Vector3::from_i32(1, 0, 0));
+    assert_hit(side.intersect_capsule(&capsule), 4., &Vector3::from_i32(-1, 0, 0));
+
+    // the cap from the top, along the axis
+    let top = Ray::new(&Vector3::from_i32(0, 0, 10), &Vector3::from_i32(0, 0, -1));
+    assert_hit(top.intersect_capsule(&capsule), 7., &Vector3::new_z_up());
+
+    // from inside, leaving through the bottom cap
+    let inside = Ray::new(&Vector3::new(), &Vector3::from_i32(0, 0, -1));
+    assert_hit(inside.intersect_capsule(&capsule), 3., &Vector3::from_i32(0, 0, -1));
+
+    let miss = Ray::new(&Vector3::from_i32(-5, 2, 0), &Vector3::from_i32(1, 0, 0));
+    assert!(miss.intersect_capsule(&capsule).is_none());
+}
+
+#[test]
+fn test_ray_from_screen_picks_sphere() {
+    let mut projection = Matrix4::new();
+    projection.projection_mut(45., 800., 600., 0.1, 100.);
+    let view = Matrix4::look_at(&Vector3::from_i32(0, -5, 0), &Vector3::new(), &Vector3::new_z_up());
+    let viewport = Viewport::from_size(800., 600.);
+
+    let sphere = Sphere::new(&Vector3::new(), 1.);
+    let center = Ray::from_screen(400., 300., &viewport, &projection, &view).unwrap();
+    let hit = center.intersect_sphere(&sphere).unwrap();
+    assert!(hit.point.distance(&Vector3::from_i32(0, -1, 0)) < 1e-3);
+
+    let corner = Ray::from_screen(10., 10., &viewport, &projection, &view).unwrap();
+    assert!(corner.intersect_sphere(&sphere).is_none());
+}
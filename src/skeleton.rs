@@ -0,0 +1,415 @@
+//! Skeletal animation: a `Skeleton` of joints, a `Pose` of local joint transforms and the
+//! `SkinningPalette` of matrices uploaded to the vertex shader.
+//!
+//! Joints are addressed by index and every joint must come after its parent, which is the
+//! order exported by most tools (glTF, FBX). This allows evaluating a pose in a single pass
+//! without recursion. A skinning matrix maps a vertex from the bind pose (model space) to the
+//! posed model space: `world * inverse_bind`.
+//!
+//! # Example
+//! ```
+//! use js_linalg::{Matrix4, Quaternion, Transform, Vector3};
+//! use js_linalg::skeleton::{Pose, Skeleton, SkinningPalette};
+//!
+//! // an arm: shoulder at the origin, elbow 2 units along x
+//! let mut bind = Pose::new(2);
+//! bind.local_mut(1).translation = Vector3::from_i32(2, 0, 0);
+//! let skeleton = Skeleton::from_bind_pose(&[None, Some(0)], &bind);
+//!
+//! // bend the elbow by 90 degrees
+//! let mut pose = bind.clone();
+//! pose.local_mut(1).rotation = Quaternion::from_axis_angle(&Vector3::new_z_up(), std::f32::consts::FRAC_PI_2);
+//!
+//! let mut palette = SkinningPalette::new();
+//! palette.update(&skeleton, &pose);
+//! // the hand vertex at (3, 0, 0) in the bind pose moves to (2, 1, 0)
+//! let hand = &palette.matrices()[1] * Vector3::from_i32(3, 0, 0);
+//! assert!((hand.x - 2.).abs() < 1e-5 && (hand.y - 1.).abs() < 1e-5);
+//!
+//! // 2 joints * 16 floats, ready for `glUniformMatrix4fv`
+//! assert_eq!(palette.as_f32().len(), 32);
+//! ```
+
+use super::{Matrix4, Quaternion, Transform, Vector3};
+
+/// The local transforms of all joints of a skeleton relative to their parents.
+#[derive(Debug, Clone)]
+pub struct Pose {
+    locals: Vec<Transform>
+}
+
+impl Pose {
+
+    /// Creates a pose of `joint_count` identity transforms.
+    pub fn new(joint_count:usize) -> Pose {
+        Pose {locals: vec![Transform::new(); joint_count]}
+    }
+
+    /// Creates a pose from the provided local transforms, one per joint.
+    pub fn from_transforms(locals:Vec<Transform>) -> Pose {
+        Pose {locals}
+    }
+
+    /// The number of joints.
+    pub fn len(&self) -> usize {
+        self.locals.len()
+    }
+
+    /// Returns `true` if the pose has no joints.
+    pub fn is_empty(&self) -> bool {
+        self.locals.is_empty()
+    }
+
+    /// The local transform of joint `i`.
+    pub fn local(&self, i:usize) -> &Transform {
+        &self.locals[i]
+    }
+
+    /// Mutable access to the local transform of joint `i`.
+    pub fn local_mut(&mut self, i:usize) -> &mut Transform {
+        &mut self.locals[i]
+    }
+
+    /// The local transforms of all joints.
+    pub fn locals(&self) -> &[Transform] {
+        &self.locals
+    }
+
+    /// In-place blending towards `other` for all joints, see `Transform::lerp_mut`.
+    /// `weight = 0` keeps this pose, `weight = 1` results in `other`.
+    /// Panics if the poses have a different number of joints.
+    pub fn blend_mut(&mut self, other:&Pose, weight:f32) -> &mut Pose {
+        assert_same_len(self, other);
+        for (a, b) in self.locals.iter_mut().zip(other.locals.iter()) {
+            a.lerp_mut(b, weight);
+        }
+        self
+    }
+
+    /// In-place blending towards `other` with a separate weight per joint, e.g. to play a
+    /// waving animation on the upper body only. `weights` may be shorter than the pose, the
+    /// joints without a weight are kept. Panics if the poses have a different number of joints
+    /// or if there are more weights than joints.
+    pub fn blend_joints_mut(&mut self, other:&Pose, weights:&[f32]) -> &mut Pose {
+        assert_same_len(self, other);
+        assert!(weights.len() <= self.len(), "{} weights for {} joints", weights.len(), self.len());
+        for ((a, b), &w) in self.locals.iter_mut().zip(other.locals.iter()).zip(weights.iter()) {
+            a.lerp_mut(b, w);
+        }
+        self
+    }
+
+    /// Computes the additive pose that turns `reference` into `target`, to be layered on top of
+    /// other poses with `add_mut`. Per joint the translation and shear are differences, the
+    /// rotation is `reference⁻¹ * target` and the scale is the component-wise ratio. A zero
+    /// scale component in `reference` has no meaningful ratio and is left unchanged (ratio 1).
+    /// Panics if the poses have a different number of joints.
+    pub fn difference(reference:&Pose, target:&Pose) -> Pose {
+        assert_same_len(reference, target);
+        let locals = reference.locals.iter().zip(target.locals.iter()).map(|(r, t)| {
+            Transform {
+                translation: t.translation - r.translation,
+                rotation: r.rotation.inverse() * t.rotation,
+                scale: Vector3::from(scale_ratio(t.scale.x, r.scale.x), scale_ratio(t.scale.y, r.scale.y), scale_ratio(t.scale.z, r.scale.z)),
+                shear: t.shear - r.shear,
+            }
+        }).collect();
+        Pose {locals}
+    }
+
+    /// In-place layering of an additive pose (see `difference`) on top of this pose with the
+    /// provided weight. Applying a difference with weight `1` to its reference results in its target.
+    /// Panics if the poses have a different number of joints.
+    pub fn add_mut(&mut self, additive:&Pose, weight:f32) -> &mut Pose {
+        assert_same_len(self, additive);
+        let identity = Quaternion::new();
+        for (a, d) in self.locals.iter_mut().zip(additive.locals.iter()) {
+            a.translation += d.translation * weight;
+            a.rotation.mult_mut(&identity.slerp(&d.rotation, weight));
+            a.scale.x *= 1. + (d.scale.x - 1.) * weight;
+            a.scale.y *= 1. + (d.scale.y - 1.) * weight;
+            a.scale.z *= 1. + (d.scale.z - 1.) * weight;
+            a.shear += d.shear * weight;
+        }
+        self
+    }
+}
+
+fn assert_same_len(a:&Pose, b:&Pose) {
+    assert_eq!(a.len(), b.len(), "poses have {} and {} joints", a.len(), b.len());
+}
+
+fn scale_ratio(target:f32, reference:f32) -> f32 {
+    if reference != 0.0 { target / reference } else { 1.0 }
+}
+
+/// The joint hierarchy and inverse bind matrices of a skinned mesh, see the module documentation.
+#[derive(Debug, Clone, Default)]
+pub struct Skeleton {
+    parents: Vec<Option<usize>>,
+    inverse_binds: Vec<Matrix4>
+}
+
+impl Skeleton {
+
+    /// Creates a new skeleton without joints.
+    pub fn new() -> Skeleton {
+        Skeleton::default()
+    }
+
+    /// Creates a skeleton from the parent of each joint and the pose the mesh was bound in.
+    /// The inverse bind matrices are the inverted world matrices of the bind pose.
+    /// Panics if a parent does not come before its child or the pose has a different length.
+    pub fn from_bind_pose(parents:&[Option<usize>], bind:&Pose) -> Skeleton {
+        assert_eq!(parents.len(), bind.len(), "bind pose has {} joints, expected {}", bind.len(), parents.len());
+        let mut skeleton = Skeleton::new();
+        for &p in parents {
+            skeleton.add_joint(p, &Matrix4::new());
+        }
+
+        let mut worlds = Vec::new();
+        skeleton.world_matrices(bind, &mut worlds);
+        for (inverse_bind, world) in skeleton.inverse_binds.iter_mut().zip(worlds.iter()) {
+            inverse_bind.set_matrix4(world);
+            inverse_bind.inverse_affine_mut();
+        }
+        skeleton
+    }
+
+    /// Adds a joint below `parent`, or as a root for `None`, and returns its index.
+    /// Panics if `parent` is not the index of an existing joint.
+    pub fn add_joint(&mut self, parent:Option<usize>, inverse_bind:&Matrix4) -> usize {
+        let index = self.parents.len();
+        if let Some(p) = parent {
+            assert!(p < index, "invalid parent index {}", p);
+        }
+        self.parents.push(parent);
+        self.inverse_binds.push(Matrix4::from_matrix4(inverse_bind));
+        index
+    }
+
+    /// The number of joints.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Returns `true` if the skeleton has no joints.
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The parent of joint `i`, `None` for roots.
+    pub fn parent(&self, i:usize) -> Option<usize> {
+        self.parents[i]
+    }
+
+    /// The inverse bind matrix of joint `i`, mapping model space to the space of the joint
+    /// in the bind pose.
+    pub fn inverse_bind(&self, i:usize) -> &Matrix4 {
+        &self.inverse_binds[i]
+    }
+
+    /// Replaces the inverse bind matrix of joint `i`.
+    pub fn set_inverse_bind(&mut self, i:usize, m:&Matrix4) -> &mut Skeleton {
+        self.inverse_binds[i].set_matrix4(m);
+        self
+    }
+
+    /// Computes the model space matrix of every joint for the provided pose into `out`, which
+    /// is resized to the number of joints. Panics if the pose has a different number of joints.
+    pub fn world_matrices(&self, pose:&Pose, out:&mut Vec<Matrix4>) {
+        assert_eq!(pose.len(), self.len(), "pose has {} joints, expected {}", pose.len(), self.len());
+        out.resize(self.len(), Matrix4::new());
+        for i in 0..self.len() {
+            let local = pose.locals[i].to_matrix4();
+            match self.parents[i] {
+                Some(p) => {
+                    // parents come first, so their world matrix is already up to date
+                    let (done, rest) = out.split_at_mut(i);
+                    rest[0].set_matrix4(&done[p]);
+                    rest[0].mult_mut(&local);
+                },
+                None => out[i].set_matrix4(&local),
+            }
+        }
+    }
+}
+
+/// The skinning matrices of a posed skeleton in one contiguous buffer, reused between frames
+/// to avoid allocations.
+#[derive(Debug, Clone, Default)]
+pub struct SkinningPalette {
+    worlds: Vec<Matrix4>,
+    matrices: Vec<Matrix4>
+}
+
+impl SkinningPalette {
+
+    /// Creates an empty palette.
+    pub fn new() -> SkinningPalette {
+        SkinningPalette::default()
+    }
+
+    /// Evaluates the pose: computes the world matrix of every joint and the skinning matrices
+    /// `world * inverse_bind`. Panics if the pose does not fit the skeleton.
+    pub fn update(&mut self, skeleton:&Skeleton, pose:&Pose) -> &mut SkinningPalette {
+        skeleton.world_matrices(pose, &mut self.worlds);
+        self.matrices.resize(skeleton.len(), Matrix4::new());
+        for ((m, world), inverse_bind) in self.matrices.iter_mut().zip(self.worlds.iter()).zip(skeleton.inverse_binds.iter()) {
+            m.set_matrix4(world);
+            m.mult_mut(inverse_bind);
+        }
+        self
+    }
+
+    /// The number of joints of the last update.
+    pub fn len(&self) -> usize {
+        self.matrices.len()
+    }
+
+    /// Returns `true` if nothing has been evaluated yet.
+    pub fn is_empty(&self) -> bool {
+        self.matrices.is_empty()
+    }
+
+    /// The model space matrices of the joints, e.g. to attach a weapon to a hand.
+    pub fn world_matrices(&self) -> &[Matrix4] {
+        &self.worlds
+    }
+
+    /// The skinning matrices, one per joint.
+    pub fn matrices(&self) -> &[Matrix4] {
+        &self.matrices
+    }
+
+    /// The skinning matrices as one flat slice of `f32`'s for uploading, see `Matrix4::slice_as_f32`.
+    pub fn as_f32(&self) -> &[f32] {
+        Matrix4::slice_as_f32(&self.matrices)
+    }
+}
+
+#[cfg(test)]
+fn assert_vector3_near(a:&Vector3, b:&Vector3) {
+    assert!(a.distance(b) < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[cfg(test)]
+fn chain_bind_pose() -> (Skeleton, Pose) {
+    // a root with a spine of two joints and a branch off the first one
+    let mut bind = Pose::new(4);
+    bind.local_mut(0).translation = Vector3::from_i32(0, 1, 0);
+    bind.local_mut(1).translation = Vector3::from_i32(0, 2, 0);
+    bind.local_mut(2).translation = Vector3::from_i32(0, 2, 0);
+    bind.local_mut(3).translation = Vector3::from_i32(1, 0, 0);
+    bind.local_mut(3).rotation = Quaternion::from_axis_angle(&Vector3::new_z_up(), -1.);
+    (Skeleton::from_bind_pose(&[None, Some(0), Some(1), Some(1)], &bind), bind)
+}
+
+#[test]
+fn test_bind_pose_gives_identity_palette() {
+    let (skeleton, bind) = chain_bind_pose();
+    let mut palette = SkinningPalette::new();
+    palette.update(&skeleton, &bind);
+    assert_eq!(palette.len(), 4);
+    for m in palette.matrices() {
+        for (a, b) in m.as_array().iter().zip(Matrix4::new().as_array().iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+    assert_vector3_near(&(&palette.world_matrices()[2] * Vector3::new()), &Vector3::from_i32(0, 5, 0));
+    assert_eq!(palette.as_f32()[16 * 3 + 15], 1.);
+}
+
+#[test]
+fn test_palette_follows_parents() {
+    let (skeleton, bind) = chain_bind_pose();
+    let mut pose = bind.clone();
+    pose.local_mut(0).translation.x = 3.;
+    let turn = Quaternion::from_axis_angle(&Vector3::from_i32(1, 0, 0), std::f32::consts::FRAC_PI_2);
+    pose.local_mut(1).rotation = turn;
+
+    let mut palette = SkinningPalette::new();
+    palette.update(&skeleton, &pose);
+    // a vertex at the tip of the spine, 2 above joint 2 in the bind pose
+    let v = &palette.matrices()[2] * Vector3::from_i32(0, 7, 0);
+    assert_vector3_near(&v, &Vector3::from_i32(3, 3, 4));
+    // the root joint moves without rotating
+    assert_vector3_near(&(&palette.matrices()[0] * Vector3::from_i32(0, 1, 0)), &Vector3::from_i32(3, 1, 0));
+}
+
+#[test]
+fn test_pose_blending() {
+    let (_, bind) = chain_bind_pose();
+    let mut target = bind.clone();
+    target.local_mut(2).translation.y = 4.;
+    target.local_mut(1).rotation = Quaternion::from_axis_angle(&Vector3::new_z_up(), 1.);
+
+    let mut half = bind.clone();
+    half.blend_mut(&target, 0.5);
+    assert_eq!(half.local(2).translation.y, 3.);
+    let expected = Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.5);
+    assert!((half.local(1).rotation.dot(&expected) - 1.).abs() < 1e-5);
+
+    let mut masked = bind.clone();
+    masked.blend_joints_mut(&target, &[0., 0., 1.]);
+    assert_eq!(masked.local(2).translation.y, 4.);
+    assert!((masked.local(1).rotation.w - 1.).abs() < 1e-6);
+}
+
+#[test]
+fn test_additive_pose() {
+    let (_, bind) = chain_bind_pose();
+    let mut nod = bind.clone();
+    nod.local_mut(2).rotation = Quaternion::from_axis_angle(&Vector3::from_i32(1, 0, 0), 0.4);
+    nod.local_mut(2).scale = Vector3::from(1., 2., 1.);
+    nod.local_mut(0).translation.z = 1.;
+    let additive = Pose::difference(&bind, &nod);
+
+    // the difference applied to its reference gives the target
+    let mut p = bind.clone();
+    p.add_mut(&additive, 1.);
+    for (a, b) in p.locals().iter().zip(nod.locals().iter()) {
+        assert_vector3_near(&a.translation, &b.translation);
+        assert_vector3_near(&a.scale, &b.scale);
+        assert!((a.rotation.dot(&b.rotation) - 1.).abs() < 1e-5);
+    }
+
+    // layered on another pose with half the weight
+    let mut walk = bind.clone();
+    walk.local_mut(2).rotation = Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.3);
+    walk.add_mut(&additive, 0.5);
+    let expected = Quaternion::from_axis_angle(&Vector3::new_z_up(), 0.3) * Quaternion::from_axis_angle(&Vector3::from_i32(1, 0, 0), 0.2);
+    assert!((walk.local(2).rotation.dot(&expected) - 1.).abs() < 1e-5);
+    assert!((walk.local(2).scale.y - 1.5).abs() < 1e-6);
+    assert!((walk.local(0).translation.z - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_additive_pose_zero_reference_scale() {
+    let (_, bind) = chain_bind_pose();
+    let mut hidden = bind.clone();
+    hidden.local_mut(1).scale = Vector3::from(0., 1., 1.);
+    let mut target = hidden.clone();
+    target.local_mut(1).scale = Vector3::from(0., 3., 1.);
+
+    let additive = Pose::difference(&hidden, &target);
+    assert_vector3_near(&additive.local(1).scale, &Vector3::from(1., 3., 1.));
+
+    let mut p = hidden.clone();
+    p.add_mut(&additive, 1.);
+    assert_vector3_near(&p.local(1).scale, &target.local(1).scale);
+}
+
+#[test]
+#[should_panic]
+fn test_blend_mismatched_poses() {
+    let (_, bind) = chain_bind_pose();
+    Pose::new(2).blend_mut(&bind, 0.5);
+}
+
+#[test]
+#[should_panic]
+fn test_parent_must_come_first() {
+    let mut skeleton = Skeleton::new();
+    skeleton.add_joint(Some(0), &Matrix4::new());
+}
@@ -0,0 +1,530 @@
+//! Cubic splines through `Vector3` control points with arc-length parameterization, e.g. for
+//! camera paths.
+//!
+//! A `Spline` consists of cubic segments. It is evaluated either by the curve parameter `u`,
+//! which runs from `0` to `segment_count()` (segment `i` covers `[i, i + 1]`), or by the
+//! distance travelled along the curve. The speed along `u` varies with the spacing of the
+//! control points, so animations moving at constant speed should use the distance based
+//! functions (`point_at_distance`, `sample_uniform`, ...). Distances are looked up in a table
+//! of accumulated chord lengths that is built when the spline is created.
+//!
+//! # Example
+//! ```
+//! use js_linalg::{Matrix4, Vector3};
+//! use js_linalg::spline::{Spline, SplineKind};
+//!
+//! let path = Spline::new(SplineKind::CatmullRom, vec![
+//!     Vector3::from_i32(0, 0, 0),
+//!     Vector3::from_i32(10, 0, 0),
+//!     Vector3::from_i32(10, 10, 0),
+//!     Vector3::from_i32(0, 10, 2),
+//! ]);
+//!
+//! // move the camera along the path at 2 units per second, keeping +z as up
+//! let time = 1.5;
+//! let eye = path.point_at_distance(2. * time);
+//! assert!((path.distance_at_parameter(path.closest_parameter(&eye)) - 3.).abs() < 1e-2);
+//!
+//! // orientations for 64 stops along the path, inverted to view matrices
+//! let frames = path.rotation_minimizing_frames(64, &Vector3::new_z_up()).unwrap();
+//! let mut view = Matrix4::from_matrix4(&frames[10]);
+//! view.inverse_rigid_mut();
+//! ```
+
+use super::{Matrix4, Vector3, Error};
+use super::interpolation::{bezier_weights, catmull_rom_weights};
+
+/// The type of the segments of a `Spline`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SplineKind {
+    /// Passes through all control points. Needs at least 2 points, `n` points give `n - 1`
+    /// segments. The tangents at both ends point to the neighbouring control point.
+    CatmullRom,
+
+    /// Uniform cubic B-spline approximating the control points with a curvature continuous
+    /// curve, which usually does not pass through the points. Needs at least 4 points,
+    /// `n` points give `n - 3` segments.
+    BSpline,
+
+    /// A chain of cubic Bézier curves where the last point of one segment is the first point
+    /// of the next one. Needs `3k + 1` points for `k` segments, e.g. 4, 7, 10, ...
+    Bezier
+}
+
+/// The number of arc-length table entries per segment used by `Spline::new`.
+pub const DEFAULT_RESOLUTION: usize = 32;
+
+/// A cubic spline with an arc-length table, see the module documentation.
+#[derive(Debug, Clone)]
+pub struct Spline {
+    kind: SplineKind,
+    points: Vec<Vector3>,
+    resolution: usize,
+    // accumulated chord lengths at u = i / resolution
+    lengths: Vec<f32>
+}
+
+/// The cubic basis weights of one segment for the `derivative`-th derivative (0 to 2). The
+/// Catmull-Rom and Bézier weights are the ones of the `interpolation` curves.
+fn basis(kind: SplineKind, t: f32, derivative: usize) -> [f32; 4] {
+    match kind {
+        SplineKind::CatmullRom => catmull_rom_weights(t, derivative),
+        SplineKind::BSpline => {
+            let t2 = t * t;
+            let t3 = t2 * t;
+            let u = 1. - t;
+            match derivative {
+                0 => [u * u * u / 6., (3. * t3 - 6. * t2 + 4.) / 6., (-3. * t3 + 3. * t2 + 3. * t + 1.) / 6., t3 / 6.],
+                1 => [-0.5 * u * u, 1.5 * t2 - 2. * t, -1.5 * t2 + t + 0.5, 0.5 * t2],
+                _ => [u, 3. * t - 2., -3. * t + 1., t]
+            }
+        },
+        SplineKind::Bezier => bezier_weights(t, derivative)
+    }
+}
+
+impl Spline {
+
+    /// Creates a spline of the provided kind with `DEFAULT_RESOLUTION` arc-length table
+    /// entries per segment.
+    ///
+    /// # Panics
+    /// Panics if the number of points does not fit the kind, see `SplineKind` and `try_new`.
+    pub fn new(kind: SplineKind, points: Vec<Vector3>) -> Spline {
+        let count = points.len();
+        match Spline::try_new(kind, points) {
+            Ok(spline) => spline,
+            Err(_) => panic!("{:?} spline cannot be built from {} points", kind, count),
+        }
+    }
+
+    /// Creates a spline like `new`, but returns `Error::InvalidPointCount` instead of
+    /// panicking if the number of points does not fit the kind.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Error, Vector3};
+    /// use js_linalg::spline::{Spline, SplineKind};
+    /// let e = Spline::try_new(SplineKind::Bezier, vec![Vector3::new(); 5]).unwrap_err();
+    /// assert_eq!(e, Error::InvalidPointCount {count: 5});
+    /// assert!(Spline::try_new(SplineKind::Bezier, vec![Vector3::new(); 7]).is_ok());
+    /// ```
+    pub fn try_new(kind: SplineKind, points: Vec<Vector3>) -> Result<Spline, Error> {
+        let valid = match kind {
+            SplineKind::CatmullRom => points.len() >= 2,
+            SplineKind::BSpline => points.len() >= 4,
+            SplineKind::Bezier => points.len() >= 4 && points.len() % 3 == 1,
+        };
+        if !valid {
+            return Err(Error::InvalidPointCount {count: points.len() as u32});
+        }
+
+        let mut spline = Spline {kind, points, resolution: DEFAULT_RESOLUTION, lengths: Vec::new()};
+        spline.update_lengths();
+        Ok(spline)
+    }
+
+    /// The type of the segments.
+    pub fn kind(&self) -> SplineKind {
+        self.kind
+    }
+
+    /// The control points.
+    pub fn points(&self) -> &[Vector3] {
+        &self.points
+    }
+
+    /// Moves a single control point and rebuilds the arc-length table.
+    ///
+    /// # Panics
+    /// Panics if `i` is not the index of a control point, see `try_set_point`.
+    pub fn set_point(&mut self, i: usize, p: &Vector3) -> &mut Spline {
+        let len = self.points.len();
+        match self.try_set_point(i, p) {
+            Ok(spline) => spline,
+            Err(_) => panic!("spline has control points 0 to {}, not {}", len - 1, i),
+        }
+    }
+
+    /// Moves a single control point like `set_point`, but returns `Error::IndexOutOfRange`
+    /// instead of panicking if `i` is not the index of a control point.
+    pub fn try_set_point(&mut self, i: usize, p: &Vector3) -> Result<&mut Spline, Error> {
+        if i >= self.points.len() {
+            return Err(Error::IndexOutOfRange {index: i as u32, len: self.points.len() as u32});
+        }
+        self.points[i] = *p;
+        self.update_lengths();
+        Ok(self)
+    }
+
+    /// The number of arc-length table entries per segment.
+    pub fn resolution(&self) -> usize {
+        self.resolution
+    }
+
+    /// Changes the number of arc-length table entries per segment (at least 1) and rebuilds
+    /// the table. More entries give more accurate distances for strongly bent segments.
+    pub fn set_resolution(&mut self, resolution: usize) -> &mut Spline {
+        self.resolution = resolution.max(1);
+        self.update_lengths();
+        self
+    }
+
+    /// The number of cubic segments, which is also the largest curve parameter `u`.
+    pub fn segment_count(&self) -> usize {
+        match self.kind {
+            SplineKind::CatmullRom => self.points.len() - 1,
+            SplineKind::BSpline => self.points.len() - 3,
+            SplineKind::Bezier => (self.points.len() - 1) / 3,
+        }
+    }
+
+    /// The (approximated) length of the whole curve.
+    pub fn length(&self) -> f32 {
+        self.lengths[self.lengths.len() - 1]
+    }
+
+    fn update_lengths(&mut self) {
+        let n = self.segment_count() * self.resolution;
+        self.lengths.clear();
+        self.lengths.push(0.);
+        let mut last = self.point(0.);
+        for i in 1..=n {
+            let p = self.point(i as f32 / self.resolution as f32);
+            let l = self.lengths[i - 1] + p.distance(&last);
+            self.lengths.push(l);
+            last = p;
+        }
+    }
+
+    /// The four control points of segment `i`.
+    fn segment(&self, i: usize) -> [Vector3; 4] {
+        let p = &self.points;
+        match self.kind {
+            SplineKind::CatmullRom => {
+                // phantom points mirrored at the ends
+                let before = if i == 0 { p[0] * 2. - p[1] } else { p[i - 1] };
+                let after = if i + 2 < p.len() { p[i + 2] } else { p[i + 1] * 2. - p[i] };
+                [before, p[i], p[i + 1], after]
+            },
+            SplineKind::BSpline => [p[i], p[i + 1], p[i + 2], p[i + 3]],
+            SplineKind::Bezier => [p[3 * i], p[3 * i + 1], p[3 * i + 2], p[3 * i + 3]],
+        }
+    }
+
+    /// Evaluates the `derivative`-th derivative (0 to 2) at curve parameter `u`.
+    fn evaluate(&self, u: f32, derivative: usize) -> Vector3 {
+        let last = self.segment_count() - 1;
+        let u = u.clamp(0., self.segment_count() as f32);
+        let i = (u.floor() as usize).min(last);
+        let w = basis(self.kind, u - i as f32, derivative);
+        let p = self.segment(i);
+        p[0] * w[0] + p[1] * w[1] + p[2] * w[2] + p[3] * w[3]
+    }
+
+    /// The point at curve parameter `u`, which is clamped to `[0, segment_count()]`.
+    pub fn point(&self, u: f32) -> Vector3 {
+        self.evaluate(u, 0)
+    }
+
+    /// The derivative with respect to the curve parameter `u`, pointing in the direction of
+    /// travel. Its length is the speed along `u`.
+    pub fn derivative(&self, u: f32) -> Vector3 {
+        self.evaluate(u, 1)
+    }
+
+    /// The second derivative with respect to the curve parameter `u`.
+    pub fn second_derivative(&self, u: f32) -> Vector3 {
+        self.evaluate(u, 2)
+    }
+
+    /// The curve parameter `u` at the provided distance from the start of the curve. The
+    /// distance is clamped to `[0, length()]`.
+    pub fn parameter_at_distance(&self, distance: f32) -> f32 {
+        let distance = distance.clamp(0., self.length());
+        let i = self.lengths.partition_point(|&l| l < distance).max(1);
+        let (l0, l1) = (self.lengths[i - 1], self.lengths[i]);
+        let f = if l1 > l0 { (distance - l0) / (l1 - l0) } else { 0. };
+        (i - 1) as f32 / self.resolution as f32 + f / self.resolution as f32
+    }
+
+    /// The distance from the start of the curve to curve parameter `u`.
+    pub fn distance_at_parameter(&self, u: f32) -> f32 {
+        let x = u.clamp(0., self.segment_count() as f32) * self.resolution as f32;
+        let i = (x.floor() as usize).min(self.lengths.len() - 2);
+        self.lengths[i] + (self.lengths[i + 1] - self.lengths[i]) * (x - i as f32)
+    }
+
+    /// The point at the provided distance from the start of the curve. Increasing the
+    /// distance linearly moves along the curve at constant speed.
+    pub fn point_at_distance(&self, distance: f32) -> Vector3 {
+        self.point(self.parameter_at_distance(distance))
+    }
+
+    /// Returns `count` points spaced at equal distances along the curve, including the first
+    /// and the last point of the curve.
+    pub fn sample_uniform(&self, count: usize) -> Vec<Vector3> {
+        let step = if count > 1 { self.length() / (count - 1) as f32 } else { 0. };
+        (0..count).map(|i| self.point_at_distance(i as f32 * step)).collect()
+    }
+
+    /// The curve parameter `u` of the point on the curve closest to `p`. The closest entry of
+    /// the arc-length table is refined with a few Newton iterations.
+    pub fn closest_parameter(&self, p: &Vector3) -> f32 {
+        let n = self.lengths.len();
+        let mut best = 0;
+        let mut best_distance = f32::INFINITY;
+        for i in 0..n {
+            let d = self.point(i as f32 / self.resolution as f32).distance_squared(p);
+            if d < best_distance {
+                best = i;
+                best_distance = d;
+            }
+        }
+
+        // minimize |C(u) - p|² by finding the root of (C(u) - p) · C'(u)
+        let max = self.segment_count() as f32;
+        let mut u = best as f32 / self.resolution as f32;
+        for _ in 0..8 {
+            let d = self.point(u) - p;
+            let d1 = self.derivative(u);
+            let f = d.dot(&d1);
+            let df = d1.dot(&d1) + d.dot(&self.second_derivative(u));
+            if df.abs() < f32::EPSILON {
+                break;
+            }
+            let next = (u - f / df).clamp(0., max);
+            if (next - u).abs() < 1e-6 {
+                u = next;
+                break;
+            }
+            u = next;
+        }
+
+        // Newton may run off towards a worse local minimum
+        if self.point(u).distance_squared(p) > best_distance {
+            return best as f32 / self.resolution as f32;
+        }
+        u
+    }
+
+    /// The point on the curve closest to `p`, see `closest_parameter`.
+    pub fn closest_point(&self, p: &Vector3) -> Vector3 {
+        self.point(self.closest_parameter(p))
+    }
+
+    /// The Frenet frame at curve parameter `u` as camera-to-world matrix: the camera looks
+    /// along the tangent (`-z`), `y` is the principal normal pointing to the center of
+    /// curvature and the translation is the point on the curve. The inverse
+    /// (`inverse_rigid_mut`) is a view matrix like the one of `look_at_mut`.
+    ///
+    /// Frenet frames flip at inflection points and are undefined on straight parts, where
+    /// `Error::ZeroLength` is returned. Use `rotation_minimizing_frames` for cameras.
+    pub fn frenet_frame(&self, u: f32) -> Result<Matrix4, Error> {
+        let d1 = self.derivative(u);
+        let tangent = d1.try_normalize()?;
+        let binormal = d1.cross(&self.second_derivative(u)).try_normalize()?;
+        let normal = binormal.cross(&tangent);
+        Ok(frame(&self.point(u), &tangent, &normal))
+    }
+
+    /// Returns `count` frames at equal distances along the curve (see `sample_uniform`) that
+    /// rotate as little as possible around the tangent, computed with the double reflection
+    /// method of Wang et al. The `y` axis of the first frame is `up` made perpendicular to the
+    /// tangent; the frames are camera-to-world matrices like in `frenet_frame`.
+    ///
+    /// A zero derivative at the start, e.g. of a Bézier segment whose first two control points
+    /// coincide, is replaced by the direction of the chord to the first entry of the arc-length
+    /// table. Returns `Error::ZeroLength` if that chord has zero length too, i.e. the curve does
+    /// not move away from its start, and `Error::ParallelUpVector` if `up` is parallel to the
+    /// tangent at the start.
+    pub fn rotation_minimizing_frames(&self, count: usize, up: &Vector3) -> Result<Vec<Matrix4>, Error> {
+        let mut frames = Vec::with_capacity(count);
+        if count == 0 {
+            return Ok(frames);
+        }
+        let step = if count > 1 { self.length() / (count - 1) as f32 } else { 0. };
+
+        let u = self.parameter_at_distance(0.);
+        let mut x = self.point(u);
+        let mut t = self.derivative(u).try_normalize()
+            .or_else(|_| (self.point(1. / self.resolution as f32) - x).try_normalize())?;
+        let mut r = (*up - t * up.dot(&t)).try_normalize().map_err(|_| Error::ParallelUpVector)?;
+        frames.push(frame(&x, &t, &r));
+
+        for i in 1..count {
+            let u = self.parameter_at_distance(i as f32 * step);
+            let x_next = self.point(u);
+            let t_next = self.derivative(u).try_normalize().unwrap_or(t);
+
+            // reflect at the bisecting plane of x and x_next, then at the plane between the tangents
+            let v1 = x_next - x;
+            let c1 = v1.dot(&v1);
+            let (r_l, t_l) = if c1 > f32::EPSILON {
+                (r - v1 * (2. / c1 * v1.dot(&r)), t - v1 * (2. / c1 * v1.dot(&t)))
+            } else {
+                (r, t)
+            };
+            let v2 = t_next - t_l;
+            let c2 = v2.dot(&v2);
+            let r_next = if c2 > f32::EPSILON { r_l - v2 * (2. / c2 * v2.dot(&r_l)) } else { r_l };
+
+            x = x_next;
+            t = t_next;
+            // remove the drift away from being perpendicular to the tangent
+            r = (r_next - t * r_next.dot(&t)).try_normalize().unwrap_or(r_next);
+            frames.push(frame(&x, &t, &r));
+        }
+        Ok(frames)
+    }
+}
+
+/// A camera-to-world matrix at `position` looking along `forward` with the provided `up`;
+/// both unit length and perpendicular.
+fn frame(position: &Vector3, forward: &Vector3, up: &Vector3) -> Matrix4 {
+    let right = forward.cross(up);
+    let mut m = Matrix4::new();
+    m.set_column(0, right.x, right.y, right.z, 0.)
+     .set_column(1, up.x, up.y, up.z, 0.)
+     .set_column(2, -forward.x, -forward.y, -forward.z, 0.)
+     .set_column(3, position.x, position.y, position.z, 1.);
+    m
+}
+
+#[cfg(test)]
+fn assert_near(a: &Vector3, b: &Vector3, eps: f32) {
+    assert!(a.distance(b) < eps, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn test_spline_kinds() {
+    use super::interpolation;
+
+    let p = vec![Vector3::from_i32(0, 0, 0), Vector3::from_i32(1, 2, 0), Vector3::from_i32(3, 2, 1),
+                 Vector3::from_i32(4, 0, 1), Vector3::from_i32(5, -1, 0), Vector3::from_i32(6, 0, 0),
+                 Vector3::from_i32(7, 1, 2)];
+
+    let cr = Spline::new(SplineKind::CatmullRom, p.clone());
+    assert_eq!(cr.segment_count(), 6);
+    assert_near(&cr.point(2.), &p[2], 1e-5);
+    assert_near(&cr.point(6.), &p[6], 1e-5);
+    assert_near(&cr.point(2.5), &interpolation::catmull_rom(&p[1], &p[2], &p[3], &p[4], 0.5), 1e-5);
+    assert_near(&cr.derivative(0.), &(p[1] - p[0]), 1e-5);
+
+    let bezier = Spline::new(SplineKind::Bezier, p.clone());
+    assert_eq!(bezier.segment_count(), 2);
+    assert_near(&bezier.point(1.), &p[3], 1e-5);
+    assert_near(&bezier.derivative(1.5), &interpolation::bezier_derivative(&p[3], &p[4], &p[5], &p[6], 0.5), 1e-5);
+
+    // B-splines are continuous in position, velocity and acceleration across segments
+    let b = Spline::new(SplineKind::BSpline, p.clone());
+    assert_eq!(b.segment_count(), 4);
+    let h = 1e-3;
+    assert_near(&b.point(1. - h), &b.point(1. + h), 1e-2);
+    assert_near(&b.derivative(1. - h), &b.derivative(1. + h), 1e-2);
+    assert_near(&b.second_derivative(1. - h), &b.second_derivative(1. + h), 1e-1);
+}
+
+#[test]
+#[should_panic]
+fn test_bezier_point_count() {
+    Spline::new(SplineKind::Bezier, vec![Vector3::new(); 5]);
+}
+
+#[test]
+fn test_try_new_and_try_set_point() {
+    assert_eq!(Spline::try_new(SplineKind::CatmullRom, vec![Vector3::new()]).unwrap_err(), Error::InvalidPointCount {count: 1});
+    assert_eq!(Spline::try_new(SplineKind::BSpline, vec![Vector3::new(); 3]).unwrap_err(), Error::InvalidPointCount {count: 3});
+
+    let mut s = Spline::try_new(SplineKind::CatmullRom, vec![Vector3::new(), Vector3::from_i32(2, 0, 0)]).unwrap();
+    assert_eq!(s.try_set_point(2, &Vector3::new()).unwrap_err(), Error::IndexOutOfRange {index: 2, len: 2});
+    assert_eq!(s.length(), 2.);
+    s.try_set_point(1, &Vector3::from_i32(0, 5, 0)).unwrap();
+    assert!((s.length() - 5.).abs() < 1e-5);
+}
+
+#[test]
+fn test_arc_length() {
+    // a straight line with unevenly spaced points has constant speed by distance only
+    let s = Spline::new(SplineKind::CatmullRom, vec![
+        Vector3::from_i32(0, 0, 0), Vector3::from_i32(4, 0, 0), Vector3::from_i32(10, 0, 0)]);
+    assert!((s.length() - 10.).abs() < 1e-3);
+    assert!((s.point_at_distance(5.).x - 5.).abs() < 1e-2);
+    assert!((s.distance_at_parameter(s.parameter_at_distance(7.)) - 7.).abs() < 1e-3);
+
+    let samples = s.sample_uniform(11);
+    for (i, p) in samples.iter().enumerate() {
+        assert!((p.x - i as f32).abs() < 2e-2, "{} != {}", p.x, i);
+    }
+
+    // quarter circle from a bezier curve
+    let k = 0.5523;
+    let mut arc = Spline::new(SplineKind::Bezier, vec![
+        Vector3::from_i32(1, 0, 0), Vector3::from(1., k, 0.), Vector3::from(k, 1., 0.), Vector3::from_i32(0, 1, 0)]);
+    arc.set_resolution(128);
+    assert!((arc.length() - std::f32::consts::FRAC_PI_2).abs() < 1e-3);
+}
+
+#[test]
+fn test_closest_point() {
+    let s = Spline::new(SplineKind::CatmullRom, vec![
+        Vector3::from_i32(0, 0, 0), Vector3::from_i32(4, 2, 0), Vector3::from_i32(8, 0, 0), Vector3::from_i32(12, 3, 0)]);
+
+    for &u in &[0.3f32, 1.2, 2.7] {
+        let p = s.point(u);
+        // a point offset along the curve normal has its closest point at u
+        let d = s.derivative(u);
+        let offset = Vector3::from(-d.y, d.x, 0.).normalize() * 0.25;
+        assert!((s.closest_parameter(&(p + offset)) - u).abs() < 1e-3);
+    }
+    assert_near(&s.closest_point(&Vector3::from_i32(-5, -5, 0)), &Vector3::new(), 1e-5);
+}
+
+#[test]
+fn test_frames() {
+    // a circle-like B-spline in the xy plane
+    let s = Spline::new(SplineKind::BSpline, vec![
+        Vector3::from_i32(1, 0, 0), Vector3::from_i32(0, 1, 0), Vector3::from_i32(-1, 0, 0),
+        Vector3::from_i32(0, -1, 0), Vector3::from_i32(1, 0, 0), Vector3::from_i32(0, 1, 0)]);
+
+    let f = s.frenet_frame(1.).unwrap();
+    assert!((f.determinant() - 1.).abs() < 1e-5);
+    // the normal points inwards, the binormal along z
+    let p = s.point(1.);
+    assert!(Vector3::from(f.m_1_0, f.m_1_1, f.m_1_2).dot(&p) < 0.);
+    assert!((f.m_0_2.abs() - 1.).abs() < 1e-5);
+
+    let straight = Spline::new(SplineKind::CatmullRom, vec![Vector3::new(), Vector3::from_i32(1, 0, 0)]);
+    assert_eq!(straight.frenet_frame(0.5).unwrap_err(), Error::ZeroLength);
+
+    let frames = s.rotation_minimizing_frames(32, &Vector3::new_z_up()).unwrap();
+    assert_eq!(frames.len(), 32);
+    for f in frames.iter() {
+        // the planar curve keeps the up vector
+        assert!((f.m_1_2 - 1.).abs() < 1e-3);
+        assert!((f.determinant() - 1.).abs() < 1e-3);
+
+        // the view matrix of the frame looks along the curve
+        let mut view = Matrix4::from_matrix4(f);
+        view.inverse_rigid_mut();
+        let eye = Vector3::from(f.m_3_0, f.m_3_1, f.m_3_2);
+        let ahead = eye - Vector3::from(f.m_2_0, f.m_2_1, f.m_2_2);
+        let v = &view * ahead;
+        assert!((v.z + 1.).abs() < 1e-4);
+    }
+
+    let frames = straight.rotation_minimizing_frames(4, &Vector3::from_i32(0, 1, 1)).unwrap();
+    assert!((frames[3].m_1_1 - frames[0].m_1_1).abs() < 1e-5);
+    assert_eq!(straight.rotation_minimizing_frames(2, &Vector3::from_i32(2, 0, 0)).unwrap_err(), Error::ParallelUpVector);
+
+    // the first two control points coincide, so the curve starts with zero velocity along +x
+    let bezier = Spline::new(SplineKind::Bezier, vec![
+        Vector3::new(), Vector3::new(), Vector3::from_i32(2, 1, 0), Vector3::from_i32(3, 1, 0)]);
+    assert!(bezier.derivative(0.).length() < 1e-6);
+    let frames = bezier.rotation_minimizing_frames(8, &Vector3::new_z_up()).unwrap();
+    let forward = Vector3::from(-frames[0].m_2_0, -frames[0].m_2_1, -frames[0].m_2_2);
+    assert!(forward.dot(&(bezier.point(0.1) - bezier.point(0.)).normalize()) > 0.99);
+
+    let point = Spline::new(SplineKind::CatmullRom, vec![Vector3::new(), Vector3::new()]);
+    assert_eq!(point.rotation_minimizing_frames(2, &Vector3::new_z_up()).unwrap_err(), Error::ZeroLength);
+}
@@ -0,0 +1,121 @@
+use super::{Transform, Matrix4, Quaternion, Vector3};
+
+impl Transform {
+
+    /// Creates a new identity `Transform`: no translation, no rotation, unit scale and no shear.
+    pub fn new() -> Transform {
+        Transform {
+            translation: Vector3::new(),
+            rotation: Quaternion::new(),
+            scale: Vector3::from(1.0, 1.0, 1.0),
+            shear: Vector3::new(),
+        }
+    }
+
+    /// Creates a new `Transform` from translation, rotation and scale without shear.
+    pub fn from(translation:&Vector3, rotation:&Quaternion, scale:&Vector3) -> Transform {
+        Transform {
+            translation: *translation,
+            rotation: *rotation,
+            scale: *scale,
+            shear: Vector3::new(),
+        }
+    }
+
+    /// In-place interpolation towards `t`: translation, scale and shear are interpolated
+    /// linearly, the rotation with `Quaternion::slerp`. `alpha = 0` keeps this transform,
+    /// `alpha = 1` results in `t`.
+    pub fn lerp_mut(&mut self, t:&Transform, alpha:f32) -> &mut Transform {
+        self.translation.lerp_mut(&t.translation, alpha);
+        self.rotation = self.rotation.slerp(&t.rotation, alpha);
+        self.scale.lerp_mut(&t.scale, alpha);
+        self.shear.lerp_mut(&t.shear, alpha);
+        self
+    }
+
+    /// Interpolation between this transform and `t`, returned as new `Transform`. See `lerp_mut`.
+    pub fn lerp(&self, t:&Transform, alpha:f32) -> Transform {
+        let mut r = *self;
+        r.lerp_mut(t, alpha);
+        r
+    }
+
+    /// Composes the parts into a new `Matrix4`. See `Matrix4::compose_mut`.
+    pub fn to_matrix4(&self) -> Matrix4 {
+        let mut m = Matrix4::new();
+        m.compose_mut(self);
+        m
+    }
+}
+
+impl Default for Transform {
+    /// Same as `Transform::new()`.
+    fn default() -> Transform {
+        Transform::new()
+    }
+}
+
+#[cfg(test)]
+fn assert_transform_round_trip(m:&Matrix4) {
+    let t = m.decompose().unwrap();
+    let c = t.to_matrix4();
+    for (a, b) in c.as_array().iter().zip(m.as_array().iter()) {
+        assert!((a - b).abs() < 1e-4, "{:?} != {:?}", c, m);
+    }
+}
+
+#[test]
+fn test_decompose_trs() {
+    let axis = Vector3::from(1., -2., 0.5).normalize();
+    let mut m = Matrix4::new();
+    m.translation_mut(1., 2., 3.);
+    let mut r = Matrix4::new();
+    r.rotation_mut(0.8, &axis);
+    m.mult_mut(&r);
+    let mut s = Matrix4::new();
+    s.scale_mut(2., 3., 0.5);
+    m.mult_mut(&s);
+
+    let t = m.decompose().unwrap();
+    assert!((t.translation.z - 3.).abs() < 1e-5);
+    assert!((t.scale.x - 2.).abs() < 1e-5);
+    assert!((t.scale.y - 3.).abs() < 1e-5);
+    assert!((t.scale.z - 0.5).abs() < 1e-5);
+    assert!(t.shear.length() < 1e-5);
+    assert!((t.rotation.dot(&Quaternion::from_axis_angle(&axis, 0.8)).abs() - 1.).abs() < 1e-5);
+
+    assert_transform_round_trip(&m);
+}
+
+#[test]
+fn test_decompose_negative_scale_and_shear() {
+    let mut m = Matrix4::new();
+    m.scale_mut(-2., 1., 1.);
+    let t = m.decompose().unwrap();
+    assert!((t.scale.x + 2.).abs() < 1e-6);
+    assert!((t.scale.y - 1.).abs() < 1e-6);
+    assert!((t.rotation.w.abs() - 1.).abs() < 1e-6);
+
+    // mirrored, sheared and rotated
+    let mut m = Matrix4::new();
+    m.rotation_mut(2.5, &Vector3::from(0., 1., 1.).normalize());
+    let mut h = Matrix4::new();
+    h.m_1_0 = 0.3;
+    h.m_2_0 = -0.2;
+    h.m_2_1 = 0.7;
+    m.mult_mut(&h);
+    let mut s = Matrix4::new();
+    s.scale_mut(1.5, -0.5, 2.);
+    m.mult_mut(&s);
+    m.m_3_1 = -4.;
+
+    let t = m.decompose().unwrap();
+    assert!((t.scale.x + 1.5).abs() < 1e-5);
+    assert!((t.shear.x - 0.3).abs() < 1e-5);
+    assert!((t.shear.z + 0.7).abs() < 1e-5);
+    assert_transform_round_trip(&m);
+
+    let mut singular = Matrix4::new();
+    singular.scale_mut(1., 0., 1.);
+    assert!(singular.decompose().is_none());
+}
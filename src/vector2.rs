@@ -0,0 +1,522 @@
+use super::{Vector2, Matrix2, Matrix3, Error};
+
+impl Vector2 {
+
+    /// Creates a new Vector2 with all dimensions set to zero.
+    pub fn new() -> Vector2 {
+        Vector2 {x: 0.0, y: 0.0}
+    }
+
+    /// Creates a new Vector2 from a given Vector2 by copying
+    /// the dimension values.
+    pub fn from_vector2(vec: &Vector2) -> Vector2 {
+        Vector2 {x: vec.x, y: vec.y}
+    }
+
+    /// Creates a new Vector2 from two `f32`'s.
+    pub fn from(x:f32, y:f32) -> Vector2 {
+        Vector2 {x, y}
+    }
+
+    /// Creates a new Vector2 from two `i32`'s.
+    pub fn from_i32(x:i32, y:i32) -> Vector2 {
+        Vector2 {x: x as f32, y: y as f32}
+    }
+
+    /// In-place normalization of this `Vector2`. It divides all dimensions by the length of
+    /// this Vector2 such that this vector ends up with a length of 1.
+    pub fn normalize_mut(&mut self) -> &mut Vector2 {
+        let d = self.length();
+        self.x /= d;
+        self.y /= d;
+        self
+    }
+
+    /// Returns a normalized copy of this vector.
+    pub fn normalize(&self) -> Vector2 {
+        let d = self.length();
+        Vector2 {
+            x: self.x / d,
+            y: self.y / d,
+        }
+    }
+
+    /// Normalizes this vector like `normalize`, but returns `Error::ZeroLength` instead of NaN
+    /// components if the length of this vector is (close to) zero.
+    pub fn try_normalize(&self) -> Result<Vector2, Error> {
+        let mut v = *self;
+        v.try_normalize_mut()?;
+        Ok(v)
+    }
+
+    /// In-place variant of `try_normalize`. This vector is left untouched on error.
+    pub fn try_normalize_mut(&mut self) -> Result<&mut Vector2, Error> {
+        let d = self.length();
+        if !(d >= f32::EPSILON && d.is_finite()) {
+            return Err(Error::ZeroLength);
+        }
+        self.x /= d;
+        self.y /= d;
+        Ok(self)
+    }
+
+    /// Set the individual dimensions of this Vector2.
+    pub fn set(&mut self, xp:f32, yp:f32) -> &mut Vector2 {
+        self.x = xp;
+        self.y = yp;
+
+        self
+    }
+
+    /// Set the individual dimensions of this Vector2 copied
+    /// from the provided Vector2.
+    pub fn set_vector2(&mut self, vec:&Vector2) -> &mut Vector2 {
+        self.x = vec.x;
+        self.y = vec.y;
+
+        self
+    }
+
+    /// In-place subtraction of provided values from each dimension of this `Vector2`.
+    pub fn sub_mut(&mut self, xp:f32, yp:f32) -> &mut Vector2 {
+        self.x -= xp;
+        self.y -= yp;
+
+        self
+    }
+
+    /// Subtracts given parameters from this Vector2 and returns result as new Vector2.
+    pub fn sub(&self, xp:f32, yp:f32) -> Vector2 {
+        Vector2 {
+            x: self.x - xp,
+            y: self.y - yp,
+        }
+    }
+
+    /// In-place subtraction of a provided `Vector2` from this `Vector2`.
+    pub fn sub_mut_vector2(&mut self, vec:&Vector2) -> &mut Vector2 {
+        self.x -= vec.x;
+        self.y -= vec.y;
+
+        self
+    }
+
+    /// In-place addition on both dimensions via two provided values.
+    pub fn add_mut(&mut self, xp:f32, yp:f32) -> &mut Vector2 {
+        self.x += xp;
+        self.y += yp;
+
+        self
+    }
+
+    /// In-place addition of a provided `Vector2` to this `Vector2`.
+    pub fn add_mut_vector2(&mut self, vec:&Vector2) -> &mut Vector2 {
+        self.x += vec.x;
+        self.y += vec.y;
+
+        self
+    }
+
+    /// In-place multiplication of both dimensions with `s`.
+    pub fn scale_mut(&mut self, s:f32) -> &mut Vector2 {
+        self.x *= s;
+        self.y *= s;
+
+        self
+    }
+
+    /// Computes the dot product of this and the provided vector.
+    pub fn dot(&self, vec:&Vector2) -> f32 {
+        self.x * vec.x + self.y * vec.y
+    }
+
+    /// Computes the 2D cross product (also called perp dot product), i.e. the z component of the
+    /// 3D cross product of both vectors. It is positive if `vec` is counter-clockwise of this vector.
+    pub fn cross(&self, vec:&Vector2) -> f32 {
+        self.x * vec.y - self.y * vec.x
+    }
+
+    /// In-place rotation by 90 degrees counter-clockwise.
+    pub fn perpendicular_mut(&mut self) -> &mut Vector2 {
+        let x = self.x;
+        self.x = -self.y;
+        self.y = x;
+
+        self
+    }
+
+    /// Returns a copy of this vector rotated by 90 degrees counter-clockwise.
+    pub fn perpendicular(&self) -> Vector2 {
+        Vector2 {x: -self.y, y: self.x}
+    }
+
+    /// Returns the length of this vector.
+    pub fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    /// Returns the squared length of this vector, which is cheaper than `length`.
+    pub fn length_squared(&self) -> f32 {
+        self.x * self.x + self.y * self.y
+    }
+
+    /// Returns the distance between this and the provided point.
+    pub fn distance(&self, vec:&Vector2) -> f32 {
+        (self - vec).length()
+    }
+
+    /// Returns the components as array reference without copying. This is possible since the
+    /// memory layout of `Vector2` is guaranteed to be `[f32; 2]`.
+    pub fn as_array(&self) -> &[f32; 2] {
+        // SAFETY: `Vector2` is `#[repr(C)]` and consists of 2 `f32`'s without padding, so it
+        // has the size and alignment of `[f32; 2]`; the result borrows `self`.
+        unsafe { &*(self as *const Vector2 as *const [f32; 2]) }
+    }
+
+    /// Returns the components as mutable array reference without copying.
+    pub fn as_mut_array(&mut self) -> &mut [f32; 2] {
+        // SAFETY: same layout argument as in `as_array`, the exclusive borrow of `self` is
+        // handed over to the returned array.
+        unsafe { &mut *(self as *mut Vector2 as *mut [f32; 2]) }
+    }
+
+    /// Returns the components as slice without copying.
+    pub fn as_slice(&self) -> &[f32] {
+        self.as_array()
+    }
+
+    /// Returns a pointer to the first component (`x`), e.g. for `glUniform*` calls.
+    /// The pointer is valid for 2 `f32`'s as long as this `Vector2` lives.
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Vector2 as *const f32
+    }
+
+    /// Reinterprets a slice of `Vector2`'s as one flat slice of `f32`'s without copying, e.g. for
+    /// uploading texture coordinates. The result has `2 * values.len()` elements, starting with
+    /// `x` of the first `Vector2` and ending with `y` of the last one.
+    pub fn slice_as_f32(values: &[Vector2]) -> &[f32] {
+        // SAFETY: every element is 2 consecutive `f32`'s without padding (`#[repr(C)]`) and
+        // slice elements are contiguous, so the memory is exactly `2 * len` initialized `f32`'s
+        // borrowed for the lifetime of `values`.
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const f32, values.len() * 2) }
+    }
+}
+
+impl Default for Vector2 {
+    /// Same as `Vector2::new()`.
+    fn default() -> Vector2 {
+        Vector2::new()
+    }
+}
+
+impl std::ops::Add for Vector2 {
+    type Output = Vector2;
+
+    /// Overrides '+' operator to add one vector to another.
+    ///
+    /// # Examples
+    /// ```
+    /// use js_linalg::Vector2;
+    /// let a = Vector2::from_i32(1, 2);
+    /// let b = Vector2::from_i32(4, 4);
+    /// let c = a + b;
+    /// assert_eq!(c.x, 5.);
+    /// assert_eq!(c.y, 6.);
+    /// ```
+    fn add(self, rhs: Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y
+        }
+    }
+}
+
+impl std::ops::Add<&Vector2> for Vector2 {
+    type Output = Vector2;
+
+    fn add(self, rhs: &Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y
+        }
+    }
+}
+
+impl std::ops::Add<&Vector2> for &Vector2 {
+    type Output = Vector2;
+
+    fn add(self, rhs: &Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y
+        }
+    }
+}
+
+impl std::ops::Add<Vector2> for &Vector2 {
+    type Output = Vector2;
+
+    fn add(self, rhs: Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn check_vector2_add_completeness() {
+    let a = Vector2::from(0., 2.);
+    let b = Vector2::from(1., 3.);
+
+    let _c  = a + b;
+    let _c  = &a + b;
+    let _c  = a + &b;
+    let _c  = &a + &b;
+}
+
+impl std::ops::AddAssign<&Vector2> for Vector2 {
+    /// Implements '+=' operator for Vector2
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Vector2;
+    /// let mut a =  Vector2::from_i32(1, 2);
+    /// let     b = &Vector2::from_i32(1, 2);
+    /// a += b;
+    /// assert_eq!(a.x, 2.);
+    /// assert_eq!(a.y, 4.);
+    /// ```
+    fn add_assign(&mut self, rhs: &Vector2) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl std::ops::AddAssign<Vector2> for Vector2 {
+
+    fn add_assign(&mut self, rhs: Vector2) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl std::ops::Sub for Vector2 {
+    type Output = Vector2;
+
+    fn sub(self, rhs: Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y
+        }
+    }
+}
+
+impl std::ops::Sub<Vector2> for &Vector2 {
+    type Output = Vector2;
+
+    fn sub(self, rhs: Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y
+        }
+    }
+}
+
+impl std::ops::Sub<&Vector2> for &Vector2 {
+    type Output = Vector2;
+
+    fn sub(self, rhs: &Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y
+        }
+    }
+}
+
+impl std::ops::Sub<&Vector2> for Vector2 {
+    type Output = Vector2;
+
+    fn sub(self, rhs: &Vector2) -> Vector2 {
+        Vector2 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn check_vector2_sub_completeness() {
+    let a = Vector2::from(0., 2.);
+    let b = Vector2::from(1., 3.);
+
+    let _c  = a - b;
+    let _c  = &a - b;
+    let _c  = a - &b;
+    let _c  = &a - &b;
+}
+
+impl std::ops::SubAssign<&Vector2> for Vector2 {
+    /// Implements '-=' operator for Vector2
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Vector2;
+    /// let mut a = Vector2::from_i32(1, 2);
+    /// let     b = Vector2::from_i32(1, 1);
+    /// a -= &b;
+    /// assert_eq!(a.x, 0.);
+    /// assert_eq!(a.y, 1.);
+    /// ```
+    fn sub_assign(&mut self, rhs: &Vector2) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl std::ops::SubAssign<Vector2> for Vector2 {
+
+    fn sub_assign(&mut self, rhs: Vector2) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl std::ops::Mul<f32> for Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: f32) -> Vector2 {
+        Vector2 {
+            x: self.x * rhs,
+            y: self.y * rhs
+        }
+    }
+}
+
+impl std::ops::Mul<f32> for &Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: f32) -> Vector2 {
+        Vector2 {
+            x: self.x * rhs,
+            y: self.y * rhs
+        }
+    }
+}
+
+impl std::ops::Mul<Vector2> for f32 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: Vector2) -> Vector2 {
+        Vector2 {
+            x: self * rhs.x,
+            y: self * rhs.y
+        }
+    }
+}
+
+impl std::ops::Mul<&Vector2> for f32 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: &Vector2) -> Vector2 {
+        Vector2 {
+            x: self * rhs.x,
+            y: self * rhs.y
+        }
+    }
+}
+
+impl std::ops::MulAssign<f32> for Vector2 {
+
+    fn mul_assign(&mut self, rhs: f32) {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn check_vector2_mul_completeness() {
+    let a = Vector2::from(0., 2.);
+    let b = 1.5;
+
+    let c  = a * b;
+    assert_eq!(c.y, 3.);
+    let _c  = &a * b;
+
+    let _c  = b * a;
+    let _c  = b * &a;
+}
+
+impl std::ops::MulAssign<&Matrix2> for Vector2 {
+    /// Overrides '*=' operator to multiply a `Matrix2` with a `Vector2` in-place.
+    fn mul_assign(&mut self, m: &Matrix2) {
+        m.mult_to_vector2(self);
+    }
+}
+
+impl std::ops::MulAssign<&Matrix3> for Vector2 {
+    /// Overrides '*=' operator to transform a `Vector2` by a 2D affine `Matrix3` in-place.
+    /// The third dimension is set to 1.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix3, Vector2};
+    /// let mut m = Matrix3::new();
+    /// m.translation_2d_mut(3., 4.);
+    /// let mut b = Vector2::from_i32(1, 1);
+    /// b *= &m;
+    /// assert_eq!(b.x, 4.);
+    /// assert_eq!(b.y, 5.);
+    /// ```
+    fn mul_assign(&mut self, m: &Matrix3) {
+        m.mult_to_vector2(self, 1.0);
+    }
+}
+
+impl std::ops::MulAssign<Matrix2> for Vector2 {
+    /// Overrides '*=' operator to multiply a `Matrix2` with a `Vector2` in-place.
+    fn mul_assign(&mut self, m: Matrix2) {
+        m.mult_to_vector2(self);
+    }
+}
+
+impl std::ops::MulAssign<Matrix3> for Vector2 {
+    /// Overrides '*=' operator to transform a `Vector2` by a 2D affine `Matrix3` in-place.
+    /// The third dimension is set to 1.
+    fn mul_assign(&mut self, m: Matrix3) {
+        m.mult_to_vector2(self, 1.0);
+    }
+}
+
+#[test]
+fn check_vector2_mul_assign_completeness() {
+    let mut m = Matrix3::new();
+    m.translation_2d_mut(1., 0.);
+    let mut a = Vector2::from_i32(1, 2);
+
+    a *= &Matrix2::new();
+    a *= Matrix2::new();
+    a *= &m;
+    a *= m;
+    assert_eq!(a.x, 3.);
+    assert_eq!(a.y, 2.);
+}
+
+#[test]
+fn test_new_vector2() {
+    let m = Vector2::new();
+    assert_eq!(m.x, 0.0);
+    assert_eq!(m.y, 0.0);
+}
+
+#[test]
+fn test_vector2_products() {
+    let a = Vector2::from_i32(3, 4);
+    assert_eq!(a.length(), 5.);
+    assert_eq!(a.dot(&Vector2::from_i32(1, 2)), 11.);
+    assert_eq!(a.cross(&a.perpendicular()), 25.);
+    assert_eq!(Vector2::slice_as_f32(&[a, a]), &[3., 4., 3., 4.]);
+}
@@ -1,4 +1,4 @@
-use super::Vector3;
+use super::{Vector3, Error};
 
 impl Vector3 {
 
@@ -21,7 +21,7 @@ impl Vector3 {
     /// Creates a new Vector3 from three `f32`'s. This is a slightly
     /// more convenient way to create Vector3`s than via `Vector3{x: ..., y:...}`.
     pub fn from(x:f32, y:f32, z:f32) -> Vector3 {
-        Vector3 {x:x, y:y, z:z}
+        Vector3 {x, y, z}
     }
 
     /// Creates a new Vector3 from three `i32`'s. 
@@ -162,16 +162,249 @@ impl Vector3 {
         }
     }
 
+    /// Normalizes this vector like `normalize`, but returns `Error::ZeroLength` instead of NaN
+    /// components if the length of this vector is (close to) zero.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Error, Vector3};
+    /// assert_eq!(Vector3::new().try_normalize().unwrap_err(), Error::ZeroLength);
+    /// assert_eq!(Vector3::from_i32(0, 3, 0).try_normalize().unwrap().y, 1.);
+    /// ```
+    pub fn try_normalize(&self) -> Result<Vector3, Error> {
+        let mut v = *self;
+        v.try_normalize_mut()?;
+        Ok(v)
+    }
+
+    /// In-place variant of `try_normalize`. This vector is left untouched on error.
+    pub fn try_normalize_mut(&mut self) -> Result<&mut Vector3, Error> {
+        let d = self.length();
+        if !(d >= f32::EPSILON && d.is_finite()) {
+            return Err(Error::ZeroLength);
+        }
+        self.x /= d;
+        self.y /= d;
+        self.z /= d;
+        Ok(self)
+    }
+
+    /// Computes the dot product of this vector and the provided one.
+    pub fn dot(&self, a:&Vector3) -> f32 {
+        self.x * a.x + self.y * a.y + self.z * a.z
+    }
+
+    /// Returns the length (euclidean norm) of this vector.
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns the squared length of this vector, which saves the square root when only
+    /// comparing lengths.
+    pub fn length_squared(&self) -> f32 {
+        self.dot(self)
+    }
+
+    /// In-place linear interpolation towards `vec`: `t = 0` keeps this vector, `t = 1`
+    /// results in `vec`.
+    pub fn lerp_mut(&mut self, vec:&Vector3, t:f32) -> &mut Vector3 {
+        self.x += (vec.x - self.x) * t;
+        self.y += (vec.y - self.y) * t;
+        self.z += (vec.z - self.z) * t;
+
+        self
+    }
+
+    /// Linear interpolation between this vector and `vec`, returned as new `Vector3`.
+    ///
+    /// # Examples
+    /// ```
+    /// use js_linalg::Vector3;
+    /// let a = Vector3::from_i32(0, 0, 0);
+    /// let b = Vector3::from_i32(2, 4, 6);
+    /// assert_eq!(a.lerp(&b, 0.5).z, 3.);
+    /// ```
+    pub fn lerp(&self, vec:&Vector3, t:f32) -> Vector3 {
+        let mut v = *self;
+        v.lerp_mut(vec, t);
+        v
+    }
+
+    /// Returns the distance between this point and the provided one.
+    pub fn distance(&self, a:&Vector3) -> f32 {
+        self.distance_squared(a).sqrt()
+    }
+
+    /// Returns the squared distance between this point and the provided one.
+    pub fn distance_squared(&self, a:&Vector3) -> f32 {
+        let dx = self.x - a.x;
+        let dy = self.y - a.y;
+        let dz = self.z - a.z;
+        dx * dx + dy * dy + dz * dz
+    }
+
+    /// Returns the angle between this vector and the provided one in radians, ranging from
+    /// `0` to `PI`. Neither vector needs to be normalized.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Vector3;
+    /// let a = Vector3::from_i32(2, 0, 0);
+    /// let b = Vector3::from_i32(0, 0, 3);
+    /// assert!((a.angle_between(&b) - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    /// ```
+    pub fn angle_between(&self, a:&Vector3) -> f32 {
+        // atan2 stays accurate for nearly (anti-)parallel vectors, unlike acos of the dot product
+        f32::atan2(self.cross(a).length(), self.dot(a))
+    }
+
+    /// In-place projection of this vector onto the provided one, i.e. this vector ends up as
+    /// the component parallel to `onto`. `onto` does not need to be normalized, but a zero
+    /// `onto` yields NaN components; use `try_project` if that can happen.
+    pub fn project_mut(&mut self, onto:&Vector3) -> &mut Vector3 {
+        let s = self.dot(onto) / onto.length_squared();
+
+        self.x = onto.x * s;
+        self.y = onto.y * s;
+        self.z = onto.z * s;
+
+        self
+    }
+
+    /// Projects this vector onto the provided one and returns the result as new `Vector3`.
+    /// See `project_mut`.
+    pub fn project(&self, onto:&Vector3) -> Vector3 {
+        let mut v = *self;
+        v.project_mut(onto);
+        v
+    }
+
+    /// Like `project`, but returns `Error::ZeroLength` instead of NaN components if `onto` has
+    /// zero (or non-finite) length.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Error, Vector3};
+    /// let v = Vector3::from_i32(3, 4, 5);
+    /// assert_eq!(v.try_project(&Vector3::new()).unwrap_err(), Error::ZeroLength);
+    /// assert_eq!(v.try_project(&Vector3::from_i32(0, 2, 0)).unwrap().y, 4.);
+    /// ```
+    pub fn try_project(&self, onto:&Vector3) -> Result<Vector3, Error> {
+        let l = onto.length_squared();
+        if !(l > 0.0 && l.is_finite()) {
+            return Err(Error::ZeroLength);
+        }
+        Ok(self.project(onto))
+    }
+
+    /// In-place reflection of this (incident) vector at a surface with the provided normal,
+    /// like GLSL's `reflect`. `normal` must be normalized.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::Vector3;
+    /// let mut v = Vector3::from_i32(1, -1, 0);
+    /// v.reflect_mut(&Vector3::from_i32(0, 1, 0));
+    /// assert_eq!(v.y, 1.);
+    /// ```
+    pub fn reflect_mut(&mut self, normal:&Vector3) -> &mut Vector3 {
+        let d = 2.0 * self.dot(normal);
+
+        self.x -= d * normal.x;
+        self.y -= d * normal.y;
+        self.z -= d * normal.z;
+
+        self
+    }
+
+    /// Reflects this vector at a surface with the provided normal and returns the result as
+    /// new `Vector3`. See `reflect_mut`.
+    pub fn reflect(&self, normal:&Vector3) -> Vector3 {
+        let mut v = *self;
+        v.reflect_mut(normal);
+        v
+    }
+
+    /// In-place refraction of this (normalized, incident) vector at a surface with the provided
+    /// normalized normal, like GLSL's `refract`. `eta` is the ratio of the refraction indices
+    /// (outside / inside). On total internal reflection, this vector is set to zero.
+    pub fn refract_mut(&mut self, normal:&Vector3, eta:f32) -> &mut Vector3 {
+        let d = self.dot(normal);
+        let k = 1.0 - eta * eta * (1.0 - d * d);
+
+        if k < 0.0 {
+            return self.set(0.0, 0.0, 0.0);
+        }
+
+        let s = eta * d + k.sqrt();
+        self.x = eta * self.x - s * normal.x;
+        self.y = eta * self.y - s * normal.y;
+        self.z = eta * self.z - s * normal.z;
+
+        self
+    }
+
+    /// Refracts this vector at a surface with the provided normal and returns the result as
+    /// new `Vector3`. See `refract_mut`.
+    pub fn refract(&self, normal:&Vector3, eta:f32) -> Vector3 {
+        let mut v = *self;
+        v.refract_mut(normal, eta);
+        v
+    }
+
+    /// Returns the components as array reference without copying. This is possible since the
+    /// memory layout of `Vector3` is guaranteed to be `[f32; 3]`.
+    pub fn as_array(&self) -> &[f32; 3] {
+        // SAFETY: `Vector3` is `#[repr(C)]` and consists of 3 `f32`'s without padding, so it
+        // has the size and alignment of `[f32; 3]`; the result borrows `self`.
+        unsafe { &*(self as *const Vector3 as *const [f32; 3]) }
+    }
+
+    /// Returns the components as mutable array reference without copying.
+    pub fn as_mut_array(&mut self) -> &mut [f32; 3] {
+        // SAFETY: same layout argument as in `as_array`, the exclusive borrow of `self` is
+        // handed over to the returned array.
+        unsafe { &mut *(self as *mut Vector3 as *mut [f32; 3]) }
+    }
+
+    /// Returns the components as slice without copying.
+    pub fn as_slice(&self) -> &[f32] {
+        self.as_array()
+    }
+
+    /// Returns a pointer to the first component (`x`), e.g. for `glUniform*` calls.
+    /// The pointer is valid for 3 `f32`'s as long as this `Vector3` lives.
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Vector3 as *const f32
+    }
+
+    /// Reinterprets a slice of `Vector3`'s as one flat slice of `f32`'s without copying, e.g. for
+    /// uploading a vertex buffer. The result has `3 * values.len()` elements, starting with
+    /// `x` of the first `Vector3` and ending with `z` of the last one.
+    pub fn slice_as_f32(values: &[Vector3]) -> &[f32] {
+        // SAFETY: every element is 3 consecutive `f32`'s without padding (`#[repr(C)]`) and
+        // slice elements are contiguous, so the memory is exactly `3 * len` initialized `f32`'s
+        // borrowed for the lifetime of `values`.
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const f32, values.len() * 3) }
+    }
+
 }
 
 impl std::convert::AsMut<Vector3> for Vector3 {
 
     fn as_mut(&mut self) -> &mut Vector3 {
-        return self;
+        self
     }
 
 }
 
+impl Default for Vector3 {
+    /// Same as `Vector3::new()`.
+    fn default() -> Vector3 {
+        Vector3::new()
+    }
+}
+
 impl std::ops::Add for Vector3 {
     type Output = Vector3;
 
@@ -260,6 +493,7 @@ impl std::ops::Add<Vector3> for &Vector3 {
 }
 
 #[test]
+#[allow(clippy::op_ref)]
 fn check_vector3_add_completeness() {
     let a = Vector3::from(0., 2., 4.);
     let b = Vector3::from(1., 3., 2.);
@@ -293,7 +527,6 @@ impl std::ops::AddAssign<&Vector3> for &mut Vector3 {
     /// assert_eq!(a.y, 4.);
     /// assert_eq!(a.z, 6.);
     /// ```    
-    
     fn add_assign(&mut self, rhs: &Vector3) {
         self.x += rhs.x;
         self.y += rhs.y;
@@ -323,7 +556,6 @@ impl std::ops::AddAssign<&Vector3> for Vector3 {
     /// a += b;
     /// a += &b;
     /// ```
-    
     fn add_assign(&mut self, rhs: &Vector3) {
         self.x += rhs.x;
         self.y += rhs.y;
@@ -400,6 +632,7 @@ impl std::ops::Sub<&Vector3> for Vector3 {
 }
 
 #[test]
+#[allow(clippy::op_ref)]
 fn check_vector3_sub_completeness() {
     let a = Vector3::from(0., 2., 4.);
     let b = Vector3::from(1., 3., 2.);
@@ -423,7 +656,6 @@ impl std::ops::SubAssign<&Vector3> for Vector3 {
     /// assert_eq!(a.y, 1.);
     /// assert_eq!(a.z, 2.);
     /// ```    
-    
     fn sub_assign(&mut self, rhs: &Vector3) {
         self.x -= rhs.x;
         self.y -= rhs.y;
@@ -444,7 +676,6 @@ impl std::ops::SubAssign<Vector3> for Vector3 {
     /// assert_eq!(a.y, 1.);
     /// assert_eq!(a.z, 2.);
     /// ```    
-    
     fn sub_assign(&mut self, rhs: Vector3) {
         self.x -= rhs.x;
         self.y -= rhs.y;
@@ -458,8 +689,8 @@ impl std::ops::Mul<f32> for Vector3 {
     fn mul(self, rhs: f32) -> Vector3 {
         Vector3 {
             x: self.x * rhs,
-            y: self.x * rhs,
-            z: self.x * rhs
+            y: self.y * rhs,
+            z: self.z * rhs
         }
     }
 }
@@ -470,8 +701,8 @@ impl std::ops::Mul<f32> for &Vector3 {
     fn mul(self, rhs: f32) -> Vector3 {
         Vector3 {
             x: self.x * rhs,
-            y: self.x * rhs,
-            z: self.x * rhs
+            y: self.y * rhs,
+            z: self.z * rhs
         }
     }
 }
@@ -505,12 +736,13 @@ impl std::ops::MulAssign<f32> for Vector3 {
 
     fn mul_assign(&mut self, rhs: f32) {
         self.x *= rhs;
-        self.x *= rhs;
-        self.x *= rhs;       
+        self.y *= rhs;
+        self.z *= rhs;
     }
 }
 
 #[test]
+#[allow(clippy::op_ref)]
 fn check_vector3_mul_completeness() {
     let a = Vector3::from(0., 2., 4.);
     let b = 1.4;
@@ -524,6 +756,18 @@ fn check_vector3_mul_completeness() {
 }
 
 
+#[test]
+fn test_vector3_mul_scalar() {
+    // regression: all three components used to be scaled copies of `x`
+    let a = Vector3::from_i32(1, 2, 3);
+    assert_eq!((a * 2.).as_array(), &[2., 4., 6.]);
+    assert_eq!((&a * 2.).as_array(), &[2., 4., 6.]);
+
+    let mut b = a;
+    b *= -1.;
+    assert_eq!(b.as_array(), &[-1., -2., -3.]);
+}
+
 #[test]
 fn zero_defined_correctly() {
     let a = Vector3::new();
@@ -589,4 +833,74 @@ impl std::ops::MulAssign<Matrix4> for Vector3 {
     fn mul_assign(&mut self, m: Matrix4) {
         m.mult_to_vector3(self, 1.0);
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_vector3_memory_layout() {
+    assert_eq!(std::mem::size_of::<Vector3>(), 3 * std::mem::size_of::<f32>());
+
+    let mut a = Vector3::from_i32(1, 2, 3);
+    assert_eq!(a.as_array(), &[1., 2., 3.]);
+    a.as_mut_array()[1] = 5.;
+    assert_eq!(a.y, 5.);
+
+    let vertices = [Vector3::from_i32(1, 2, 3), Vector3::from_i32(4, 5, 6)];
+    assert_eq!(Vector3::slice_as_f32(&vertices), &[1., 2., 3., 4., 5., 6.]);
+}
+
+#[test]
+fn test_vector3_metrics() {
+    let a = Vector3::from_i32(1, 2, 2);
+    let b = Vector3::from_i32(4, 6, 2);
+    assert_eq!(a.dot(&b), 20.);
+    assert_eq!(a.length(), 3.);
+    assert_eq!(a.length_squared(), 9.);
+    assert_eq!(a.distance(&b), 5.);
+    assert_eq!(a.distance_squared(&b), 25.);
+    assert!(a.angle_between(&a).abs() < 1e-6);
+    assert!((a.angle_between(&(a * -1.)) - std::f32::consts::PI).abs() < 1e-6);
+
+    let v = a * 2.;
+    assert_eq!(v.z, 4.);
+    let mut v = a;
+    v *= 3.;
+    assert_eq!(v.y, 6.);
+}
+
+#[test]
+fn test_vector3_project_reflect_refract() {
+    let p = Vector3::from_i32(3, 4, 5).project(&Vector3::from_i32(0, 2, 0));
+    assert_eq!(p.x, 0.);
+    assert_eq!(p.y, 4.);
+    assert_eq!(p.z, 0.);
+    assert_eq!(Vector3::from_i32(3, 4, 5).try_project(&Vector3::new()).unwrap_err(), Error::ZeroLength);
+    assert!(Vector3::from_i32(3, 4, 5).project(&Vector3::new()).x.is_nan());
+
+    let up = Vector3::from_i32(0, 1, 0);
+    let r = Vector3::from_i32(1, -1, 0).reflect(&up);
+    assert_eq!(r.x, 1.);
+    assert_eq!(r.y, 1.);
+
+    let incident = Vector3::from(1., -1., 0.).normalize();
+
+    // same medium, no bending
+    let t = incident.refract(&up, 1.0);
+    assert!((t.x - incident.x).abs() < 1e-6);
+    assert!((t.y - incident.y).abs() < 1e-6);
+
+    // into a denser medium the ray bends towards the normal, Snell's law holds
+    let eta = 1.0 / 1.5;
+    let t = incident.refract(&up, eta);
+    assert!((t.length() - 1.).abs() < 1e-6);
+    assert!(t.x.abs() < incident.x.abs());
+    assert!((t.x - eta * incident.x).abs() < 1e-6);
+
+    // total internal reflection
+    let t = incident.refract(&up, 1.5);
+    assert_eq!(t.length(), 0.);
+
+    assert!(Vector3::new().try_normalize().is_err());
+    let mut v = Vector3::from_i32(0, 0, 0);
+    assert_eq!(v.try_normalize_mut().unwrap_err(), Error::ZeroLength);
+    assert_eq!(v.length(), 0.);
+    assert_eq!(Vector3::from_i32(3, 0, 4).try_normalize_mut().unwrap().x, 0.6);
+}
@@ -1,4 +1,4 @@
-use super::Vector4;
+use super::{Vector3, Vector4, Error};
 
 /// A four dimensional vector of `f32`'s.
 /// 
@@ -52,8 +52,47 @@ impl Vector4 {
         Vector4 {x: x as f32, y: y as f32, z: z as f32, w:w as f32}
     }
 
+    /// Creates a new `Vector4` from four `f32`'s.
+    pub fn from(x:f32, y:f32, z:f32, w:f32) -> Vector4 {
+        Vector4 {x, y, z, w}
+    }
+
+    /// Creates a new `Vector4` from a `Vector3` and the fourth dimension `w`. Use `w = 1` for
+    /// points and `w = 0` for directions.
+    ///
+    /// # Examples
+    /// ```
+    /// use js_linalg::{Vector3, Vector4};
+    /// let v = Vector4::from_vector3(&Vector3::from_i32(1, 2, 3), 1.);
+    /// assert_eq!(v.z, 3.);
+    /// assert_eq!(v.w, 1.);
+    /// ```
+    pub fn from_vector3(vec: &Vector3, w:f32) -> Vector4 {
+        Vector4 {x: vec.x, y: vec.y, z: vec.z, w}
+    }
+
+    /// Returns the first three dimensions as `Vector3`, dropping `w`.
+    pub fn to_vector3(&self) -> Vector3 {
+        Vector3 {x: self.x, y: self.y, z: self.z}
+    }
+
+    /// Returns the first three dimensions divided by `w` as `Vector3`, e.g. to get from clip
+    /// space to normalized device coordinates after multiplying with a projection matrix.
+    ///
+    /// # Examples
+    /// ```
+    /// use js_linalg::Vector4;
+    /// let v = Vector4::from_i32(2, 4, 6, 2).perspective_divide();
+    /// assert_eq!(v.x, 1.);
+    /// assert_eq!(v.y, 2.);
+    /// assert_eq!(v.z, 3.);
+    /// ```
+    pub fn perspective_divide(&self) -> Vector3 {
+        Vector3 {x: self.x / self.w, y: self.y / self.w, z: self.z / self.w}
+    }
+
     /// Scales the length of this vector to 1 without changing its direction.
-    pub fn normalize_mut(&mut self) {
+    pub fn normalize_mut(&mut self) -> &mut Vector4 {
 
         let d = ( self.x*self.x + self.y*self.y + self.z*self.z + self.w*self.w) .sqrt();
 
@@ -61,43 +100,529 @@ impl Vector4 {
         self.y /= d;
         self.z /= d;
         self.w /= d;
+
+        self
+    }
+
+    /// Returns a copy of this vector scaled to a length of 1.
+    pub fn normalize(&self) -> Vector4 {
+        let mut v = *self;
+        v.normalize_mut();
+        v
+    }
+
+    /// Normalizes this vector like `normalize`, but returns `Error::ZeroLength` instead of NaN
+    /// components if the length of this vector is (close to) zero.
+    pub fn try_normalize(&self) -> Result<Vector4, Error> {
+        let mut v = *self;
+        v.try_normalize_mut()?;
+        Ok(v)
+    }
+
+    /// In-place variant of `try_normalize`. This vector is left untouched on error.
+    pub fn try_normalize_mut(&mut self) -> Result<&mut Vector4, Error> {
+        let d = self.length();
+        if !(d >= f32::EPSILON && d.is_finite()) {
+            return Err(Error::ZeroLength);
+        }
+        self.x /= d;
+        self.y /= d;
+        self.z /= d;
+        self.w /= d;
+        Ok(self)
     }
 
-    pub fn set(&mut self, xp:f32, yp:f32, zp:f32, wp:f32) {
+    pub fn set(&mut self, xp:f32, yp:f32, zp:f32, wp:f32) -> &mut Vector4 {
         self.x = xp;
         self.y = yp;
         self.z = zp;
         self.w = wp;
+
+        self
+    }
+
+    /// Set the individual dimensions of this Vector4 copied
+    /// from the provided Vector4.
+    pub fn set_vector4(&mut self, vec:&Vector4) -> &mut Vector4 {
+        self.x = vec.x;
+        self.y = vec.y;
+        self.z = vec.z;
+        self.w = vec.w;
+
+        self
     }
 
-    pub fn scale_mut(&mut self, s:f32) {
+    pub fn scale_mut(&mut self, s:f32) -> &mut Vector4 {
         self.x *= s;
         self.y *= s;
         self.z *= s;
         self.w *= s;
+
+        self
     }
 
-    pub fn sub_mut(&mut self, xp:f32, yp:f32, zp:f32, wp:f32) {
+    pub fn sub_mut(&mut self, xp:f32, yp:f32, zp:f32, wp:f32) -> &mut Vector4 {
         self.x -= xp;
         self.y -= yp;
         self.z -= zp;
         self.w -= wp;
+
+        self
     }
 
-    pub fn sub_mut_vector4(&mut self, vec:&Vector4) {
+    pub fn sub_mut_vector4(&mut self, vec:&Vector4) -> &mut Vector4 {
         self.x -= vec.x;
         self.y -= vec.y;
         self.z -= vec.z;
         self.w -= vec.w;
+
+        self
     }
 
-    pub fn add_mut(&mut self, xp:f32, yp:f32, zp:f32, wp:f32) {
+    pub fn add_mut(&mut self, xp:f32, yp:f32, zp:f32, wp:f32) -> &mut Vector4 {
         self.x += xp;
         self.y += yp;
         self.z += zp;
         self.w += wp;
+
+        self
+    }
+
+    /// In-place addition of a provided `Vector4` to this `Vector4`.
+    pub fn add_mut_vector4(&mut self, vec:&Vector4) -> &mut Vector4 {
+        self.x += vec.x;
+        self.y += vec.y;
+        self.z += vec.z;
+        self.w += vec.w;
+
+        self
+    }
+
+    /// Computes the dot product of this and the provided vector.
+    pub fn dot(&self, vec:&Vector4) -> f32 {
+        self.x * vec.x + self.y * vec.y + self.z * vec.z + self.w * vec.w
+    }
+
+    /// Returns the length of this vector.
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns the squared length of this vector, which is cheaper than `length`.
+    pub fn length_squared(&self) -> f32 {
+        self.dot(self)
+    }
+
+    /// In-place linear interpolation towards `vec`: `t = 0` keeps this vector, `t = 1`
+    /// results in `vec`.
+    pub fn lerp_mut(&mut self, vec:&Vector4, t:f32) -> &mut Vector4 {
+        self.x += (vec.x - self.x) * t;
+        self.y += (vec.y - self.y) * t;
+        self.z += (vec.z - self.z) * t;
+        self.w += (vec.w - self.w) * t;
+
+        self
+    }
+
+    /// Linear interpolation between this vector and `vec`, returned as new `Vector4`.
+    ///
+    /// # Examples
+    /// ```
+    /// use js_linalg::Vector4;
+    /// let a = Vector4::from_i32(0, 0, 0, 0);
+    /// let b = Vector4::from_i32(2, 4, 6, 8);
+    /// let c = a.lerp(&b, 0.5);
+    /// assert_eq!(c.w, 4.);
+    /// ```
+    pub fn lerp(&self, vec:&Vector4, t:f32) -> Vector4 {
+        let mut v = *self;
+        v.lerp_mut(vec, t);
+        v
+    }
+
+    /// Returns the components as array reference without copying. This is possible since the
+    /// memory layout of `Vector4` is guaranteed to be `[f32; 4]`.
+    pub fn as_array(&self) -> &[f32; 4] {
+        // SAFETY: `Vector4` is `#[repr(C)]` and consists of 4 `f32`'s without padding, so it
+        // has the size and alignment of `[f32; 4]`; the result borrows `self`.
+        unsafe { &*(self as *const Vector4 as *const [f32; 4]) }
+    }
+
+    /// Returns the components as mutable array reference without copying.
+    pub fn as_mut_array(&mut self) -> &mut [f32; 4] {
+        // SAFETY: same layout argument as in `as_array`, the exclusive borrow of `self` is
+        // handed over to the returned array.
+        unsafe { &mut *(self as *mut Vector4 as *mut [f32; 4]) }
+    }
+
+    /// Returns the components as slice without copying.
+    pub fn as_slice(&self) -> &[f32] {
+        self.as_array()
+    }
+
+    /// Returns a pointer to the first component (`x`), e.g. for `glUniform*` calls.
+    /// The pointer is valid for 4 `f32`'s as long as this `Vector4` lives.
+    pub fn as_ptr(&self) -> *const f32 {
+        self as *const Vector4 as *const f32
+    }
+
+    /// Reinterprets a slice of `Vector4`'s as one flat slice of `f32`'s without copying, e.g. for
+    /// uploading a vertex buffer. The result has `4 * values.len()` elements, starting with
+    /// `x` of the first `Vector4` and ending with `w` of the last one.
+    pub fn slice_as_f32(values: &[Vector4]) -> &[f32] {
+        // SAFETY: every element is 4 consecutive `f32`'s without padding (`#[repr(C)]`) and
+        // slice elements are contiguous, so the memory is exactly `4 * len` initialized `f32`'s
+        // borrowed for the lifetime of `values`.
+        unsafe { std::slice::from_raw_parts(values.as_ptr() as *const f32, values.len() * 4) }
+    }
+
+}
+
+impl Default for Vector4 {
+    /// Same as `Vector4::new()`.
+    fn default() -> Vector4 {
+        Vector4::new()
+    }
+}
+
+impl std::ops::Add<Vector4> for Vector4 {
+    type Output = Vector4;
+
+    /// Overrides '+' operator to add one vector to another.
+    ///
+    /// # Examples
+    /// ```
+    /// use js_linalg::Vector4;
+    /// let a = Vector4::from_i32(1, 2, 3, 4);
+    /// let b = Vector4::from_i32(4, 4, 4, 4);
+    /// let c = a + b;
+    /// assert_eq!(c.w, 4. + 4.);
+    /// ```
+    fn add(self, rhs: Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+            w: self.w + rhs.w
+        }
+    }
+}
+
+impl std::ops::Add<&Vector4> for Vector4 {
+    type Output = Vector4;
+
+    fn add(self, rhs: &Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+            w: self.w + rhs.w
+        }
+    }
+}
+
+impl std::ops::Add<&Vector4> for &Vector4 {
+    type Output = Vector4;
+
+    fn add(self, rhs: &Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+            w: self.w + rhs.w
+        }
+    }
+}
+
+impl std::ops::Add<Vector4> for &Vector4 {
+    type Output = Vector4;
+
+    fn add(self, rhs: Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+            w: self.w + rhs.w
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn check_vector4_add_completeness() {
+    let a = Vector4::from(0., 2., 4., 6.);
+    let b = Vector4::from(1., 3., 2., 1.);
+
+    let _c  = a + b;
+    let _c  = &a + b;
+    let _c  = a + &b;
+    let _c  = &a + &b;
+}
+
+impl std::ops::AddAssign<&Vector4> for Vector4 {
+
+    fn add_assign(&mut self, rhs: &Vector4) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+        self.w += rhs.w;
+    }
+}
+
+impl std::ops::AddAssign<Vector4> for Vector4 {
+
+    fn add_assign(&mut self, rhs: Vector4) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+        self.w += rhs.w;
+    }
+}
+
+impl std::ops::Sub<Vector4> for Vector4 {
+    type Output = Vector4;
+
+    /// Overrides '-' operator to subtract one vector from another.
+    ///
+    /// # Examples
+    /// ```
+    /// use js_linalg::Vector4;
+    /// let a = Vector4::from_i32(1, 2, 3, 4);
+    /// let b = Vector4::from_i32(4, 4, 4, 4);
+    /// let c = a - b;
+    /// assert_eq!(c.w, 4. - 4.);
+    /// ```
+    fn sub(self, rhs: Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+            w: self.w - rhs.w
+        }
+    }
+}
+
+impl std::ops::Sub<&Vector4> for Vector4 {
+    type Output = Vector4;
+
+    fn sub(self, rhs: &Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+            w: self.w - rhs.w
+        }
+    }
+}
+
+impl std::ops::Sub<&Vector4> for &Vector4 {
+    type Output = Vector4;
+
+    fn sub(self, rhs: &Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+            w: self.w - rhs.w
+        }
+    }
+}
+
+impl std::ops::Sub<Vector4> for &Vector4 {
+    type Output = Vector4;
+
+    fn sub(self, rhs: Vector4) -> Vector4 {
+        Vector4 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+            w: self.w - rhs.w
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn check_vector4_sub_completeness() {
+    let a = Vector4::from(0., 2., 4., 6.);
+    let b = Vector4::from(1., 3., 2., 1.);
+
+    let _c  = a - b;
+    let _c  = &a - b;
+    let _c  = a - &b;
+    let _c  = &a - &b;
+}
+
+impl std::ops::SubAssign<&Vector4> for Vector4 {
+
+    fn sub_assign(&mut self, rhs: &Vector4) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+        self.w -= rhs.w;
+    }
+}
+
+impl std::ops::SubAssign<Vector4> for Vector4 {
+
+    fn sub_assign(&mut self, rhs: Vector4) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+        self.w -= rhs.w;
+    }
+}
+
+impl std::ops::Mul<f32> for Vector4 {
+    type Output = Vector4;
+
+    fn mul(self, rhs: f32) -> Vector4 {
+        Vector4 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+            w: self.w * rhs
+        }
+    }
+}
+
+impl std::ops::Mul<f32> for &Vector4 {
+    type Output = Vector4;
+
+    fn mul(self, rhs: f32) -> Vector4 {
+        Vector4 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+            w: self.w * rhs
+        }
     }
+}
+
+impl std::ops::MulAssign<f32> for Vector4 {
+
+    fn mul_assign(&mut self, rhs: f32) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+        self.w *= rhs;
+    }
+}
+
+impl std::ops::Mul<Vector4> for f32 {
+    type Output = Vector4;
+
+    fn mul(self, rhs: Vector4) -> Vector4 {
+        Vector4 {
+            x: self * rhs.x,
+            y: self * rhs.y,
+            z: self * rhs.z,
+            w: self * rhs.w
+        }
+    }
+}
+
+impl std::ops::Mul<&Vector4> for f32 {
+    type Output = Vector4;
+
+    fn mul(self, rhs: &Vector4) -> Vector4 {
+        Vector4 {
+            x: self * rhs.x,
+            y: self * rhs.y,
+            z: self * rhs.z,
+            w: self * rhs.w
+        }
+    }
+}
+
+impl std::ops::Div<f32> for Vector4 {
+    type Output = Vector4;
+
+    fn div(self, rhs: f32) -> Vector4 {
+        Vector4 {
+            x: self.x / rhs,
+            y: self.y / rhs,
+            z: self.z / rhs,
+            w: self.w / rhs
+        }
+    }
+}
+
+impl std::ops::Div<f32> for &Vector4 {
+    type Output = Vector4;
+
+    fn div(self, rhs: f32) -> Vector4 {
+        Vector4 {
+            x: self.x / rhs,
+            y: self.y / rhs,
+            z: self.z / rhs,
+            w: self.w / rhs
+        }
+    }
+}
+
+impl std::ops::DivAssign<f32> for Vector4 {
+
+    fn div_assign(&mut self, rhs: f32) {
+        self.x /= rhs;
+        self.y /= rhs;
+        self.z /= rhs;
+        self.w /= rhs;
+    }
+}
+
+impl std::ops::Neg for Vector4 {
+    type Output = Vector4;
+
+    fn neg(self) -> Vector4 {
+        Vector4 {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+            w: -self.w
+        }
+    }
+}
 
+impl std::ops::Neg for &Vector4 {
+    type Output = Vector4;
+
+    fn neg(self) -> Vector4 {
+        Vector4 {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+            w: -self.w
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::op_ref)]
+fn check_vector4_mul_div_neg_completeness() {
+    let a = Vector4::from(0., 2., 4., 6.);
+    let b = 2.;
+
+    let c  = a * b;
+    assert_eq!(c.w, 12.);
+    let _c  = &a * b;
+    let _c  = b * a;
+    let _c  = b * &a;
+
+    let c  = a / b;
+    assert_eq!(c.w, 3.);
+    let _c  = &a / b;
+
+    let c = -a;
+    assert_eq!(c.y, -2.);
+    let _c = -&a;
+
+    let mut d = a;
+    d *= 2.;
+    d /= 4.;
+    d += a;
+    d -= &a;
+    assert_eq!(d.z, 2.);
 }
 
 #[test]
@@ -108,3 +633,24 @@ fn test_new_vector4() {
     assert_eq!(m.z, 0.0);
     assert_eq!(m.w, 0.0);
 }
+
+#[test]
+fn test_vector4_memory_layout() {
+    assert_eq!(std::mem::size_of::<Vector4>(), 4 * std::mem::size_of::<f32>());
+
+    let a = Vector4::from_i32(1, 2, 3, 4);
+    assert_eq!(a.as_array(), &[1., 2., 3., 4.]);
+
+    let values = [a, Vector4::from_i32(5, 6, 7, 8)];
+    assert_eq!(Vector4::slice_as_f32(&values), &[1., 2., 3., 4., 5., 6., 7., 8.]);
+}
+
+#[test]
+fn test_vector4_vector3_conversion() {
+    let v = Vector3::from_i32(1, 2, 3);
+    let a = Vector4::from_vector3(&v, 0.5);
+    assert_eq!(a.to_vector3().z, 3.);
+    assert_eq!(a.perspective_divide().z, 6.);
+    assert_eq!(Vector4::from_i32(0, 3, 0, 4).length(), 5.);
+    assert_eq!(Vector4::from_i32(0, 3, 0, 4).normalize().w, 0.8);
+}
@@ -0,0 +1,142 @@
+use super::{Viewport, DepthRange, Matrix4, Vector3, Vector4};
+
+impl Viewport {
+
+    /// Creates a new `Viewport` from the lower-left corner and its size. The depth
+    /// range is set to the OpenGL default `-1..1`.
+    pub fn new(x:f32, y:f32, width:f32, height:f32) -> Viewport {
+        Viewport {x, y, width, height, depth_range: DepthRange::NegativeOneToOne}
+    }
+
+    /// Creates a new `Viewport` at the origin with the provided size in pixels. The
+    /// depth range is set to the OpenGL default `-1..1`.
+    pub fn from_size(width:f32, height:f32) -> Viewport {
+        Viewport {x: 0.0, y: 0.0, width, height, depth_range: DepthRange::NegativeOneToOne}
+    }
+
+    /// Maps a point in world space to window coordinates, just like `gluProject`. The
+    /// resulting `x` and `y` are in pixels with the origin in the lower-left corner, `z`
+    /// is the depth in `0..1`. Returns `None` if the point lies in the plane of the camera.
+    ///
+    /// # Example
+    /// ```
+    /// use js_linalg::{Matrix4, Vector3, Viewport};
+    /// let mut projection = Matrix4::new();
+    /// projection.projection_mut(45., 800., 600., 0.1, 100.);
+    /// let view = Matrix4::look_at(&Vector3::from_i32(0, -5, 0), &Vector3::new(), &Vector3::new_z_up());
+    ///
+    /// let viewport = Viewport::from_size(800., 600.);
+    /// let window = viewport.project(&Vector3::new(), &projection, &view).unwrap();
+    /// assert!((window.x - 400.).abs() < 1e-3);
+    /// assert!((window.y - 300.).abs() < 1e-3);
+    /// ```
+    pub fn project(&self, world:&Vector3, projection:&Matrix4, view:&Matrix4) -> Option<Vector3> {
+        let mut clip = Vector4 {x: world.x, y: world.y, z: world.z, w: 1.0};
+        view.mult_to_vector4(&mut clip);
+        projection.mult_to_vector4(&mut clip);
+
+        if clip.w == 0.0 {
+            return None;
+        }
+
+        let ndc_x = clip.x / clip.w;
+        let ndc_y = clip.y / clip.w;
+        let ndc_z = clip.z / clip.w;
+
+        let z = match self.depth_range {
+            DepthRange::NegativeOneToOne => (ndc_z + 1.0) * 0.5,
+            DepthRange::ZeroToOne        => ndc_z,
+        };
+
+        Some(Vector3 {
+            x: self.x + (ndc_x + 1.0) * 0.5 * self.width,
+            y: self.y + (ndc_y + 1.0) * 0.5 * self.height,
+            z,
+        })
+    }
+
+    /// Maps window coordinates back to world space, just like `gluUnProject`. The reverse
+    /// of `project`. Returns `None` if `projection * view` cannot be inverted.
+    pub fn unproject(&self, window:&Vector3, projection:&Matrix4, view:&Matrix4) -> Option<Vector3> {
+        let inverse = (projection * view).try_inverse().ok()?;
+        self.unproject_with_inverse(window, &inverse)
+    }
+
+    /// Same as `unproject` but takes the already inverted `projection * view` matrix,
+    /// which saves the inversion when unprojecting several points at once. Returns `None`
+    /// if the window coordinates do not map to a finite point in front of the camera,
+    /// e.g. depth 1 with an infinite projection.
+    pub fn unproject_with_inverse(&self, window:&Vector3, inverse_projection_view:&Matrix4) -> Option<Vector3> {
+        let z = match self.depth_range {
+            DepthRange::NegativeOneToOne => window.z * 2.0 - 1.0,
+            DepthRange::ZeroToOne        => window.z,
+        };
+
+        let mut v = Vector4 {
+            x: (window.x - self.x) / self.width * 2.0 - 1.0,
+            y: (window.y - self.y) / self.height * 2.0 - 1.0,
+            z,
+            w: 1.0,
+        };
+        inverse_projection_view.mult_to_vector4(&mut v);
+
+        // visible points have a positive clip space w, so does their unprojection
+        if v.w <= 0.0 {
+            return None;
+        }
+
+        Some(Vector3 {x: v.x / v.w, y: v.y / v.w, z: v.z / v.w})
+    }
+}
+
+#[test]
+fn test_project_unproject_round_trip() {
+    let mut projection = Matrix4::new();
+    projection.projection_mut(60., 640., 480., 0.5, 50.);
+    let view = Matrix4::look_at(&Vector3::from_i32(4, 3, 5), &Vector3::new(), &Vector3::new_z_up());
+    let viewport = Viewport::new(10., 20., 640., 480.);
+
+    let world = Vector3::from(0.5, -1., 0.25);
+    let window = viewport.project(&world, &projection, &view).unwrap();
+    assert!(window.z > 0.0 && window.z < 1.0);
+
+    let back = viewport.unproject(&window, &projection, &view).unwrap();
+    assert!((back.x - world.x).abs() < 1e-3);
+    assert!((back.y - world.y).abs() < 1e-3);
+    assert!((back.z - world.z).abs() < 1e-3);
+}
+
+#[test]
+fn test_project_unproject_reversed_z() {
+    let mut projection = Matrix4::new();
+    projection.projection_reversed_z_mut(60., 640., 480., 0.5);
+    let view = Matrix4::look_at(&Vector3::from_i32(4, 3, 5), &Vector3::new(), &Vector3::new_z_up());
+    let mut viewport = Viewport::from_size(640., 480.);
+    viewport.depth_range = DepthRange::ZeroToOne;
+
+    let world = Vector3::from(0.5, -1., 0.25);
+    let window = viewport.project(&world, &projection, &view).unwrap();
+    let back = viewport.unproject(&window, &projection, &view).unwrap();
+    assert!((back.x - world.x).abs() < 1e-3);
+    assert!((back.y - world.y).abs() < 1e-3);
+    assert!((back.z - world.z).abs() < 1e-3);
+}
+
+#[test]
+fn test_project_unproject_scaled_view() {
+    // a scene modelled in millimetres: the combined matrix has a tiny determinant but is regular
+    let mut projection = Matrix4::new();
+    projection.projection_mut(60., 640., 480., 0.5, 50.);
+    let mut view = Matrix4::look_at(&Vector3::from_i32(4, 3, 5), &Vector3::new(), &Vector3::new_z_up());
+    let mut millimetres = Matrix4::new();
+    millimetres.scale_mut(0.001, 0.001, 0.001);
+    view.mult_mut(&millimetres);
+    let viewport = Viewport::new(10., 20., 640., 480.);
+
+    let world = Vector3::from(500., -1000., 250.);
+    let window = viewport.project(&world, &projection, &view).unwrap();
+    let back = viewport.unproject(&window, &projection, &view).unwrap();
+    assert!((back.x - world.x).abs() < 1.);
+    assert!((back.y - world.y).abs() < 1.);
+    assert!((back.z - world.z).abs() < 1.);
+}